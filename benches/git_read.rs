@@ -0,0 +1,215 @@
+//! Times `scud state` and `scud branch` on a generated git repository large
+//! enough for the cost of reading it to show (thousands of files, hundreds
+//! of branches). Compare the git CLI against gitoxide by running it with and
+//! without the gix feature:
+//!
+//! ```sh
+//! cargo bench --bench git_read
+//! cargo bench --bench git_read --features gix
+//! ```
+//!
+//! The size of the repository can be changed with SCUD_BENCH_FILES and
+//! SCUD_BENCH_BRANCHES.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    time::{Duration, Instant},
+};
+
+const ITERATIONS: usize = 10;
+
+/// A throwaway git repository with a lot of files, branches and changes.
+struct GeneratedRepo {
+    dir: PathBuf,
+}
+
+impl GeneratedRepo {
+    fn new(files: usize, branches: usize) -> Self {
+        let dir = std::env::temp_dir()
+            .join(format!("scud-bench-git-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        let repo = GeneratedRepo { dir };
+        repo.git(&["init", "--quiet", "--initial-branch", "main"]);
+
+        // Spread the files over directories of a hundred files each
+        for i in 0..files {
+            repo.write(&repo.file(i), &format!("line {}\n", i));
+        }
+        repo.git(&["add", "--all"]);
+        repo.git(&["commit", "--quiet", "-m", "initial"]);
+        repo.write(&repo.file(0), "changed\n");
+        repo.git(&["commit", "--quiet", "--all", "-m", "second"]);
+
+        // A remote-tracking branch one commit behind, so main is ahead of it
+        let base = repo.git(&["rev-parse", "HEAD~1"]);
+        let mut refs = format!("create refs/remotes/origin/main {}\n", base);
+        for i in 0..branches {
+            refs.push_str(&format!("create refs/heads/branch-{} {}\n", i, base));
+        }
+        repo.git_with_stdin(&["update-ref", "--stdin"], &refs);
+        repo.git(&["config", "remote.origin.url", "."]);
+        repo.git(&[
+            "config",
+            "remote.origin.fetch",
+            "+refs/heads/*:refs/remotes/origin/*",
+        ]);
+        repo.git(&["config", "branch.main.remote", "origin"]);
+        repo.git(&["config", "branch.main.merge", "refs/heads/main"]);
+
+        // A few changes of every kind for scud state to find
+        for i in 1..files.min(50) {
+            repo.write(&repo.file(i), "modified\n");
+        }
+        for i in 50..files.min(60) {
+            fs::remove_file(repo.dir.join(repo.file(i))).unwrap();
+        }
+        for i in 60..files.min(80) {
+            repo.write(&repo.file(i), "staged\n");
+            repo.git(&["add", &repo.file(i)]);
+        }
+        for i in 0..50 {
+            repo.write(&format!("untracked/{}.txt", i), "untracked\n");
+        }
+
+        repo
+    }
+
+    fn file(&self, i: usize) -> String {
+        format!("dir-{}/file-{}.txt", i / 100, i)
+    }
+
+    fn write(&self, path: &str, content: &str) {
+        let path = self.dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    /// Runs git in the repository, panicking when it fails.
+    fn git(&self, args: &[&str]) -> String {
+        let output = git(&self.dir).args(args).output().unwrap();
+        assert!(
+            output.status.success(),
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    fn git_with_stdin(&self, args: &[&str], stdin: &str) {
+        use std::io::Write;
+
+        let mut child = git(&self.dir)
+            .args(args)
+            .stdin(Stdio::piped())
+            .spawn()
+            .unwrap();
+        child
+            .stdin
+            .take()
+            .unwrap()
+            .write_all(stdin.as_bytes())
+            .unwrap();
+        assert!(child.wait().unwrap().success(), "git {} failed", args[0]);
+    }
+
+    /// Runs scud in the repository, returning how long it took.
+    fn time_scud(&self, args: &[&str]) -> Duration {
+        let start = Instant::now();
+        let output = Command::new(env!("CARGO_BIN_EXE_scud"))
+            .current_dir(&self.dir)
+            .args(args)
+            .env("NO_COLOR", "1")
+            .env("SCUD_NO_UPDATE_CHECK", "1")
+            .output()
+            .unwrap();
+        let elapsed = start.elapsed();
+
+        assert!(
+            output.status.success(),
+            "scud {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        elapsed
+    }
+}
+
+impl Drop for GeneratedRepo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+/// git with an identity of its own, isolated from the user's configuration.
+fn git(dir: &Path) -> Command {
+    let mut command = Command::new("git");
+    command
+        .current_dir(dir)
+        .env("GIT_CONFIG_GLOBAL", "/dev/null")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("GIT_AUTHOR_NAME", "scud")
+        .env("GIT_AUTHOR_EMAIL", "scud@example.com")
+        .env("GIT_COMMITTER_NAME", "scud")
+        .env("GIT_COMMITTER_EMAIL", "scud@example.com");
+    command
+}
+
+fn env_or(name: &str, default: usize) -> usize {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+fn bench(repo: &GeneratedRepo, args: &[&str]) {
+    // The first run warms up the file system cache
+    repo.time_scud(args);
+
+    let mut timings = (0..ITERATIONS)
+        .map(|_| repo.time_scud(args))
+        .collect::<Vec<_>>();
+    timings.sort();
+
+    let mean = timings.iter().sum::<Duration>() / ITERATIONS as u32;
+    println!(
+        "scud {:<8} min {:>9.2?}  median {:>9.2?}  mean {:>9.2?}",
+        args.join(" "),
+        timings[0],
+        timings[ITERATIONS / 2],
+        mean
+    );
+}
+
+fn main() {
+    let installed = Command::new("git")
+        .arg("--version")
+        .output()
+        .map_or(false, |output| output.status.success());
+    if !installed {
+        eprintln!("skipping git_read: git isn't installed");
+        return;
+    }
+
+    let files = env_or("SCUD_BENCH_FILES", 10_000);
+    let branches = env_or("SCUD_BENCH_BRANCHES", 500);
+    let backend = if cfg!(feature = "gix") {
+        "gitoxide"
+    } else {
+        "the git CLI"
+    };
+
+    println!(
+        "Generating a repository with {} files and {} branches ...",
+        files, branches
+    );
+    let repo = GeneratedRepo::new(files, branches);
+
+    println!("Reading it with {} ({} runs each):", backend, ITERATIONS);
+    bench(&repo, &["state"]);
+    bench(&repo, &["branch"]);
+}
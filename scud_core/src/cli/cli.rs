@@ -3,6 +3,8 @@ use crate::{
     cli::version_control::{Diff, Init, New},
 };
 
+use std::path::PathBuf;
+
 use clap::{Args, Parser, Subcommand};
 
 // TODO add scud stash
@@ -19,10 +21,14 @@ use clap::{Args, Parser, Subcommand};
 // else don't install it and log that package_version wasn't updated
 // need to setup semver
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum VCS {
     Git,
     Mercurial,
     Breezy,
+    Subversion,
+    Jujutsu,
+    Fossil,
     // Bazaar, not actively maintained, Breezy is recommended alternative and is
     // supported instead.
 }
@@ -42,6 +48,74 @@ pub struct Cli {
     /// The subcommand to run.
     #[clap(subcommand)]
     pub command: Commands,
+
+    /// Runs scud as if it was started in the given directory instead of the
+    /// current one (like git -C), e.g. to commit to another repository
+    /// without changing directories. Every VCS command scud spawns runs in
+    /// it too.
+    /// (optional).
+    /// [default: the current directory]
+    #[clap(short = 'C', long, global = true, value_name = "PATH")]
+    pub directory: Option<PathBuf>,
+
+    /// Traces every command that scud runs under the hood (e.g. git status
+    /// -sb) to stderr as it runs, along with its working directory, exit code
+    /// and duration. Pass it twice (-vv) to also show the output of each
+    /// command.
+    /// (optional).
+    /// [default: 0]
+    #[clap(
+        short,
+        long,
+        alias = "trace",
+        global = true,
+        action = clap::ArgAction::Count
+    )]
+    pub verbose: u8,
+
+    /// When true, will only print errors, warnings and the output of the
+    /// command itself, leaving out tips, notes, banners and the "Done in" line.
+    /// (optional).
+    /// [default: false]
+    #[clap(
+        short,
+        long,
+        global = true,
+        value_parser,
+        default_value_t = false,
+        conflicts_with = "verbose"
+    )]
+    pub quiet: bool,
+
+    /// When to color the output. `auto` colors it when printing to a terminal,
+    /// unless NO_COLOR is set.
+    /// (optional).
+    /// [default: auto]
+    #[clap(
+        long,
+        global = true,
+        value_enum,
+        value_name = "WHEN",
+        default_value_t = ColorChoice::Auto
+    )]
+    pub color: ColorChoice,
+
+    /// When true, will not color the output (same as --color never).
+    /// (optional).
+    /// [default: false]
+    #[clap(long, global = true, value_parser, default_value_t = false)]
+    pub no_color: bool,
+}
+
+/// When scud colors its output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ColorChoice {
+    /// Color the output when printing to a terminal and NO_COLOR isn't set.
+    Auto,
+    /// Always color the output (e.g. when piping into a pager).
+    Always,
+    /// Never color the output.
+    Never,
 }
 
 /// The subcommands within scud's CLI surface.
@@ -79,24 +153,25 @@ pub enum Commands {
     /// [alias: su]
     // This command is useful for quickly setting up various
     // dependencies onto your local system
-    // (i.e. git, bazaar, gh, glab, etc.),
+    // (i.e. git, breezy, gh, glab, etc.),
     // so you can focus your time on more important things.
-    // TODO
-    // #[clap(alias = "su")]
-    // Setup(Setup),
+    #[clap(alias = "su")]
+    Setup(Setup),
 
     /// Stages all modified files in the current local repository
     /// ensuring they are ready to be committed. This command can be run from
     /// any deeply nested subdirectory of the current local repository
     /// and is smart enough to stage all modified files in the current
-    /// local repository.
+    /// local repository. Given paths, --interactive or --patch, it will only
+    /// stage some of the changes (git only).
     /// [alias: s]
     // This command is useful for staging all modified files in your local repository
     #[clap(alias = "s")]
     Stage(Stage),
 
     /// Unstages all modified files and directories within
-    /// the current local repository.
+    /// the current local repository. Given paths, --interactive or --patch,
+    /// it will only unstage some of the changes (git only).
     /// [alias: u]
     // This command is useful for reverting changes made to files tracked by your
     // version control system.
@@ -152,6 +227,12 @@ pub enum Commands {
     #[clap(alias = "ps")]
     Push(Push),
 
+    /// Reverts the last stage, unstage, commit or push performed by scud
+    /// in the current local repository.
+    // This command is useful for backing out of an operation without having
+    // to reach for the raw commands of the underlying version control system.
+    Undo(Undo),
+
     /// Pulls all commits from the remote repository.
     /// [alias: pl]
     // This command is useful for pulling your remote commits to the local
@@ -159,6 +240,40 @@ pub enum Commands {
     #[clap(alias = "pl")]
     Pull(Pull),
 
+    /// Squashes all `fixup!` commits into the commits they target
+    /// via a non-interactive autosquash rebase.
+    /// [alias: sf]
+    // This command is useful for cleaning up history after creating
+    // fixup commits with `scud commit --fixup`.
+    #[clap(alias = "sf")]
+    SquashFixups(SquashFixups),
+
+    /// Lists the files with merge conflicts and steps through each conflict,
+    /// staging resolved files and continuing the merge or rebase once done.
+    /// [alias: cf]
+    // This command is useful for resolving the conflicts left behind by a
+    // merge, rebase, cherry-pick or stash pop without leaving scud.
+    #[clap(alias = "cf")]
+    Conflicts(Conflicts),
+
+    /// Lists, installs and uninstalls the hooks of the current repository
+    /// from a hooks directory (tools/hooks by default).
+    /// When passed no subcommand, it will list the hooks.
+    /// [alias: hk]
+    // This command is useful for sharing hooks across a team by keeping them
+    // in the repository itself.
+    #[clap(alias = "hk")]
+    Hooks(Hooks),
+
+    /// Checks the state of, pulls, pushes or switches the branch of every
+    /// repository of a workspace at once, listed in a manifest or found
+    /// under a directory.
+    /// [alias: ws]
+    // This command is useful for teams working across many sibling
+    // repositories, which would otherwise have to be visited one by one.
+    #[clap(alias = "ws")]
+    Workspace(Workspace),
+
     // Powerful one-liner which can be thought of as
     // sequentially running the following commands:
     //
@@ -180,7 +295,38 @@ pub enum Commands {
     // This command is useful for checking to see if your system is setup
     // to work with Scud.
     #[clap(alias = "hc")]
-    Healthcheck,
+    Healthcheck(Healthcheck),
+}
+
+/// Formats in which scud can report results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable output.
+    Text,
+    /// Machine-readable output (e.g. for CI).
+    Json,
+}
+
+/////////////////////////////////////////////////
+// Arguments for the `healthcheck` subcommand. //
+/////////////////////////////////////////////////
+
+/// Checks to see required dependencies (i.e. version control systems,
+/// CLIs for source control providers) are installed.
+/// [alias: hc]
+#[derive(Debug, Args)]
+pub struct Healthcheck {
+    /// The format to output the results in.
+    /// (optional).
+    /// [default: text]
+    #[clap(long, value_enum, required = false, default_value_t = OutputFormat::Text)]
+    pub format: OutputFormat,
+    /// Diagnose the current repository instead of the system (e.g. missing
+    /// upstream, detached HEAD, in-progress rebase, shallow clone).
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub repo: bool,
 }
 
 ///////////////////////////////////////////
@@ -188,23 +334,192 @@ pub enum Commands {
 ///////////////////////////////////////////
 
 /// Streamlines the setup process for various version control systems
-/// and source control providers.
+/// and source control providers by installing them with the package manager
+/// of the system (i.e. apt, dnf, pacman, brew, nix).
+/// When passed no subcommand, it will list the tools scud can install.
 /// [alias: su]
 #[derive(Debug, Args)]
+#[clap(args_conflicts_with_subcommands = true)]
 pub struct Setup {
-    /// The desired VCS provider (currently supported: Git, SVN, CVS, Mercurial,
-    /// Bazaar) The default is GitHub.
-    #[clap(value_parser)]
-    pub vcs: Option<String>,
+    /// The subcommand to run.
+    #[clap(subcommand)]
+    pub command: Option<SetupCommands>,
 
     /// When true, will output the commands that scud runs under the hood
-    /// for each of the supported version control systems.
+    /// with the package manager detected on the system.
     /// (optional).
     /// [default: false]
     #[clap(short, long, value_parser, required = false, default_value_t = false)]
     pub info: bool,
 }
 
+/// The subcommands within scud's setup command surface (i.e. vcs, sc, tool,
+/// identity).
+#[derive(Debug, Subcommand)]
+pub enum SetupCommands {
+    /// Installs a version control system.
+    Vcs(SetupVcs),
+
+    /// Installs the CLI of a source control provider.
+    Sc(SetupSc),
+
+    /// Installs a tool used by other scud commands (e.g. onefetch for
+    /// `scud info codebase`).
+    Tool(SetupTool),
+
+    /// Configures who commits are attributed to (user.name and user.email),
+    /// globally, for the current repository or for the repositories under a
+    /// directory, along with commit signing with GPG or SSH keys.
+    /// When passed no options, it will walk through the setup interactively.
+    /// [alias: id]
+    #[clap(alias = "id")]
+    Identity(SetupIdentity),
+}
+
+/// Version control systems scud can install.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SetupVcsKind {
+    Git,
+    Mercurial,
+    Breezy,
+    #[clap(alias = "svn")]
+    Subversion,
+    #[clap(alias = "jj")]
+    Jujutsu,
+    Fossil,
+}
+
+/// Source control providers whose CLI scud can install.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SourceControlProvider {
+    Github,
+    Gitlab,
+}
+
+/// Other tools scud can install.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SetupToolKind {
+    Onefetch,
+}
+
+/// Installs a version control system.
+#[derive(Debug, Args)]
+pub struct SetupVcs {
+    /// The version control system to install.
+    #[clap(value_enum)]
+    pub vcs: SetupVcsKind,
+
+    /// When true, will not install anything but will show the command
+    /// that would be run.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+/// Installs the CLI of a source control provider.
+#[derive(Debug, Args)]
+pub struct SetupSc {
+    /// The source control provider whose CLI to install.
+    #[clap(value_enum)]
+    pub provider: SourceControlProvider,
+
+    /// When true, will not install anything but will show the command
+    /// that would be run.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+/// Where an identity configured by `scud setup identity` applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum IdentityScope {
+    /// Every repository of the current user (~/.gitconfig).
+    Global,
+    /// The current repository only (.git/config).
+    Repo,
+}
+
+/// How commits are signed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum SigningFormat {
+    /// Sign commits with a GPG key.
+    Gpg,
+    /// Sign commits with an SSH key.
+    Ssh,
+    /// Don't sign commits.
+    None,
+}
+
+/// Configures who commits are attributed to along with commit signing.
+/// [alias: id]
+#[derive(Debug, Args)]
+pub struct SetupIdentity {
+    /// The name commits are attributed to (user.name).
+    /// (optional).
+    #[clap(long, value_parser, required = false)]
+    pub name: Option<String>,
+
+    /// The email commits are attributed to (user.email).
+    /// (optional).
+    #[clap(long, value_parser, required = false)]
+    pub email: Option<String>,
+
+    /// Where the identity applies.
+    /// (optional).
+    /// [default: global]
+    #[clap(long, value_enum, required = false, default_value_t = IdentityScope::Global)]
+    pub scope: IdentityScope,
+
+    /// Only applies the identity to repositories under the given directory
+    /// (e.g. ~/work) via an includeIf section in ~/.gitconfig, overriding
+    /// --scope.
+    /// (optional).
+    #[clap(long, value_parser, required = false)]
+    pub dir: Option<String>,
+
+    /// How commits are signed.
+    /// (optional).
+    #[clap(long, value_enum, required = false)]
+    pub signing: Option<SigningFormat>,
+
+    /// The key commits are signed with (a GPG key id or the path of a public
+    /// SSH key). When omitted, the first local key is used.
+    /// (optional).
+    #[clap(long, value_parser, required = false)]
+    pub signing_key: Option<String>,
+
+    /// When true, will verify commits can be signed by creating a signed
+    /// test commit in a temporary repository.
+    /// (optional).
+    /// [default: false]
+    #[clap(long, value_parser, required = false, default_value_t = false)]
+    pub verify: bool,
+
+    /// When true, will not change the configuration but will show the
+    /// commands that would be run.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+/// Installs a tool used by other scud commands.
+#[derive(Debug, Args)]
+pub struct SetupTool {
+    /// The tool to install.
+    #[clap(value_enum)]
+    pub tool: SetupToolKind,
+
+    /// When true, will not install anything but will show the command
+    /// that would be run.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub dry_run: bool,
+}
+
 //////////////////////////////////////////
 // Arguments for the `state` subcommand. //
 //////////////////////////////////////////
@@ -243,10 +558,32 @@ pub struct Upstream {
 /// ensuring they are ready to be committed. This command can be run from
 /// any deeply nested subdirectory of the current local repository
 /// and is smart enough to stage all modified files in the current
-/// local repository.
+/// local repository. Given paths, --interactive or --patch, it will only
+/// stage some of the changes (git only).
 /// [alias: s]
 #[derive(Debug, Args)]
 pub struct Stage {
+    /// The files, directories or git pathspecs (e.g. '*.rs') to stage,
+    /// relative to the current directory.
+    /// (optional).
+    /// [default: every change in the repository]
+    #[clap(value_name = "PATH")]
+    pub paths: Vec<String>,
+
+    /// When true, will ask which of the unstaged and untracked files (within
+    /// the given paths) to stage.
+    /// (optional).
+    /// [default: false]
+    #[clap(long, value_parser, required = false, default_value_t = false)]
+    pub interactive: bool,
+
+    /// When true, will step through the hunks of the changes and ask which
+    /// of them (or which of their lines) to stage, like git add --patch.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub patch: bool,
+
     /// When true, will not stage files but will show expected output.
     /// (optional).
     /// [default: false]
@@ -266,10 +603,34 @@ pub struct Stage {
 /////////////////////////////////////////////
 
 /// Unstages all modified files and directories in the current
-/// local repository so they are ready to be staged again.
+/// local repository so they are ready to be staged again. Given paths,
+/// --interactive or --patch, it will only unstage some of the changes (git
+/// only).
 /// [alias: u]
 #[derive(Debug, Args)]
 pub struct Unstage {
+    /// The files, directories or git pathspecs (e.g. '*.rs') to unstage,
+    /// relative to the current directory.
+    /// (optional).
+    /// [default: every staged change in the repository]
+    #[clap(value_name = "PATH")]
+    pub paths: Vec<String>,
+
+    /// When true, will ask which of the staged files (within the given paths)
+    /// to unstage.
+    /// (optional).
+    /// [default: false]
+    #[clap(long, value_parser, required = false, default_value_t = false)]
+    pub interactive: bool,
+
+    /// When true, will step through the hunks of the staged changes and ask
+    /// which of them (or which of their lines) to unstage, like git reset
+    /// --patch.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub patch: bool,
+
     /// When true, will not unstage files and directories
     /// but will show expected output.
     /// (optional).
@@ -299,6 +660,41 @@ pub struct Commit {
     #[clap(short, long, value_parser, required = false, default_value_t = false)]
     pub dry_run: bool,
 
+    /// When true, will amend the previous commit instead of creating a new one.
+    /// The commit wizard is pre-filled with the previous commit message.
+    /// (optional).
+    /// [default: false]
+    #[clap(
+        short,
+        long,
+        value_parser,
+        required = false,
+        default_value_t = false,
+        conflicts_with = "fixup"
+    )]
+    pub amend: bool,
+
+    /// When true, will create a `fixup!` commit for one of the recent
+    /// unpushed commits (selected via a fuzzy picker).
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub fixup: bool,
+
+    /// When true, will skip the built-in pre-commit checks (forbidden terms,
+    /// large files, conflict markers, trailing whitespace, secrets) along with
+    /// the pre-commit and commit-msg hooks.
+    /// (optional).
+    /// [default: false]
+    #[clap(
+        short = 'n',
+        long,
+        value_parser,
+        required = false,
+        default_value_t = false
+    )]
+    pub no_verify: bool,
+
     /// When true, will output the commands that scud runs under the hood
     /// for each of the supported version control systems.
     /// (optional).
@@ -335,6 +731,238 @@ pub struct CommitAll {
     pub info: bool,
 }
 
+/////////////////////////////////////////////////
+// Arguments for the `squash-fixups` subcommand. //
+/////////////////////////////////////////////////
+
+/// Squashes all `fixup!` commits into the commits they target
+/// via a non-interactive autosquash rebase.
+/// [alias: sf]
+#[derive(Debug, Args)]
+pub struct SquashFixups {
+    /// When true, will not rebase but will show which fixup commits
+    /// would be squashed.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub dry_run: bool,
+
+    /// When true, will output the commands that scud runs under the hood
+    /// for each of the supported version control systems.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub info: bool,
+}
+
+///////////////////////////////////////////
+// Arguments for the `conflicts` command //
+///////////////////////////////////////////
+
+/// Lists the files with merge conflicts and steps through each conflict,
+/// staging resolved files and continuing the merge or rebase once done.
+/// [alias: cf]
+#[derive(Debug, Args)]
+pub struct Conflicts {
+    /// When true, will only list the conflicted files along with
+    /// the number of conflicts in each of them.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub list: bool,
+
+    /// When true, will output the commands that scud runs under the hood
+    /// for each of the supported version control systems.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub info: bool,
+}
+
+///////////////////////////////////
+// Hooks subcommands             //
+//                               //
+// 1. List hooks                 //
+// 2. Install hooks              //
+// 3. Uninstall hooks            //
+///////////////////////////////////
+
+/// Lists, installs and uninstalls the hooks of the current repository
+/// from a hooks directory (tools/hooks by default).
+/// When passed no subcommand, it will list the hooks.
+/// [alias: hk]
+#[derive(Debug, Args)]
+#[clap(args_conflicts_with_subcommands = true)]
+pub struct Hooks {
+    /// The subcommand to run.
+    #[clap(subcommand)]
+    pub command: Option<HooksCommands>,
+
+    /// When true, will output the commands that scud runs under the hood
+    /// for each of the supported version control systems.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub info: bool,
+}
+
+/// The subcommands within scud's hooks command surface (i.e. list, install,
+/// uninstall).
+#[derive(Debug, Subcommand)]
+pub enum HooksCommands {
+    /// Lists the hook scripts in the hooks directory along with whether
+    /// they're installed into the current repository.
+    /// [alias: ls]
+    #[clap(alias = "ls")]
+    List(HooksList),
+
+    /// Installs the hook scripts from the hooks directory into the
+    /// current repository.
+    /// [alias: in]
+    #[clap(alias = "in")]
+    Install(HooksInstall),
+
+    /// Uninstalls the hooks installed by scud from the current repository.
+    /// [alias: un]
+    #[clap(alias = "un")]
+    Uninstall(HooksUninstall),
+}
+
+/// Lists the hook scripts in the hooks directory along with whether
+/// they're installed into the current repository.
+/// [alias: ls]
+#[derive(Debug, Args)]
+pub struct HooksList {
+    /// The directory containing the hook scripts, relative to the root of
+    /// the repository (falls back to SCUD_HOOKS_DIR). For git, it's kept in
+    /// the scud.hooksDir configuration so scud commit runs the same scripts.
+    /// (optional).
+    /// [default: tools/hooks]
+    #[clap(long, value_parser, required = false)]
+    pub dir: Option<String>,
+}
+
+/// Installs the hook scripts from the hooks directory into the
+/// current repository.
+/// [alias: in]
+#[derive(Debug, Args)]
+pub struct HooksInstall {
+    /// The directory containing the hook scripts, relative to the root of
+    /// the repository (falls back to SCUD_HOOKS_DIR).
+    /// (optional).
+    /// [default: tools/hooks]
+    #[clap(long, value_parser, required = false)]
+    pub dir: Option<String>,
+
+    /// When true, will copy the hook scripts instead of symlinking them.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub copy: bool,
+
+    /// When true, will replace hooks which weren't installed by scud.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub force: bool,
+
+    /// When true, will not install the hooks but will show expected output.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+/// Uninstalls the hooks installed by scud from the current repository.
+/// [alias: un]
+#[derive(Debug, Args)]
+pub struct HooksUninstall {
+    /// When true, will not uninstall the hooks but will show expected output.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub dry_run: bool,
+}
+
+///////////////////////////////////////////////
+// Arguments for the `workspace` subcommand. //
+///////////////////////////////////////////////
+
+/// Checks the state of, pulls, pushes or switches the branch of every
+/// repository of a workspace at once, listed in a manifest or found
+/// under a directory. The repositories are processed in parallel, and one
+/// failing doesn't stop the others.
+/// [alias: ws]
+#[derive(Debug, Args)]
+pub struct Workspace {
+    /// The subcommand to run.
+    #[clap(subcommand)]
+    pub command: WorkspaceCommands,
+
+    /// The manifest listing the repositories of the workspace, one path per
+    /// line relative to the manifest (lines starting with # are comments).
+    /// (optional).
+    /// [default: scud-workspace, when it exists]
+    #[clap(
+        short,
+        long,
+        global = true,
+        value_name = "FILE",
+        conflicts_with = "dir"
+    )]
+    pub manifest: Option<PathBuf>,
+
+    /// The directory to look for repositories in (a few levels deep) instead
+    /// of reading a manifest.
+    /// (optional).
+    /// [default: the current directory]
+    #[clap(short, long, global = true, value_name = "DIR")]
+    pub dir: Option<PathBuf>,
+
+    /// How many repositories to process at the same time.
+    /// (optional).
+    /// [default: the number of CPUs]
+    #[clap(short, long, global = true, value_name = "N")]
+    pub jobs: Option<usize>,
+}
+
+/// The subcommands within scud's workspace command surface (i.e. state,
+/// pull, push, branch).
+#[derive(Debug, Subcommand)]
+pub enum WorkspaceCommands {
+    /// Lists the branch, changes and commits ahead of or behind the upstream
+    /// of every repository.
+    /// [alias: st]
+    #[clap(alias = "st")]
+    State,
+
+    /// Pulls the commits of the remote repository into every repository.
+    /// [alias: pl]
+    #[clap(alias = "pl")]
+    Pull,
+
+    /// Pushes the commits of every repository to its remote repository.
+    /// [alias: ps]
+    #[clap(alias = "ps")]
+    Push,
+
+    /// Lists the branch every repository is on, or switches every
+    /// repository to the given branch.
+    /// [alias: br]
+    #[clap(alias = "br")]
+    Branch(WorkspaceBranch),
+}
+
+/// Lists the branch every repository is on, or switches every repository
+/// to the given branch.
+/// [alias: br]
+#[derive(Debug, Args)]
+pub struct WorkspaceBranch {
+    /// The branch to switch every repository to.
+    /// (optional).
+    pub name: Option<String>,
+}
+
 //////////////////////////////////////
 // Arguments for the `push` command //
 //////////////////////////////////////
@@ -358,6 +986,36 @@ pub struct Push {
     pub info: bool,
 }
 
+//////////////////////////////////////
+// Arguments for the `undo` command //
+//////////////////////////////////////
+
+/// Reverts the last stage, unstage, commit or push performed by scud
+/// in the current local repository.
+#[derive(Debug, Args)]
+pub struct Undo {
+    /// When true, will not revert the last operation but
+    /// will show expected output.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub dry_run: bool,
+
+    /// When true, will also undo a push, rewriting the history
+    /// of the remote repository.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub force: bool,
+
+    /// When true, will output the commands that scud runs under the hood
+    /// for each of the supported version control systems.
+    /// (optional).
+    /// [default: false]
+    #[clap(short, long, value_parser, required = false, default_value_t = false)]
+    pub info: bool,
+}
+
 //////////////////////////////////////
 // Arguments for the `pull` command //
 //////////////////////////////////////
@@ -382,8 +1040,12 @@ pub struct Pull {
 /// [alias: up]
 #[derive(Debug, Args)]
 pub struct Update {
-    /// When true, will update scud but
-    /// will show expected output.
+    /// The version to install instead of the latest (e.g. 0.12.0).
+    /// (optional).
+    #[clap(long, value_parser, required = false)]
+    pub version: Option<String>,
+
+    /// When true, will not update scud but will show the install plan.
     /// (optional).
     /// [default: false]
     #[clap(short, long, value_parser, required = false, default_value_t = false)]
@@ -7,14 +7,21 @@ pub mod branch;
 
 // Declarative high-level operations on the top of the VCS.
 pub mod commit;
+pub mod conflicts;
 pub mod diff;
 pub mod feature;
+pub mod hooks;
 pub mod init;
+pub mod pull;
 pub mod push;
+pub mod squash_fixups;
 pub mod stage;
 pub mod state;
+pub mod undo;
 pub mod unstage;
+pub mod workspace;
 
 // Handles ensuring the system is setup for usage with scud.
 pub mod healthcheck;
+pub mod setup;
 pub mod update;
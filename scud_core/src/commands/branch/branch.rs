@@ -1,17 +1,24 @@
+use std::path::Path;
+
 use colored::Colorize;
 
 use crate::{
     branching::{Branch, BranchCommands},
     cli::cli::VCS,
     commands::branch::executors::{
-        execute_branch_breezy, execute_branch_git, execute_branch_mercurial,
+        execute_branch_breezy, execute_branch_fossil, execute_branch_git,
+        execute_branch_jujutsu, execute_branch_mercurial,
     },
     diagnostics::{log_diagnostic, DiagnosticKind},
-    helpers::detect_vcs,
-    logging::helpers::bright_yellow_backtick,
+    error::ScudResult,
+    logging::helpers::backtick,
+    repository::Repository,
 };
 
-pub fn process_branch_commands(branch_commands: Branch) {
+pub fn process_branch_commands(
+    branch_commands: Branch,
+    working_dir: &Path,
+) -> ScudResult {
     match branch_commands.command {
         Some(branch_command) => match branch_command {
             //       BranchCommands::List(branch_list_options) => {
@@ -28,17 +35,20 @@ pub fn process_branch_commands(branch_commands: Branch) {
             }
         },
         None => {
-            branch_command(branch_commands.info);
+            branch_command(branch_commands.info, working_dir)?;
         }
     }
+
+    Ok(())
 }
 
 /// Lists all branches both local and remote in the current repository.
-pub fn branch_command(info: bool) {
+pub fn branch_command(info: bool, working_dir: &Path) -> ScudResult {
     if info {
         execute_branch_info();
+        Ok(())
     } else {
-        execute_branch();
+        execute_branch(working_dir)
     }
 }
 
@@ -54,47 +64,81 @@ pub fn execute_branch_info() {
         command_name: "branch",
         git_command: &format!(
             "{} {}",
-            "git branch -A",
-            "(along with other commands for more rich output)".bright_yellow()
+            "git branch -a",
+            "(along with git log -1 --pretty=%H <branch> for the latest commit of \
+             each branch)"
+                .bright_yellow()
         ),
         mercurial_command: &format!(
             "{} {}",
-            "hg branches",
-            "(along with other commands for more rich output)".bright_yellow()
+            "hg branches -T \"{branch}\\t{node|short}\\n\"",
+            "(along with hg bookmarks, hg branch and hg log -r . -T \
+             {activebookmark} for the bookmarks and the active ones)"
+                .bright_yellow()
         ),
         breezy_command: &format!(
             "{} {}",
-            "bzr branches",
-            "(along with other commands for more rich output)".bright_yellow()
+            "brz info",
+            "(for the shared repository holding the branch directories, along \
+             with brz revno <branch> for the latest revision of each branch)"
+                .bright_yellow()
         ),
+        other_commands: &[
+            (
+                "Jujutsu",
+                &format!(
+                    "{} {}",
+                    "jj bookmark list",
+                    "(along with jj git remote list for the remotes)"
+                        .bright_yellow()
+                ),
+            ),
+            (
+                "Fossil",
+                &format!(
+                    "{} {}",
+                    "fossil branch list",
+                    "(along with fossil info <branch> for the latest commit of \
+                     each branch and fossil remote for the remote)"
+                        .bright_yellow()
+                ),
+            ),
+        ],
     });
 }
 
-pub fn execute_branch() {
-    let vcs = detect_vcs();
+pub fn execute_branch(working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
 
     match vcs {
-        VCS::Git => execute_branch_git(),
-        VCS::Mercurial => execute_branch_mercurial(),
-        VCS::Breezy => execute_branch_breezy(),
+        VCS::Git => execute_branch_git(working_dir),
+        VCS::Mercurial => execute_branch_mercurial(working_dir)?,
+        VCS::Breezy => execute_branch_breezy(working_dir)?,
+        VCS::Subversion => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud branch (subversion)",
+        }),
+        VCS::Jujutsu => execute_branch_jujutsu(working_dir)?,
+        VCS::Fossil => execute_branch_fossil(working_dir)?,
     }
 
     log_diagnostic(DiagnosticKind::Tip {
         body: &format!(
             "{} {}{}{}{}{} {} {}{}{} {}{}{}",
             "Use".yellow(),
-            bright_yellow_backtick(),
+            backtick(),
             "scud branch create".green().italic(),
-            bright_yellow_backtick(),
+            backtick(),
             " to ".yellow(),
             "create a new local branch".bright_yellow(),
             "or use".yellow(),
-            bright_yellow_backtick(),
+            backtick(),
             "scud move".green().italic(),
-            bright_yellow_backtick(),
+            backtick(),
             "to ".yellow(),
             "move between branches".bright_yellow(),
             " in the current local repository".yellow(),
         ),
     });
+
+    Ok(())
 }
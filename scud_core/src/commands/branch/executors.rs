@@ -1,196 +1,479 @@
-use std::process::Command;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use colored::Colorize;
 
-use crate::diagnostics::{log_diagnostic, DiagnosticKind};
+use crate::{
+    cli::cli::VCS,
+    commands::{
+        hooks::helpers::get_repository_root,
+        state::helpers::get_current_branch_jujutsu,
+    },
+    error::ScudResult,
+    runner::ExternalCommand,
+};
 
-pub fn execute_branch_git() {
+pub fn execute_branch_git(working_dir: &Path) {
     println!(
         "\n{} {}",
         " Git ".black().italic().on_bright_yellow(),
         "repository status".yellow().italic(),
     );
 
-    match Command::new("git").args(&["branch", "-a"]).output() {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout).to_string();
-
+    match get_branches_git(working_dir) {
+        Ok(branches) => {
             // Log to user BRANCH INFORMATION:
             println!("\n{}\n", " BRANCH INFORMATION: ".black().on_cyan());
 
-            // Collect local branches
-            let local_branches = stdout
-                .split("\n")
-                .filter(|line| !line.contains("remotes") && !line.is_empty())
-                .collect::<Vec<&str>>();
-
-            // Log local branches
             println!("  {}\n", " LOCAL ".black().on_magenta());
-            let mut i = 0;
-            let local_branches_len = local_branches.len();
-            for mut branch in local_branches {
-                branch = branch.trim();
-                i += 1;
-
-                // check if on first or last branch
-                if i == 1 {
-                    print!("  {}  ", "/-".magenta());
-                } else if i == local_branches_len {
-                    print!("  {}  ", "\\-".magenta());
-                } else {
-                    print!("  {}  ", "--".magenta());
-                }
-
-                // check if branch is current branch
-                if branch.contains('*') {
-                    print!("{}", "**".red());
-                    let branch_name = branch.split('*').last().unwrap();
-                    branch = branch_name.trim();
-                    print!(" {}", branch.cyan());
-                    println!("{}{}", " <-".green(), " ACTIVE ".red());
-                } else {
-                    println!("{}", branch.cyan());
-                }
-
-                // check if branch is ahead or behind
-                // TODO move this to state
-                //if branch.contains('[') {
-                //    println!(
-                //        "{}{}",
-                //        " <-".black().italic(),
-                //        " AHEAD ".bright_yellow()
-                //    );
-                //} else if branch.contains(']') {
-                //    println!(
-                //        "{}{}",
-                //        " <-".black().italic(),
-                //        " BEHIND ".bright_yellow()
-                //    );
-                //}
-
-                // Get the latest commit hash for the branch
-                let mut latest_commit_hash = String::new();
-                match Command::new("git")
-                    .args(&["log", "-1", "--pretty=%H", branch])
-                    .output()
-                {
-                    Ok(output) => {
-                        latest_commit_hash =
-                            String::from_utf8_lossy(&output.stdout).to_string();
-                    }
-                    Err(error) => {
-                        log_diagnostic(DiagnosticKind::Error {
-                            subject: "git log",
-                            body: &format!("{}", error),
-                        });
-                    }
-                }
-
-                // print the separator
-                if i != local_branches_len {
-                    print!("  {}", "|".blue());
-                } else {
-                    print!("  {}", " ".blue());
-                }
-
-                // print latest commit
-                if latest_commit_hash.is_empty() {
-                    print!("  {}", "No commits".bright_black().italic());
-                } else {
-                    print!(
-                        "  {} {}",
-                        "Latest commit:".bright_yellow().italic(),
-                        latest_commit_hash.white().italic()
-                    );
-                }
+            display_branch_list(&branches.local, &branches.active);
+
+            println!("  {}\n", " REMOTE ".black().on_magenta());
+            display_branch_list(&branches.remote, "");
+        }
+        Err(error) => error.log(),
+    }
+}
+
+/// The branches of a git repository, as (name, latest commit) tuples.
+///
+/// Properties:
+///
+/// * `local`: The local branches.
+/// * `active`: The branch HEAD points at, which is empty when it's detached.
+/// * `remote`: The remote-tracking branches, without the origin/ prefix of
+/// the default remote (e.g. main for origin/main).
+#[derive(Debug, Clone, Default)]
+pub struct GitBranches {
+    pub local: Vec<(String, String)>,
+    pub active: String,
+    pub remote: Vec<(String, String)>,
+}
+
+/// Gets the local and remote-tracking branches of the current git repository
+/// along with their latest commit, reading them with gitoxide when scud is
+/// built with the gix feature and falling back to the git CLI otherwise.
+fn get_branches_git(working_dir: &Path) -> ScudResult<GitBranches> {
+    #[cfg(feature = "gix")]
+    if let Some(branches) = crate::native::get_branches_git(working_dir) {
+        return Ok(branches);
+    }
+
+    let stdout = ExternalCommand::git(["branch", "-a"])
+        .current_dir(working_dir)
+        .run()?
+        .stdout;
+    let mut branches = GitBranches::default();
+
+    for line in stdout.lines().filter(|line| !line.is_empty()) {
+        // e.g. "* main" or "  remotes/origin/main"
+        let branch = line.trim();
+        let branch = match branch.strip_prefix('*') {
+            Some(active) => {
+                branches.active = active.trim().to_string();
+                active.trim()
             }
+            None => branch,
+        };
+
+        // An unborn branch has no commits, so failures are expected
+        let latest_commit = |branch: &str| {
+            ExternalCommand::git(["log", "-1", "--pretty=%H", branch])
+                .current_dir(working_dir)
+                .output()
+                .map(|output| output.trimmed_stdout().to_string())
+                .unwrap_or_else(|error| {
+                    error.log();
+                    String::new()
+                })
+        };
+
+        match branch.strip_prefix("remotes/") {
+            // e.g. "origin/HEAD -> origin/main"
+            Some(remote) => branches.remote.push((
+                remote.trim_start_matches("origin/").to_string(),
+                latest_commit(remote.split(" -> ").next().unwrap_or(remote)),
+            )),
+            None => branches
+                .local
+                .push((branch.to_string(), latest_commit(branch))),
+        }
+    }
+
+    Ok(branches)
+}
+
+/// Lists the named branches and the bookmarks of the current Mercurial
+/// repository along with their latest commit, marking the ones the working
+/// directory is on.
+pub fn execute_branch_mercurial(working_dir: &Path) -> ScudResult {
+    println!(
+        "\n{} {}",
+        " Mercurial ".black().italic().on_bright_yellow(),
+        "repository branches".yellow().italic(),
+    );
+
+    let current_branch = ExternalCommand::hg(["branch"])
+        .current_dir(working_dir)
+        .stdout()
+        .unwrap_or_else(|| "default".to_string());
+    let active_bookmark =
+        ExternalCommand::hg(["log", "-r", ".", "-T", "{activebookmark}"])
+            .current_dir(working_dir)
+            .stdout()
+            .unwrap_or_default();
+
+    let mut branches = get_branches_mercurial("branches", "{branch}", working_dir)?;
+    // A new repository has no commits, so its branch isn't listed yet
+    if !branches.iter().any(|(branch, _)| *branch == current_branch) {
+        branches.insert(0, (current_branch.clone(), String::new()));
+    }
+    let bookmarks = get_branches_mercurial("bookmarks", "{bookmark}", working_dir)?;
+
+    println!("\n{}\n", " BRANCH INFORMATION: ".black().on_cyan());
+
+    println!("  {}\n", " BRANCHES ".black().on_magenta());
+    display_branch_list(&branches, &current_branch);
+
+    println!("  {}\n", " BOOKMARKS ".black().on_magenta());
+    if bookmarks.is_empty() {
+        println!("  {}\n", "No bookmarks".bright_black().italic());
+    } else {
+        display_branch_list(&bookmarks, &active_bookmark);
+    }
+
+    Ok(())
+}
+
+/// Lists the bookmarks of the current Jujutsu repository, which take the
+/// place of branches in jj, along with the commit they point at, marking the
+/// closest one to the working-copy commit. The git remotes of the repository
+/// are listed below them.
+pub fn execute_branch_jujutsu(working_dir: &Path) -> ScudResult {
+    println!(
+        "\n{} {}",
+        " Jujutsu ".black().italic().on_bright_yellow(),
+        "repository bookmarks".yellow().italic(),
+    );
+
+    // Remote bookmarks are listed along with the local ones when they're out
+    // of sync, so they're left out here
+    let bookmarks = ExternalCommand::jj([
+        "bookmark",
+        "list",
+        "-T",
+        "if(remote, \"\", name ++ \"\\t\" ++ \
+         if(normal_target, normal_target.commit_id().short()) ++ \"\\n\")",
+    ])
+    .current_dir(working_dir)
+    .run()?
+    .stdout
+    .lines()
+    .filter_map(|line| line.split_once('\t'))
+    .map(|(name, commit)| (name.to_string(), commit.to_string()))
+    .collect::<Vec<_>>();
+    let current_branch = get_current_branch_jujutsu(working_dir);
+    let active = current_branch.split(' ').next().unwrap_or_default();
+
+    println!("\n{}\n", " BRANCH INFORMATION: ".black().on_cyan());
+
+    println!("  {}\n", " BOOKMARKS ".black().on_magenta());
+    if bookmarks.is_empty() {
+        println!("  {}\n", "No bookmarks".bright_black().italic());
+    } else {
+        display_branch_list(&bookmarks, active);
+    }
+
+    println!("  {}\n", " REMOTE ".black().on_magenta());
+    let remotes = ExternalCommand::jj(["git", "remote", "list"])
+        .current_dir(working_dir)
+        .run()?
+        .stdout;
+    let remotes = remotes
+        .lines()
+        .filter_map(|line| line.split_once(' '))
+        .collect::<Vec<_>>();
+
+    if remotes.is_empty() {
+        println!("  {}\n", "No remotes".bright_black().italic());
+    } else {
+        for (name, url) in remotes {
+            println!(
+                "  {}  {} {}",
+                "--".magenta(),
+                format!("{}:", name).bright_yellow().italic(),
+                url.cyan()
+            );
+        }
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Lists the open branches of the current Fossil repository along with their
+/// latest commit, marking the one the checkout is on. The remote Fossil syncs
+/// with is listed below them.
+pub fn execute_branch_fossil(working_dir: &Path) -> ScudResult {
+    println!(
+        "\n{} {}",
+        " Fossil ".black().italic().on_bright_yellow(),
+        "repository branches".yellow().italic(),
+    );
 
-            // Collect remote branches
-            let remote_branches = stdout
-                .split("\n")
-                .filter(|line| line.contains("remotes"))
-                .collect::<Vec<&str>>();
-
-            // Log remote branches
-            println!("\n  {}\n", " REMOTE ".black().on_magenta());
-            let mut i = 0;
-            let remote_branches_len = remote_branches.len();
-            for mut branch in remote_branches {
-                branch = branch.trim();
-                let branch_raw = branch.replace("remotes/origin/", "");
-                branch = branch_raw.as_str();
-                i += 1;
-
-                // check if on first or last branch
-                if i == 1 {
-                    print!("  {}  ", "/-".magenta());
-                } else if i == remote_branches_len {
-                    print!("  {}  ", "\\-".magenta());
-                } else {
-                    print!("  {}  ", "--".magenta());
-                }
-
-                println!("{}", branch.cyan());
-                // print the separator
-                if i != remote_branches_len {
-                    print!("  {}", "|".blue());
-                }
-
-                // Get the latest commit hash for the branch
-                let mut latest_commit_hash = String::new();
-                match Command::new("git")
-                    .args(&["log", "-1", "--pretty=%H", branch])
-                    .output()
-                {
-                    Ok(output) => {
-                        latest_commit_hash =
-                            String::from_utf8_lossy(&output.stdout).to_string();
-                    }
-                    Err(error) => {
-                        log_diagnostic(DiagnosticKind::Error {
-                            subject: "git log",
-                            body: &format!("{}", error),
-                        });
-                    }
-                }
-
-                // print latest commit
-                if latest_commit_hash.is_empty() {
-                    println!("  {}", "No commits".bright_black().italic());
-                } else {
-                    println!(
-                        "     {} {}",
-                        "Latest commit:".bright_yellow().italic(),
-                        latest_commit_hash.white().italic()
-                    );
-                }
+    // The current branch is marked with a star (e.g. " * trunk")
+    let branch_list = ExternalCommand::fossil(["branch", "list"])
+        .current_dir(working_dir)
+        .run()?
+        .stdout;
+    let mut active = String::new();
+    let mut branches = Vec::new();
+    for line in branch_list.lines() {
+        let branch = match line.trim_start().strip_prefix("* ") {
+            Some(branch) => {
+                active = branch.trim().to_string();
+                &active
             }
+            None => line.trim(),
+        };
+        if !branch.is_empty() {
+            branches.push((
+                branch.to_string(),
+                get_latest_commit_fossil(branch, working_dir),
+            ));
+        }
+    }
+
+    println!("\n{}\n", " BRANCH INFORMATION: ".black().on_cyan());
+
+    println!("  {}\n", " LOCAL ".black().on_magenta());
+    display_branch_list(&branches, &active);
+
+    println!("  {}\n", " REMOTE ".black().on_magenta());
+    // fossil remote prints "off" when there's no remote
+    match ExternalCommand::fossil(["remote"])
+        .current_dir(working_dir)
+        .stdout()
+    {
+        Some(remote) if remote != "off" => {
+            println!("  {}  {}\n", "--".magenta(), remote.cyan());
+        }
+        _ => println!("  {}\n", "No remote".bright_black().italic()),
+    }
+
+    Ok(())
+}
+
+/// Gets the abbreviated hash of the latest commit of the given Fossil branch
+/// from the hash line of `fossil info` (e.g. "hash: 1a2b3c... 2024-01-01").
+fn get_latest_commit_fossil(branch: &str, working_dir: &Path) -> String {
+    ExternalCommand::fossil(["info", branch])
+        .current_dir(working_dir)
+        .stdout()
+        .and_then(|info| {
+            info.lines().find_map(|line| {
+                line.strip_prefix("hash:")
+                    .and_then(|hash| hash.split_whitespace().next())
+                    .map(|hash| hash.chars().take(10).collect())
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Lists the branches or bookmarks of the current Mercurial repository.
+///
+/// Arguments:
+///
+/// * `command`: The hg command listing them (i.e. branches or bookmarks).
+/// * `name_template`: The template of their name (e.g. {branch}).
+/// * `working_dir`: The directory within the repository to run hg in.
+///
+/// Returns:
+///
+/// A vector of (name, latest commit) tuples
+fn get_branches_mercurial(
+    command: &str,
+    name_template: &str,
+    working_dir: &Path,
+) -> ScudResult<Vec<(String, String)>> {
+    let template = format!("{}\\t{{node|short}}\\n", name_template);
+    let output = ExternalCommand::hg([command, "-T", &template])
+        .current_dir(working_dir)
+        .run()?;
+
+    Ok(output
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(name, node)| (name.to_string(), node.to_string()))
+        .collect())
+}
+
+/// Prints the given (name, latest commit) tuples as a tree, marking the
+/// active one.
+fn display_branch_list(branches: &[(String, String)], active: &str) {
+    for (i, (branch, latest_commit_hash)) in branches.iter().enumerate() {
+        let is_last = i + 1 == branches.len();
+
+        // check if on first or last branch
+        if i == 0 {
+            print!("  {}  ", "/-".magenta());
+        } else if is_last {
+            print!("  {}  ", "\\-".magenta());
+        } else {
+            print!("  {}  ", "--".magenta());
+        }
+
+        if branch == active {
+            println!(
+                "{} {}{}{}",
+                "**".red(),
+                branch.cyan(),
+                " <-".green(),
+                " ACTIVE ".red()
+            );
+        } else {
+            println!("{}", branch.cyan());
+        }
+
+        // print the separator
+        print!("  {}", if is_last { " " } else { "|" }.blue());
+
+        // print latest commit
+        if latest_commit_hash.is_empty() {
+            println!("  {}", "No commits".bright_black().italic());
+        } else {
+            println!(
+                "  {} {}",
+                "Latest commit:".bright_yellow().italic(),
+                latest_commit_hash.white().italic()
+            );
         }
-        Err(error) => {
-            println!("{}", error);
+    }
+    println!();
+}
+
+/// Lists the branches of the current Breezy repository along with their
+/// latest revision, marking the one the working tree belongs to.
+///
+/// Breezy keeps each branch in a directory of its own, so the branches are
+/// the directories of the shared repository the branch is in (e.g.
+/// project/trunk and project/feature), or just the branch itself when it's
+/// standalone. The parent, push and submit locations take the place of
+/// git's remote branches.
+pub fn execute_branch_breezy(working_dir: &Path) -> ScudResult {
+    println!(
+        "\n{} {}",
+        " Breezy ".black().italic().on_bright_yellow(),
+        "repository branches".yellow().italic(),
+    );
+
+    let branch_root = get_repository_root(&VCS::Breezy, working_dir);
+    let info = ExternalCommand::brz(["info"])
+        .current_dir(&branch_root)
+        .run()?
+        .stdout;
+
+    let branch_directories =
+        match get_info_location_breezy(&info, "shared repository") {
+            Some(shared_repository) => {
+                find_branches_breezy(&branch_root.join(shared_repository), 0)
+            }
+            None => vec![branch_root.clone()],
+        };
+
+    let branches = branch_directories
+        .iter()
+        .map(|directory| {
+            // A new branch has no revisions yet (revno 0)
+            let revno = ExternalCommand::brz(["revno"])
+                .current_dir(working_dir)
+                .arg(directory)
+                .stdout()
+                .filter(|revno| revno != "0")
+                .map(|revno| format!("r{}", revno))
+                .unwrap_or_default();
+
+            (directory_name(directory), revno)
+        })
+        .collect::<Vec<(String, String)>>();
+
+    println!("\n{}\n", " BRANCH INFORMATION: ".black().on_cyan());
+
+    println!("  {}\n", " LOCAL ".black().on_magenta());
+    display_branch_list(&branches, &directory_name(&branch_root));
+
+    println!("  {}\n", " REMOTE ".black().on_magenta());
+    let related_branches = ["parent branch", "push branch", "submit branch"]
+        .into_iter()
+        .filter_map(|kind| {
+            get_info_location_breezy(&info, kind)
+                .map(|location| (kind.trim_end_matches(" branch"), location))
+        })
+        .collect::<Vec<_>>();
+
+    if related_branches.is_empty() {
+        println!("  {}\n", "No related branches".bright_black().italic());
+    } else {
+        for (kind, location) in related_branches {
+            println!(
+                "  {}  {} {}",
+                "--".magenta(),
+                format!("{}:", kind).bright_yellow().italic(),
+                location.cyan()
+            );
         }
+        println!();
     }
-    // match Command::new("git").arg("reset").output() {
-    //     Ok(_) /* git add -A doesn't emit any output */ => {
-    //         execute_state_git();
-    //     }
-    //     Err(error) => log_diagnostic(
-    //         DiagnosticKind::Error {
-    //             subject: "failed to stage (git)",
-    //             body: &format!("{}", error)
-    //         }
-    //     )
-    // }
+
+    Ok(())
 }
 
-pub fn execute_branch_mercurial() {
-    log_diagnostic(DiagnosticKind::WorkInProgress {
-        feature: "scud branch (mercurial)",
-    });
+fn directory_name(directory: &Path) -> String {
+    directory
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
 }
 
-pub fn execute_branch_breezy() {
-    log_diagnostic(DiagnosticKind::WorkInProgress {
-        feature: "scud branch (breezy)",
-    });
+/// Gets a location listed by `brz info` (e.g. "  parent branch: ../trunk").
+fn get_info_location_breezy(info: &str, name: &str) -> Option<String> {
+    info.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix(':'))
+            .map(|location| location.trim().to_string())
+    })
+}
+
+/// Finds the branches in the given directory of a shared repository, which
+/// are usually right below it (e.g. project/trunk) and sometimes grouped
+/// one level further down (e.g. project/branches/feature).
+fn find_branches_breezy(directory: &Path, depth: usize) -> Vec<PathBuf> {
+    if directory.join(".bzr").join("branch").is_dir() {
+        return vec![directory.to_path_buf()];
+    }
+    if depth > 2 {
+        return vec![];
+    }
+
+    let mut entries = fs::read_dir(directory)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.is_dir()
+                        && path.file_name().map_or(false, |name| name != ".bzr")
+                })
+                .collect::<Vec<PathBuf>>()
+        })
+        .unwrap_or_default();
+    entries.sort();
+
+    entries
+        .iter()
+        .flat_map(|entry| find_branches_breezy(entry, depth + 1))
+        .collect()
 }
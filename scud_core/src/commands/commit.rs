@@ -1,4 +1,6 @@
+pub mod checks;
 pub mod commit;
 pub mod executors;
 pub mod helpers;
 pub mod logging;
+pub mod parser;
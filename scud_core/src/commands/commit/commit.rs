@@ -1,10 +1,16 @@
+use std::path::Path;
+
 use crate::{
     cli::cli::{Commit, VCS},
     commands::commit::executors::{
-        execute_commit_breezy, execute_commit_dry_run, execute_commit_git,
-        execute_commit_info, execute_commit_mercurial,
+        execute_commit_amend_git, execute_commit_breezy, execute_commit_dry_run,
+        execute_commit_fixup_git, execute_commit_fossil, execute_commit_git,
+        execute_commit_info, execute_commit_jujutsu, execute_commit_mercurial,
+        execute_commit_subversion,
     },
-    helpers::detect_vcs,
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::ScudResult,
+    repository::Repository,
 };
 
 /// Defining an enum to represent the supported Commit Message Specifications:
@@ -24,28 +30,96 @@ pub enum CommitMessageFormat {
 /// Arguments:
 ///
 /// * `commit_options`: Struct that contains the options that were passed to the
-///   command via user input (e.g. dry-run, info).
-pub fn commit_command(commit_options: Commit) {
+///   command via user input (e.g. dry-run, info, amend, fixup, no-verify).
+/// * `working_dir`: The directory to operate on, which is the current one
+///   unless given with --directory.
+pub fn commit_command(commit_options: Commit, working_dir: &Path) -> ScudResult {
     if commit_options.dry_run {
-        execute_commit_dry_run();
+        execute_commit_dry_run()
     } else if commit_options.info {
         execute_commit_info();
+        Ok(())
+    } else if commit_options.amend {
+        execute_commit_amend(commit_options.no_verify, working_dir)
+    } else if commit_options.fixup {
+        execute_commit_fixup(commit_options.no_verify, working_dir)
     } else {
-        execute_commit();
+        execute_commit(commit_options.no_verify, working_dir)
     }
 }
 
 /// Detects the version control system, and then calls the appropriate
 /// function to execute the commit
-fn execute_commit() {
-    let vcs = detect_vcs();
+fn execute_commit(no_verify: bool, working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
 
     match vcs {
         // Each executor checks to make sure there are staged files
         // before executing the commit command.
-        // If there are no staged files, it logs a warning and exits.
-        VCS::Git => execute_commit_git(),
-        VCS::Mercurial => execute_commit_mercurial(),
-        VCS::Breezy => execute_commit_breezy(),
+        // If there are no staged files, it fails with an error.
+        VCS::Git => execute_commit_git(no_verify, working_dir)?,
+        VCS::Mercurial => execute_commit_mercurial(working_dir)?,
+        VCS::Breezy => execute_commit_breezy(working_dir)?,
+        VCS::Subversion => execute_commit_subversion(working_dir)?,
+        VCS::Jujutsu => execute_commit_jujutsu(working_dir)?,
+        VCS::Fossil => execute_commit_fossil(working_dir)?,
+    }
+
+    Ok(())
+}
+
+/// Detects the version control system, and then calls the appropriate
+/// function to amend the previous commit
+fn execute_commit_amend(no_verify: bool, working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
+
+    match vcs {
+        VCS::Git => execute_commit_amend_git(no_verify, working_dir)?,
+        VCS::Mercurial => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud commit --amend (mercurial)",
+        }),
+        VCS::Breezy => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud commit --amend (breezy)",
+        }),
+        // Revisions can't be rewritten once committed, as svn commit sends
+        // them straight to the repository
+        VCS::Subversion => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud commit --amend (subversion)",
+        }),
+        VCS::Jujutsu => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud commit --amend (jujutsu)",
+        }),
+        VCS::Fossil => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud commit --amend (fossil)",
+        }),
     }
+
+    Ok(())
+}
+
+/// Detects the version control system, and then calls the appropriate
+/// function to create a fixup commit
+fn execute_commit_fixup(no_verify: bool, working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
+
+    match vcs {
+        VCS::Git => execute_commit_fixup_git(no_verify, working_dir)?,
+        VCS::Mercurial => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud commit --fixup (mercurial)",
+        }),
+        VCS::Breezy => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud commit --fixup (breezy)",
+        }),
+        VCS::Subversion => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud commit --fixup (subversion)",
+        }),
+        VCS::Jujutsu => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud commit --fixup (jujutsu)",
+        }),
+        VCS::Fossil => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud commit --fixup (fossil)",
+        }),
+    }
+
+    Ok(())
 }
@@ -1,8 +1,33 @@
-use std::process::Command;
+use std::path::Path;
 
 use crate::{
-    commands::commit::helpers::{check_for_staged_files, process_commit_message},
+    cli::cli::VCS,
+    commands::{
+        commit::{
+            checks::{log_findings, run_pre_commit_checks_git, PreCommitConfig},
+            helpers::{
+                check_for_staged_files, check_for_staged_files_breezy,
+                check_for_staged_files_fossil, check_for_staged_files_jujutsu,
+                check_for_staged_files_mercurial, check_for_staged_files_subversion,
+                get_fixup_target_git, get_previous_commit_message_git,
+                is_commit_pushed_git, process_commit_message,
+                process_commit_message_with_defaults,
+            },
+            parser::parse_commit_message,
+        },
+        hooks::helpers::{
+            can_skip_verification_git, get_repository_root,
+            run_commit_msg_hooks_git, run_hooks_git,
+        },
+        state::helpers::{get_current_branch_fossil, get_current_branch_subversion},
+        undo::journal::{
+            record_operation_git, snapshot_git, JournalEntry, Operation,
+        },
+    },
     diagnostics::{log_diagnostic, DiagnosticKind},
+    error::{ScudError, ScudResult},
+    logging::helpers::backtick,
+    runner::ExternalCommand,
 };
 
 use colored::Colorize;
@@ -10,9 +35,11 @@ use colored::Colorize;
 /// Takes the commit message from the user
 /// and then logs a diagnostic message
 /// indicating the usage of the dry-run option.
-pub fn execute_commit_dry_run() {
-    process_commit_message();
+pub fn execute_commit_dry_run() -> ScudResult {
+    process_commit_message()?;
     log_diagnostic(DiagnosticKind::DryRun { command: "commit" });
+
+    Ok(())
 }
 
 pub fn execute_commit_info() {
@@ -36,9 +63,38 @@ pub fn execute_commit_info() {
         ),
         breezy_command: &format!(
             "{} {}",
-            "bzr commit -m \"<generated message>\"",
+            "brz commit -m \"<generated message>\"",
             "(generated commit message comes from user flow w/ CLI)".bright_yellow()
         ),
+        other_commands: &[
+            (
+                "Subversion",
+                &format!(
+                    "{} {}",
+                    "svn commit -m \"<generated message>\"",
+                    "(generated commit message comes from user flow w/ CLI)"
+                        .bright_yellow()
+                ),
+            ),
+            (
+                "Jujutsu",
+                &format!(
+                    "{} {}",
+                    "jj describe -m \"<generated message>\" && jj new",
+                    "(generated commit message comes from user flow w/ CLI)"
+                        .bright_yellow()
+                ),
+            ),
+            (
+                "Fossil",
+                &format!(
+                    "{} {}",
+                    "fossil commit -m \"<generated message>\"",
+                    "(generated commit message comes from user flow w/ CLI)"
+                        .bright_yellow()
+                ),
+            ),
+        ],
     });
 }
 
@@ -48,44 +104,326 @@ pub fn execute_commit_info() {
 //////////////////////////////////////////////////////////
 
 /// Checks for staged files,
+/// runs the pre-commit checks and hooks,
 /// processes the commit message,
+/// runs the commit-msg hooks,
 /// and then executes the `git commit` command
 /// with the generated message.
-pub fn execute_commit_git() {
-    check_for_staged_files();
-
-    let commit_message = process_commit_message();
-
-    match Command::new("git")
-        .arg("commit")
-        .arg("-m")
-        .arg(commit_message)
-        .output()
-    {
-        Ok(output) => {
-            let stdout = String::from_utf8_lossy(&output.stdout);
-            let _successful_commit_output =
-                stdout.split("\n").collect::<Vec<&str>>();
-            // If the commit was successful, print the output to the user
-            println!("\n");
-            // println!("{}",
-            // String::from_utf8_lossy(&output.stdout).to_string())
+/// The checks and hooks are skipped when `no_verify` is true.
+pub fn execute_commit_git(no_verify: bool, working_dir: &Path) -> ScudResult {
+    check_for_staged_files(no_verify, working_dir)?;
+
+    if !no_verify {
+        run_hooks_git("pre-commit", &[], working_dir)?;
+    }
+
+    let mut commit_message = process_commit_message()?;
+    if !no_verify {
+        commit_message = run_commit_msg_hooks_git(&commit_message, working_dir)?;
+    }
+
+    // Record HEAD before committing so `scud undo` can un-commit
+    let snapshot = snapshot_git(Operation::Commit, working_dir);
+
+    let mut args = vec!["commit", "-m", &commit_message];
+    args.extend(no_verify_git(
+        no_verify,
+        &["pre-commit", "commit-msg"],
+        working_dir,
+    ));
+
+    run_commit_git(&args, snapshot, working_dir)
+}
+
+/// Checks that there are changes to commit,
+/// processes the commit message,
+/// and then executes the `hg commit` command
+/// with the generated message.
+/// Mercurial runs its own hooks (e.g. precommit) as part of the commit.
+pub fn execute_commit_mercurial(working_dir: &Path) -> ScudResult {
+    check_for_staged_files_mercurial(working_dir)?;
+
+    let commit_message = process_commit_message()?;
+
+    // hg commit doesn't print anything, so the new commit is summarized the
+    // way git does (e.g. [default 1a2b3c4d5e6f] feat: ...)
+    ExternalCommand::hg(["commit", "-m", &commit_message])
+        .current_dir(working_dir)
+        .run()?;
+    let summary = ExternalCommand::hg([
+        "log",
+        "-r",
+        ".",
+        "-T",
+        "[{branch} {node|short}] {desc|firstline}",
+    ])
+    .current_dir(working_dir)
+    .run()?;
+
+    println!("\n{}\n", summary.trimmed_stdout().italic());
+
+    Ok(())
+}
+
+/// Checks that there are changes to commit,
+/// processes the commit message,
+/// and then executes the `brz commit` command
+/// with the generated message.
+/// Breezy runs its own hooks (e.g. pre_commit) as part of the commit.
+pub fn execute_commit_breezy(working_dir: &Path) -> ScudResult {
+    check_for_staged_files_breezy(working_dir)?;
+
+    let commit_message = process_commit_message()?;
+
+    // brz commit reports its progress on stderr, so the new commit is
+    // summarized the way git does (e.g. [trunk r42] feat: ...)
+    ExternalCommand::brz(["commit", "-m", &commit_message])
+        .current_dir(working_dir)
+        .run()?;
+    let nick = ExternalCommand::brz(["nick"])
+        .current_dir(working_dir)
+        .run()?;
+    let revno = ExternalCommand::brz(["revno"])
+        .current_dir(working_dir)
+        .run()?;
+
+    println!(
+        "\n{}\n",
+        format!(
+            "[{} r{}] {}",
+            nick.trimmed_stdout(),
+            revno.trimmed_stdout(),
+            commit_message.lines().next().unwrap_or_default()
+        )
+        .italic()
+    );
+
+    Ok(())
+}
+
+/// Checks that there are changes to commit,
+/// processes the commit message,
+/// and then executes the `svn commit` command
+/// with the generated message from the root of the working copy,
+/// which sends the commit straight to the repository.
+pub fn execute_commit_subversion(working_dir: &Path) -> ScudResult {
+    check_for_staged_files_subversion(working_dir)?;
+
+    let commit_message = process_commit_message()?;
+
+    // svn commit only commits the changes below the directory it's run from
+    let output = ExternalCommand::svn(["commit", "-m", &commit_message])
+        .current_dir(get_repository_root(&VCS::Subversion, working_dir))
+        .run()?;
+
+    // The new commit is summarized the way git does (e.g. [trunk r42] feat:
+    // ...), taking the revision from "Committed revision 42."
+    let revision = output
+        .stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("Committed revision "))
+        .map(|revision| revision.trim_end_matches('.'))
+        .unwrap_or("?");
+
+    println!(
+        "\n{}\n",
+        format!(
+            "[{} r{}] {}",
+            get_current_branch_subversion(working_dir),
+            revision,
+            commit_message.lines().next().unwrap_or_default()
+        )
+        .italic()
+    );
+
+    Ok(())
+}
+
+/// Checks that there are changes to commit,
+/// processes the commit message,
+/// and then executes the `jj describe` command
+/// with the generated message,
+/// followed by `jj new` to start a new working-copy commit on top of it.
+pub fn execute_commit_jujutsu(working_dir: &Path) -> ScudResult {
+    check_for_staged_files_jujutsu(working_dir)?;
+
+    let commit_message = process_commit_message()?;
+
+    ExternalCommand::jj(["describe", "-m", &commit_message])
+        .current_dir(working_dir)
+        .run()?;
+    ExternalCommand::jj(["new"])
+        .current_dir(working_dir)
+        .run()?;
+
+    // jj reports its progress on stderr, so the new commit is summarized the
+    // way git does (e.g. [kntqzsqt 1a2b3c4d] feat: ...)
+    let summary = ExternalCommand::jj([
+        "log",
+        "--no-graph",
+        "-r",
+        "@-",
+        "-T",
+        "\"[\" ++ change_id.short(8) ++ \" \" ++ commit_id.short(8) ++ \"] \" ++ \
+         description.first_line()",
+    ])
+    .current_dir(working_dir)
+    .run()?;
+
+    println!("\n{}\n", summary.trimmed_stdout().italic());
+
+    Ok(())
+}
+
+/// Checks that there are changes to commit,
+/// processes the commit message,
+/// and then executes the `fossil commit` command
+/// with the generated message.
+/// With autosync on, Fossil syncs with its remote as part of the commit.
+pub fn execute_commit_fossil(working_dir: &Path) -> ScudResult {
+    check_for_staged_files_fossil(working_dir)?;
+
+    let commit_message = process_commit_message()?;
+
+    let output = ExternalCommand::fossil(["commit", "-m", &commit_message])
+        .current_dir(get_repository_root(&VCS::Fossil, working_dir))
+        .run()?;
+
+    // The new commit is summarized the way git does (e.g. [trunk 1a2b3c4d5e]
+    // feat: ...), taking the hash from "New_Version: 1a2b3c4d5e..."
+    let hash = output
+        .stdout
+        .lines()
+        .find_map(|line| line.strip_prefix("New_Version: "))
+        .map(|hash| hash.trim().chars().take(10).collect::<String>())
+        .unwrap_or_else(|| "?".to_string());
+
+    println!(
+        "\n{}\n",
+        format!(
+            "[{} {}] {}",
+            get_current_branch_fossil(working_dir),
+            hash,
+            commit_message.lines().next().unwrap_or_default()
+        )
+        .italic()
+    );
+
+    Ok(())
+}
+
+/// Pre-fills the commit wizard with the previous commit message parsed back
+/// into its parts and then executes `git commit --amend` with the generated
+/// message. Warns when the amended commit has already been pushed.
+/// The checks and hooks are skipped when `no_verify` is true.
+pub fn execute_commit_amend_git(no_verify: bool, working_dir: &Path) -> ScudResult {
+    let previous_commit_message = get_previous_commit_message_git(working_dir);
+
+    if previous_commit_message.is_empty() {
+        return Err(ScudError::failed(
+            "Attempting to amend without any previous commit",
+            "There is no commit on the current branch to amend yet",
+        ));
+    }
+
+    if is_commit_pushed_git("HEAD", working_dir) {
+        log_diagnostic(DiagnosticKind::Warning {
+            subject: "The commit being amended has already been pushed",
+            body: "Amending it rewrites history which already exists in the \
+                   remote repository. Pushing the amended commit will require a \
+                   force push and may disrupt anyone who has already pulled it.",
+        });
+    }
+
+    if !no_verify {
+        let findings =
+            run_pre_commit_checks_git(&PreCommitConfig::from_env(), working_dir);
+        if log_findings(&findings) {
+            return Err(ScudError::Reported { exit_code: 1 });
         }
-        Err(error) => log_diagnostic(DiagnosticKind::Error {
-            subject: "git commit failed",
-            body: &format!("{}", error),
-        }),
+        run_hooks_git("pre-commit", &[], working_dir)?;
     }
+
+    let mut commit_message = process_commit_message_with_defaults(
+        &parse_commit_message(&previous_commit_message),
+    )?;
+    if !no_verify {
+        commit_message = run_commit_msg_hooks_git(&commit_message, working_dir)?;
+    }
+
+    // Record HEAD before amending so `scud undo` can restore the original commit
+    let snapshot = snapshot_git(Operation::Commit, working_dir);
+
+    let mut args = vec!["commit", "--amend", "-m", &commit_message];
+    args.extend(no_verify_git(
+        no_verify,
+        &["pre-commit", "commit-msg"],
+        working_dir,
+    ));
+
+    run_commit_git(&args, snapshot, working_dir)
 }
 
-pub fn execute_commit_mercurial() {
-    let _commit_message = process_commit_message();
+/// Asks the user which of the recent unpushed commits to fix up and then
+/// executes `git commit --fixup` targeting the selected commit.
+/// The checks and hooks are skipped when `no_verify` is true.
+pub fn execute_commit_fixup_git(no_verify: bool, working_dir: &Path) -> ScudResult {
+    check_for_staged_files(no_verify, working_dir)?;
+
+    if !no_verify {
+        run_hooks_git("pre-commit", &[], working_dir)?;
+    }
+
+    let fixup_target = get_fixup_target_git(working_dir)?;
+
+    // Record HEAD before committing so `scud undo` can un-commit
+    let snapshot = snapshot_git(Operation::Commit, working_dir);
 
-    todo!("execute_commit_mercurial");
+    let fixup = format!("--fixup={}", fixup_target);
+    let mut args = vec!["commit", &fixup];
+    args.extend(no_verify_git(no_verify, &["pre-commit"], working_dir));
+
+    run_commit_git(&args, snapshot, working_dir)?;
+
+    log_diagnostic(DiagnosticKind::Tip {
+        body: &format!(
+            "{} {}{}{} {}",
+            "Use".yellow(),
+            backtick(),
+            "scud squash-fixups".green().italic(),
+            backtick(),
+            "to squash fixup commits into the commits they target".yellow(),
+        ),
+    });
+
+    Ok(())
 }
 
-pub fn execute_commit_breezy() {
-    let _commit_message = process_commit_message();
+/// Skips the hooks git would run on its own when the user asked to skip them
+/// or when scud already ran them, so they don't run twice.
+fn no_verify_git(
+    no_verify: bool,
+    handled_hook_types: &[&str],
+    working_dir: &Path,
+) -> Option<&'static str> {
+    if no_verify || can_skip_verification_git(handled_hook_types, working_dir) {
+        Some("--no-verify")
+    } else {
+        None
+    }
+}
+
+/// Runs the given `git commit` invocation, recording the snapshot in the
+/// journal on success and failing with git's output otherwise.
+fn run_commit_git(
+    args: &[&str],
+    snapshot: JournalEntry,
+    working_dir: &Path,
+) -> ScudResult {
+    let output = ExternalCommand::git(args).current_dir(working_dir).run()?;
+
+    record_operation_git(snapshot, working_dir);
+    println!("\n{}\n", output.trimmed_stdout().italic());
 
-    todo!("execute_commit_breezy");
+    Ok(())
 }
@@ -1,13 +1,28 @@
-use std::process::{exit, Command};
+use std::path::Path;
 
 use colored::Colorize;
 use dialoguer::{theme::ColorfulTheme, Confirm, FuzzySelect, Input};
 use indicatif::ProgressBar;
 
 use crate::{
-    commands::commit::{commit::CommitMessageFormat, logging::log_commit_message},
+    cli::cli::VCS,
+    commands::{
+        commit::{
+            checks::{log_findings, run_pre_commit_checks_git, PreCommitConfig},
+            commit::CommitMessageFormat,
+            logging::{log_commit_message, log_unformatted_commit_message},
+            parser::CommitMessageParts,
+        },
+        hooks::helpers::get_repository_root,
+        state::helpers::{
+            get_changes_breezy, get_changes_fossil, get_changes_jujutsu,
+            get_changes_mercurial, get_changes_subversion,
+        },
+    },
     diagnostics::{log_diagnostic, DiagnosticKind},
-    logging::helpers::bright_yellow_backtick,
+    error::{ScudError, ScudResult},
+    logging::theme::Themed,
+    runner::ExternalCommand,
 };
 
 /// Loop that asks the user for a commit message format, then asks the
@@ -16,26 +31,43 @@ use crate::{
 ///
 /// Returns:
 ///
-/// A String
-pub fn process_commit_message() -> String {
+/// A String, or an error when one of the prompts was interrupted
+pub fn process_commit_message() -> ScudResult<String> {
+    process_commit_message_with_defaults(&CommitMessageParts::default())
+}
+
+/// Same as `process_commit_message`, but each step of the wizard is pre-filled
+/// with the given pieces of a commit message (e.g. the previous commit message
+/// parsed back into its parts when amending).
+///
+/// Arguments:
+///
+/// * `defaults`: The values each prompt of the wizard starts out with.
+///
+/// Returns:
+///
+/// A String, or an error when one of the prompts was interrupted
+pub fn process_commit_message_with_defaults(
+    defaults: &CommitMessageParts,
+) -> ScudResult<String> {
     #[allow(unused_assignments)]
     let mut commit_message = String::new();
 
     loop {
         // TODO check for global config file and if so, skip this step
-        let commit_message_format = get_commit_message_format();
+        let commit_message_format = get_commit_message_format(defaults)?;
 
         commit_message = String::new();
 
         match commit_message_format {
             CommitMessageFormat::Conventional => {
-                commit_message = commit_conventional_standard();
+                commit_message = commit_conventional_standard(defaults)?;
             }
             CommitMessageFormat::Angular => {
                 commit_message = commit_angular_standard();
             }
             CommitMessageFormat::None => {
-                commit_message = commit_standard_none();
+                commit_message = commit_standard_none(defaults)?;
             }
             CommitMessageFormat::Unknown => {
                 log_diagnostic(DiagnosticKind::Error {
@@ -55,17 +87,16 @@ pub fn process_commit_message() -> String {
             ))
             .default(true)
             .wait_for_newline(true)
-            .interact()
-            .unwrap();
+            .interact()?;
 
         if confirm_commit {
             break;
         }
 
-        clearscreen::clear().expect("failed to clear screen");
+        let _ = clearscreen::clear();
     }
 
-    commit_message
+    Ok(commit_message)
 }
 
 // TODO refactor to commit/formatters.rs
@@ -81,36 +112,42 @@ pub fn process_commit_message() -> String {
 /// and referenced issues, then logs a prettified version of the commit message
 /// to the user before returning it
 ///
+/// Arguments:
+///
+/// * `defaults`: The values each prompt starts out with.
+///
 /// Returns:
 ///
 /// A String
-pub fn commit_conventional_standard() -> String {
+pub fn commit_conventional_standard(
+    defaults: &CommitMessageParts,
+) -> ScudResult<String> {
     let pb = ProgressBar::new(6);
 
     pb.inc(1);
     pb.message();
 
-    let commit_type = get_commit_type();
+    let commit_type = get_commit_type(&defaults.commit_type)?;
 
     pb.inc(1);
 
-    let scope = get_scope();
+    let scope = get_scope(&defaults.scope)?;
 
     pb.inc(1);
 
-    let subject = get_subject(&commit_type, &scope);
+    let subject = get_subject(&commit_type, &scope, &defaults.subject)?;
 
     pb.inc(1);
 
-    let body = get_body();
+    let body = get_body(&defaults.body)?;
 
     pb.inc(1);
 
-    let breaking_changes = get_breaking_changes();
+    let breaking_changes = get_breaking_changes(&defaults.breaking_changes)?;
 
     pb.inc(1);
 
-    let referenced_issues = get_referenced_issues();
+    let referenced_issues = get_referenced_issues(&defaults.referenced_issues)?;
 
     pb.finish_and_clear();
 
@@ -141,15 +178,36 @@ pub fn commit_conventional_standard() -> String {
         referenced_issues,
     );
 
-    commit_message
+    Ok(commit_message)
 }
 
 pub fn commit_angular_standard() -> String {
     "angular message".to_string()
 }
 
-pub fn commit_standard_none() -> String {
-    "none message".to_string()
+/// It asks the user for a subject and an optional body, then logs the
+/// unformatted commit message to the user before returning it
+///
+/// Arguments:
+///
+/// * `defaults`: The values each prompt starts out with.
+///
+/// Returns:
+///
+/// A String
+pub fn commit_standard_none(defaults: &CommitMessageParts) -> ScudResult<String> {
+    let subject = get_subject("", "", &defaults.subject)?;
+
+    let body = get_body(&defaults.body)?;
+
+    let commit_message = match body.len() {
+        0 => subject.clone(),
+        _ => format!("{subject}\n\n{body}"),
+    };
+
+    log_unformatted_commit_message(subject, body);
+
+    Ok(commit_message)
 }
 
 /////////////////////////////////////////////////////
@@ -160,10 +218,17 @@ pub fn commit_standard_none() -> String {
 /// It asks the user to select a commit message format, and returns the selected
 /// format
 ///
+/// Arguments:
+///
+/// * `defaults`: Pre-filled commit message, an unformatted message (no commit
+///   type) preselects the "None" format.
+///
 /// Returns:
 ///
 /// A CommitMessageFormat enum
-pub fn get_commit_message_format() -> CommitMessageFormat {
+pub fn get_commit_message_format(
+    defaults: &CommitMessageParts,
+) -> ScudResult<CommitMessageFormat> {
     let commit_message_formatting_options = &[
         format!(
             "{}{}",
@@ -188,14 +253,19 @@ pub fn get_commit_message_format() -> CommitMessageFormat {
                 "Select a ".bright_yellow().italic(),
                 "commit message format".yellow().italic()
             ))
-            .default(0)
+            .default(
+                match defaults.commit_type.is_empty() && !defaults.subject.is_empty()
+                {
+                    true => 2,
+                    false => 0,
+                },
+            )
             .items(&commit_message_formatting_options[..])
-            .interact()
-            .unwrap();
+            .interact()?;
 
-    clearscreen::clear().expect("failed to clear screen");
+    let _ = clearscreen::clear();
 
-    match selected_commit_message_format {
+    let commit_message_format = match selected_commit_message_format {
         0 => CommitMessageFormat::Conventional,
         1 => CommitMessageFormat::Angular,
         2 => CommitMessageFormat::None,
@@ -208,7 +278,9 @@ pub fn get_commit_message_format() -> CommitMessageFormat {
             });
             CommitMessageFormat::None
         }
-    }
+    };
+
+    Ok(commit_message_format)
 }
 
 /// It returns the number of characters that can be used in the subject line of
@@ -238,10 +310,14 @@ pub fn get_remaining_subject_length(commit_type: &str, scope: &str) -> usize {
 /// It takes a list of commit types, prompts the user to select one, and returns
 /// the selected commit type
 ///
+/// Arguments:
+///
+/// * `default_type`: The commit type to preselect (e.g. feat), if any.
+///
 /// Returns:
 ///
 /// A string
-pub fn get_commit_type() -> String {
+pub fn get_commit_type(default_type: &str) -> ScudResult<String> {
     let commit_type_options = &[
         "feat:  A new feature",
         "fix:  A bug fix",
@@ -272,18 +348,22 @@ pub fn get_commit_type() -> String {
             "type of change".yellow().italic(),
             " that you're committing:".bright_yellow().italic()
         ))
-        .default(0)
+        .default(
+            commit_type_options
+                .iter()
+                .position(|option| option.split(':').next() == Some(default_type))
+                .unwrap_or(0),
+        )
         .items(&commit_type_options[..])
-        .interact()
-        .unwrap();
+        .interact()?;
 
-    clearscreen::clear().expect("failed to clear screen");
+    let _ = clearscreen::clear();
 
-    commit_type_options[selected_commit_type]
-        .split(":")
+    Ok(commit_type_options[selected_commit_type]
+        .split(':')
         .next()
-        .unwrap()
-        .to_string()
+        .unwrap_or_default()
+        .to_string())
 }
 
 /// It asks the user for the scope of the change, and returns the answer
@@ -291,7 +371,7 @@ pub fn get_commit_type() -> String {
 /// Returns:
 ///
 /// A string
-pub fn get_scope() -> String {
+pub fn get_scope(default_scope: &str) -> ScudResult<String> {
     let scope = Input::new()
         .with_prompt(format!(
             "\n{}{}{}{}:{}",
@@ -299,18 +379,22 @@ pub fn get_scope() -> String {
             "scope".yellow().italic(),
             " of this change ".bright_yellow().italic(),
             "(e.g. component or file name)".green().italic(),
-            " (press enter to skip)".black().italic()
+            " (press enter to skip)".muted().italic()
         ))
+        .with_initial_text(default_scope)
         .default("".to_string())
-        .interact_text()
-        .unwrap();
+        .interact_text()?;
 
-    clearscreen::clear().expect("failed to clear screen");
+    let _ = clearscreen::clear();
 
-    scope
+    Ok(scope)
 }
 
-pub fn get_subject(commit_type: &str, scope: &str) -> String {
+pub fn get_subject(
+    commit_type: &str,
+    scope: &str,
+    default_subject: &str,
+) -> ScudResult<String> {
     let remaining_subject_length =
         get_remaining_subject_length(&commit_type, &scope);
 
@@ -319,12 +403,12 @@ pub fn get_subject(commit_type: &str, scope: &str) -> String {
             "\n{} {}{}{} {} {}{}{}",
             "Write a".bright_yellow().italic(),
             "short".yellow().italic(),
-            ",".black().italic(),
+            ",".muted().italic(),
             " imperative tense description".yellow().italic(),
             "of the change".bright_yellow().italic(),
-            "(max ".black().italic(),
-            remaining_subject_length.to_string().black().italic(),
-            " chars)".black().italic()
+            "(max ".muted().italic(),
+            remaining_subject_length.to_string().muted().italic(),
+            " chars)".muted().italic()
         ))
         .validate_with(|input: &String| -> Result<(), &str> {
             if input.len() <= remaining_subject_length {
@@ -337,34 +421,34 @@ pub fn get_subject(commit_type: &str, scope: &str) -> String {
                 )
             }
         })
+        .with_initial_text(default_subject)
         .default("".into())
-        .interact_text()
-        .unwrap();
+        .interact_text()?;
 
-    clearscreen::clear().expect("failed to clear screen");
+    let _ = clearscreen::clear();
 
-    subject
+    Ok(subject)
 }
 
-pub fn get_body() -> String {
+pub fn get_body(default_body: &str) -> ScudResult<String> {
     let body = Input::new()
         .with_prompt(format!(
             "\n{}{}{}{}",
             "Provide a ".bright_yellow().italic(),
             "longer description".yellow().italic(),
             " of the change ".bright_yellow().italic(),
-            " (press enter to skip)".black().italic()
+            " (press enter to skip)".muted().italic()
         ))
+        .with_initial_text(default_body)
         .default("".into())
-        .interact_text()
-        .unwrap();
+        .interact_text()?;
 
-    clearscreen::clear().expect("failed to clear screen");
+    let _ = clearscreen::clear();
 
-    body
+    Ok(body)
 }
 
-pub fn get_breaking_changes() -> String {
+pub fn get_breaking_changes(default_breaking_changes: &str) -> ScudResult<String> {
     // Continue
 
     let breaking_changes = Confirm::with_theme(&ColorfulTheme::default())
@@ -374,10 +458,9 @@ pub fn get_breaking_changes() -> String {
             "breaking changes".yellow().italic(),
             "?".bright_yellow().italic()
         ))
-        .default(false)
+        .default(!default_breaking_changes.is_empty())
         .wait_for_newline(true)
-        .interact()
-        .unwrap();
+        .interact()?;
 
     let mut breaking_changes_section = String::new();
 
@@ -389,19 +472,19 @@ pub fn get_breaking_changes() -> String {
                 " a description".yellow().italic(),
                 " of the breaking changes".bright_yellow().italic()
             ))
+            .with_initial_text(default_breaking_changes)
             .default("".into())
-            .interact_text()
-            .unwrap();
+            .interact_text()?;
 
         breaking_changes_section = format!("{}", breaking_changes_message)
     }
 
-    clearscreen::clear().expect("failed to clear screen");
+    let _ = clearscreen::clear();
 
-    breaking_changes_section
+    Ok(breaking_changes_section)
 }
 
-pub fn get_referenced_issues() -> String {
+pub fn get_referenced_issues(default_referenced_issues: &str) -> ScudResult<String> {
     let referenced_issues = Confirm::with_theme(&ColorfulTheme::default())
         .with_prompt(format!(
             "{}{}{}",
@@ -409,10 +492,9 @@ pub fn get_referenced_issues() -> String {
             "affect any open issues".yellow().italic(),
             "?".bright_yellow().italic()
         ))
-        .default(false)
+        .default(!default_referenced_issues.is_empty())
         .wait_for_newline(true)
-        .interact()
-        .unwrap();
+        .interact()?;
 
     let mut referenced_issues_section = String::new();
 
@@ -424,52 +506,223 @@ pub fn get_referenced_issues() -> String {
                 "list of issue numbers ".yellow().italic(),
                 "(e.g. #123, #456, #789, etc.)".green().italic()
             ))
+            .with_initial_text(default_referenced_issues)
             .default("".into())
-            .interact_text()
-            .unwrap();
+            .interact_text()?;
 
         referenced_issues_section = format!("{}", referenced_issues_message)
     }
 
-    clearscreen::clear().expect("failed to clear screen");
+    let _ = clearscreen::clear();
 
-    referenced_issues_section
+    Ok(referenced_issues_section)
 }
 
-pub fn check_for_staged_files() {
+/// Checks that there are staged changes to commit and then runs the built-in
+/// pre-commit checks against them, failing when either fails.
+///
+/// Arguments:
+///
+/// * `no_verify`: When true, the pre-commit checks are skipped.
+/// * `working_dir`: The directory within the repository to run git in.
+pub fn check_for_staged_files(no_verify: bool, working_dir: &Path) -> ScudResult {
     // Get changes staged for commit
     // git diff --name-only --cached
-    match Command::new("git")
-        .args(["diff", "--name-only", "--cached"])
+    let staged_changes = ExternalCommand::git(["diff", "--name-only", "--cached"])
+        .current_dir(working_dir)
+        .run()?
+        .stdout;
+
+    if staged_changes.is_empty() {
+        return Err(no_staged_changes_error());
+    }
+
+    // The findings are logged along with how to fix them
+    if !no_verify
+        && log_findings(&run_pre_commit_checks_git(
+            &PreCommitConfig::from_env(),
+            working_dir,
+        ))
+    {
+        return Err(ScudError::Reported { exit_code: 1 });
+    }
+
+    Ok(())
+}
+
+/// Checks that `hg commit` has any changes to commit (Mercurial).
+pub fn check_for_staged_files_mercurial(working_dir: &Path) -> ScudResult {
+    let changes =
+        get_changes_mercurial(&get_repository_root(&VCS::Mercurial, working_dir))?;
+
+    if changes.staged.is_empty() {
+        return Err(no_staged_changes_error());
+    }
+
+    Ok(())
+}
+
+/// Checks that `brz commit` has any changes to commit (Breezy).
+pub fn check_for_staged_files_breezy(working_dir: &Path) -> ScudResult {
+    let changes =
+        get_changes_breezy(&get_repository_root(&VCS::Breezy, working_dir))?;
+
+    if changes.staged.is_empty() {
+        return Err(no_staged_changes_error());
+    }
+
+    Ok(())
+}
+
+/// Checks that `svn commit` has any changes to commit (Subversion).
+pub fn check_for_staged_files_subversion(working_dir: &Path) -> ScudResult {
+    let changes =
+        get_changes_subversion(&get_repository_root(&VCS::Subversion, working_dir))?;
+
+    if changes.staged.is_empty() {
+        return Err(no_staged_changes_error());
+    }
+
+    Ok(())
+}
+
+/// Checks that the working-copy commit has any changes to commit (Jujutsu).
+pub fn check_for_staged_files_jujutsu(working_dir: &Path) -> ScudResult {
+    let changes =
+        get_changes_jujutsu(&get_repository_root(&VCS::Jujutsu, working_dir))?;
+
+    if changes.staged.is_empty() {
+        return Err(no_staged_changes_error());
+    }
+
+    Ok(())
+}
+
+/// Checks that `fossil commit` has any changes to commit (Fossil).
+pub fn check_for_staged_files_fossil(working_dir: &Path) -> ScudResult {
+    let changes =
+        get_changes_fossil(&get_repository_root(&VCS::Fossil, working_dir))?;
+
+    if changes.staged.is_empty() {
+        return Err(no_staged_changes_error());
+    }
+
+    Ok(())
+}
+
+fn no_staged_changes_error() -> ScudError {
+    ScudError::failed(
+        "Attempting to commit without any staged changes",
+        "Please stage your changes before going through the commit process",
+    )
+    .with_hint(
+        format!(
+            "{}",
+            "Stage all unstaged changes and untracked files for commit with"
+                .yellow()
+        ),
+        "scud stage",
+    )
+}
+
+/////////////////////////////////////////////////////
+// Helpers for amending and fixing up commits      //
+/////////////////////////////////////////////////////
+
+/// Gets the full message of the previous commit (git).
+///
+/// Returns:
+///
+/// A String (empty when there is no previous commit)
+pub fn get_previous_commit_message_git(working_dir: &Path) -> String {
+    match ExternalCommand::git(["log", "-1", "--format=%B"])
+        .current_dir(working_dir)
         .output()
     {
-        Ok(output) => {
-            let staged_changes = String::from_utf8_lossy(&output.stdout).to_string();
+        Ok(output) => output.trimmed_stdout().to_string(),
+        Err(error) => {
+            error.log();
+            String::new()
+        }
+    }
+}
 
-            if staged_changes.len() == 0 {
-                log_diagnostic(DiagnosticKind::Error {
-                    subject: "Attempting to commit without any staged changes",
-                    body: "Please stage your changes before going through the \
-                              commit process",
-                });
-                log_diagnostic(DiagnosticKind::Tip {
-                    body: &format!(
-                        "{} {}{}{} {}",
-                        "Use".yellow(),
-                        bright_yellow_backtick(),
-                        "scud stage".green().italic(),
-                        bright_yellow_backtick(),
-                        "to stage all unstaged changes and untracked files for \
-                         commit"
-                            .yellow(),
-                    ),
-                });
-                exit(1);
-            }
+/// Checks whether the given commit is already contained in any remote-tracking
+/// branch, i.e. whether it has already been pushed (git).
+pub fn is_commit_pushed_git(commit: &str, working_dir: &Path) -> bool {
+    ExternalCommand::git(["branch", "--remotes", "--contains", commit])
+        .current_dir(working_dir)
+        .stdout()
+        .map_or(false, |branches| !branches.is_empty())
+}
+
+/// Gets the most recent commits which haven't been pushed to any remote yet
+/// (git).
+///
+/// Returns:
+///
+/// A vector of (commit hash, subject) tuples, most recent first
+pub fn get_unpushed_commits_git(working_dir: &Path) -> Vec<(String, String)> {
+    match ExternalCommand::git([
+        "log",
+        "-n",
+        "20",
+        "--format=%H%x09%s",
+        "HEAD",
+        "--not",
+        "--remotes",
+    ])
+    .current_dir(working_dir)
+    .output()
+    {
+        Ok(output) => output
+            .stdout
+            .lines()
+            .filter_map(|line| line.split_once('\t'))
+            .map(|(hash, subject)| (hash.to_string(), subject.to_string()))
+            .collect(),
+        Err(error) => {
+            error.log();
+            vec![]
         }
-        Err(error) => log_diagnostic(DiagnosticKind::Error {
-            subject: "getting staged changes (git)",
-            body: &format!("{}", error),
-        }),
     }
 }
+
+/// It asks the user to pick one of the recent unpushed commits as the target
+/// of a fixup commit
+///
+/// Returns:
+///
+/// The hash of the selected commit
+pub fn get_fixup_target_git(working_dir: &Path) -> ScudResult<String> {
+    let unpushed_commits = get_unpushed_commits_git(working_dir);
+
+    if unpushed_commits.is_empty() {
+        return Err(ScudError::failed(
+            "No unpushed commits to fix up",
+            "All commits on the current branch have already been pushed. Fixing \
+             up a pushed commit would require rewriting the history of the remote \
+             repository.",
+        ));
+    }
+
+    let commit_options = unpushed_commits
+        .iter()
+        .map(|(hash, subject)| format!("{}  {}", &hash[..7], subject))
+        .collect::<Vec<String>>();
+
+    let selected_commit = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "{}{}{}",
+            "Select the ".bright_yellow().italic(),
+            "commit".yellow().italic(),
+            " you want to fix up:".bright_yellow().italic()
+        ))
+        .default(0)
+        .items(&commit_options[..])
+        .interact()?;
+
+    let _ = clearscreen::clear();
+
+    Ok(unpushed_commits[selected_commit].0.clone())
+}
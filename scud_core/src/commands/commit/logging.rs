@@ -1,6 +1,7 @@
 use colored::Colorize;
 
-use crate::logging::helpers::{black_italic_close_paren, black_italic_open_paren};
+use crate::logging::theme::Themed;
+use crate::logging::helpers::{close_paren, open_paren};
 
 // supports generating commit messages following an
 // assortment of commit message formats (Angular, Conventional, etc.)
@@ -44,15 +45,15 @@ pub fn log_commit_message(
         .green()
         .italic();
     let commit_type = commit_type.bright_yellow();
-    let left_paren = black_italic_open_paren();
+    let left_paren = open_paren();
     let scope = scope.green();
-    let right_paren = black_italic_close_paren();
+    let right_paren = close_paren();
     let subject = subject.bright_yellow();
     let body = body.italic();
     let breaking_changes_header = "BREAKING CHANGE: ".red();
-    let breaking_changes = breaking_changes.black().italic();
+    let breaking_changes = breaking_changes.muted().italic();
     let refs_header = "Refs: ".yellow();
-    let refs = refs.black().italic();
+    let refs = refs.muted().italic();
 
     let mut logged_commit_message =
         format!("{commit_message_header} {commit_message_note}\n\n");
@@ -79,3 +80,21 @@ pub fn log_commit_message(
 
     println!("{}\n", logged_commit_message);
 }
+
+pub fn log_unformatted_commit_message(subject: String, body: String) {
+    let commit_message_header = " GENERATED COMMIT MESSAGE ".on_red();
+    let commit_message_note = "(colored output omitted from commit message)"
+        .green()
+        .italic();
+    let subject = subject.bright_yellow();
+    let body = body.italic();
+
+    let logged_commit_message = match body.len() {
+        0 => format!("{commit_message_header} {commit_message_note}\n\n{subject}"),
+        _ => format!(
+            "{commit_message_header} {commit_message_note}\n\n{subject}\n\n{body}"
+        ),
+    };
+
+    println!("{}\n", logged_commit_message);
+}
@@ -0,0 +1,103 @@
+/// The individual pieces of a commit message gathered by the commit wizard.
+///
+/// Properties:
+///
+/// * `commit_type`: The type of change (e.g. feat, fix, docs), empty when the
+///   message doesn't follow a commit message standard.
+/// * `scope`: The scope of the change (e.g. component or file name).
+/// * `subject`: Short, imperative tense description of the change.
+/// * `body`: Longer description of the change.
+/// * `breaking_changes`: Description of the breaking changes (`BREAKING
+///   CHANGE:` footer).
+/// * `referenced_issues`: Comma separated list of issues (`Refs:` footer).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CommitMessageParts {
+    pub commit_type: String,
+    pub scope: String,
+    pub subject: String,
+    pub body: String,
+    pub breaking_changes: String,
+    pub referenced_issues: String,
+}
+
+/// Parses a commit message (e.g. the message of the previous commit when
+/// amending) back into the pieces the commit wizard asks the user for.
+///
+/// Headers following the Conventional Commit Standard
+/// (`type(scope)!: subject`) are split into their type, scope and subject,
+/// any other header is treated as the subject of an unformatted message.
+/// `BREAKING CHANGE:` and `Refs:` footers are extracted from the remaining
+/// paragraphs.
+///
+/// Arguments:
+///
+/// * `message`: The raw commit message.
+///
+/// Returns:
+///
+/// A CommitMessageParts struct
+pub fn parse_commit_message(message: &str) -> CommitMessageParts {
+    let mut parts = CommitMessageParts::default();
+    let message = message.trim();
+
+    let (header, rest) = match message.split_once('\n') {
+        Some((header, rest)) => (header.trim(), rest.trim()),
+        None => (message, ""),
+    };
+
+    match parse_header(header) {
+        Some((commit_type, scope, subject)) => {
+            parts.commit_type = commit_type;
+            parts.scope = scope;
+            parts.subject = subject;
+        }
+        None => parts.subject = header.to_string(),
+    }
+
+    let mut body_paragraphs = vec![];
+
+    for paragraph in rest.split("\n\n") {
+        let paragraph = paragraph.trim();
+
+        if let Some(breaking_changes) = paragraph
+            .strip_prefix("BREAKING CHANGE:")
+            .or_else(|| paragraph.strip_prefix("BREAKING-CHANGE:"))
+        {
+            parts.breaking_changes = breaking_changes.trim().to_string();
+        } else if let Some(referenced_issues) = paragraph.strip_prefix("Refs:") {
+            parts.referenced_issues = referenced_issues.trim().to_string();
+        } else if !paragraph.is_empty() {
+            body_paragraphs.push(paragraph);
+        }
+    }
+
+    parts.body = body_paragraphs.join("\n\n");
+
+    parts
+}
+
+/// Splits a `type(scope)!: subject` header into its type, scope and subject.
+fn parse_header(header: &str) -> Option<(String, String, String)> {
+    let (prefix, subject) = header.split_once(": ")?;
+    let prefix = prefix.strip_suffix('!').unwrap_or(prefix);
+
+    let (commit_type, scope) = match prefix.split_once('(') {
+        Some((commit_type, scope)) => (commit_type, scope.strip_suffix(')')?),
+        None => (prefix, ""),
+    };
+
+    let is_valid_type = !commit_type.is_empty()
+        && commit_type
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-');
+
+    if !is_valid_type {
+        return None;
+    }
+
+    Some((
+        commit_type.to_string(),
+        scope.to_string(),
+        subject.trim().to_string(),
+    ))
+}
@@ -0,0 +1,3 @@
+pub mod conflicts;
+pub mod executors;
+pub mod helpers;
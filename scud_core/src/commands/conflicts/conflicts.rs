@@ -0,0 +1,51 @@
+use std::path::Path;
+
+use crate::{
+    cli::cli::{Conflicts, VCS},
+    commands::conflicts::executors::{
+        execute_conflicts_breezy, execute_conflicts_fossil, execute_conflicts_git,
+        execute_conflicts_info, execute_conflicts_jujutsu,
+        execute_conflicts_list_git, execute_conflicts_mercurial,
+        execute_conflicts_subversion,
+    },
+    error::ScudResult,
+    repository::Repository,
+};
+
+/// Executes the conflicts command with the given options
+///
+/// Arguments:
+///
+/// * `conflicts_options`: Struct that contains the options that were passed to
+///   the command via user input (e.g. list, info).
+/// * `working_dir`: The directory to operate on, which is the current one
+///   unless given with --directory.
+pub fn conflicts_command(
+    conflicts_options: Conflicts,
+    working_dir: &Path,
+) -> ScudResult {
+    if conflicts_options.info {
+        execute_conflicts_info();
+        Ok(())
+    } else {
+        execute_conflicts(conflicts_options.list, working_dir)
+    }
+}
+
+/// Detects the version control system, and then calls the appropriate
+/// function to list or resolve the conflicts
+fn execute_conflicts(list: bool, working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
+
+    match vcs {
+        VCS::Git if list => execute_conflicts_list_git(working_dir),
+        VCS::Git => execute_conflicts_git(working_dir)?,
+        VCS::Mercurial => execute_conflicts_mercurial(),
+        VCS::Breezy => execute_conflicts_breezy(),
+        VCS::Subversion => execute_conflicts_subversion(),
+        VCS::Jujutsu => execute_conflicts_jujutsu(),
+        VCS::Fossil => execute_conflicts_fossil(),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,583 @@
+use std::{fs, path::Path};
+
+use colored::Colorize;
+use dialoguer::{theme::ColorfulTheme, Confirm, Editor, FuzzySelect};
+
+use crate::{
+    commands::conflicts::helpers::{
+        apply_resolutions, count_conflicts, detect_conflict_operation_git,
+        fill_in_bases_git, get_conflict_sides_git, get_conflicted_files_git,
+        get_file_resolution_options, get_repository_root_git, parse_conflicts,
+        render_conflict, ConflictHunk, ConflictOperation, ConflictSegment,
+        ConflictSides, FileResolution, Resolution,
+    },
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::ScudResult,
+    logging::helpers::backtick,
+    logging::theme::Themed,
+    runner::ExternalCommand,
+    state::executors::execute_state_git,
+};
+
+pub fn execute_conflicts_info() {
+    log_diagnostic(DiagnosticKind::ScudCommandInfo {
+        command: "conflicts",
+        description: "This command is used to resolve the conflicts left behind by \
+                      a merge, rebase, cherry-pick or stash pop. It steps through \
+                      each conflicting hunk showing ours, theirs and the common \
+                      base, stages each resolved file and finally continues the \
+                      operation which stopped.",
+    });
+    log_diagnostic(DiagnosticKind::VCSInfo {
+        command_name: "conflicts",
+        git_command: &format!(
+            "{} {}",
+            "git diff --name-only --diff-filter=U, git add <file>, git commit \
+             --no-edit | git rebase --continue",
+            "(along with git merge-file --diff3 to recover the base of each hunk)"
+                .bright_yellow()
+        ),
+        mercurial_command: &format!("{}", "(not yet supported)".bright_yellow()),
+        breezy_command: &format!("{}", "(not yet supported)".bright_yellow()),
+        other_commands: &[],
+    });
+}
+
+//////////////////////////////////////////////////////////
+// Functions handle the execution of each the supported //
+// underlying VCS implementations of the conflicts      //
+// command                                              //
+//////////////////////////////////////////////////////////
+
+/// Lists every conflicted file along with its number of conflicts.
+pub fn execute_conflicts_list_git(working_dir: &Path) {
+    log_conflict_operation(&detect_conflict_operation_git(working_dir));
+
+    let conflicted_files = get_conflicted_files_git(working_dir);
+
+    if conflicted_files.is_empty() {
+        log_no_conflicts();
+        return;
+    }
+
+    log_conflicted_files(&conflicted_files, working_dir);
+
+    log_diagnostic(DiagnosticKind::Tip {
+        body: &format!(
+            "{} {}{}{} {}",
+            "Use".yellow(),
+            backtick(),
+            "scud conflicts".green().italic(),
+            backtick(),
+            "to step through and resolve each conflict".yellow(),
+        ),
+    });
+}
+
+/// Steps through each conflict hunk of each conflicted file, letting the user
+/// pick how to resolve it. Resolved files are staged and once no conflicts are
+/// left, the operation which stopped is continued.
+pub fn execute_conflicts_git(working_dir: &Path) -> ScudResult {
+    let operation = detect_conflict_operation_git(working_dir);
+    log_conflict_operation(&operation);
+
+    let conflicted_files = get_conflicted_files_git(working_dir);
+
+    if conflicted_files.is_empty() {
+        log_no_conflicts();
+        if operation != ConflictOperation::Unknown {
+            continue_operation_git(&operation, working_dir)?;
+        }
+        return Ok(());
+    }
+
+    log_conflicted_files(&conflicted_files, working_dir);
+
+    let repository_root = get_repository_root_git(working_dir);
+
+    for (file_index, conflicted_file) in conflicted_files.iter().enumerate() {
+        let path = repository_root.join(conflicted_file);
+
+        // Deleted and binary files can't be read, and neither they nor
+        // renamed files have any conflict markers
+        let mut segments = fs::read_to_string(&path)
+            .map(|content| parse_conflicts(&content))
+            .unwrap_or_default();
+        if count_conflicts(&segments) == 0 {
+            resolve_whole_file_git(
+                conflicted_file,
+                (file_index + 1, conflicted_files.len()),
+                working_dir,
+            )?;
+            continue;
+        }
+
+        fill_in_bases_git(conflicted_file, &mut segments, working_dir);
+
+        let conflict_count = count_conflicts(&segments);
+        let mut resolutions = vec![];
+        let mut hunk_index = 0;
+
+        for segment in &segments {
+            if let ConflictSegment::Conflict(hunk) = segment {
+                hunk_index += 1;
+
+                log_diagnostic(DiagnosticKind::Note {
+                    body: &format!(
+                        "{}{}{}{}{}",
+                        format!("Conflict {} of {} in ", hunk_index, conflict_count)
+                            .yellow(),
+                        conflicted_file.cyan().italic(),
+                        " (file ".yellow(),
+                        format!("{} of {}", file_index + 1, conflicted_files.len())
+                            .bright_yellow(),
+                        ")".yellow(),
+                    ),
+                });
+
+                log_conflict_hunk(hunk);
+
+                resolutions.push(get_resolution(hunk)?);
+
+                // Not being able to clear the screen only affects the looks
+                let _ = clearscreen::clear();
+            }
+        }
+
+        let resolved_content = apply_resolutions(&segments, &resolutions);
+
+        if let Err(error) = fs::write(&path, &resolved_content) {
+            log_diagnostic(DiagnosticKind::Error {
+                subject: &format!("failed to write {}", conflicted_file),
+                body: &format!("{}", error),
+            });
+            continue;
+        }
+
+        let remaining = count_conflicts(&parse_conflicts(&resolved_content));
+
+        if remaining > 0 {
+            log_diagnostic(DiagnosticKind::Note {
+                body: &format!(
+                    "{}{}{}",
+                    format!("{} conflict(s) left unresolved in ", remaining)
+                        .yellow(),
+                    conflicted_file.cyan().italic(),
+                    ", the file was not staged".yellow(),
+                ),
+            });
+        } else if stage_resolved_file_git(conflicted_file, working_dir) {
+            println!(
+                "    {}  {}",
+                " RESOLVED ".black().on_bright_green(),
+                conflicted_file.bright_green().italic()
+            );
+        }
+    }
+
+    println!();
+
+    if get_conflicted_files_git(working_dir).is_empty() {
+        continue_operation_git(&operation, working_dir)?;
+    } else {
+        log_diagnostic(DiagnosticKind::Tip {
+            body: &format!(
+                "{} {}{}{} {}",
+                "Some conflicts are still unresolved, rerun".yellow(),
+                backtick(),
+                "scud conflicts".green().italic(),
+                backtick(),
+                "when you're ready to resolve them".yellow(),
+            ),
+        });
+    }
+
+    Ok(())
+}
+
+pub fn execute_conflicts_mercurial() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud conflicts (mercurial)",
+    });
+}
+
+pub fn execute_conflicts_breezy() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud conflicts (breezy)",
+    });
+}
+
+pub fn execute_conflicts_subversion() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud conflicts (subversion)",
+    });
+}
+
+pub fn execute_conflicts_jujutsu() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud conflicts (jujutsu)",
+    });
+}
+
+pub fn execute_conflicts_fossil() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud conflicts (fossil)",
+    });
+}
+
+////////////////////////////////////////////////
+// Helpers for resolving and continuing       //
+////////////////////////////////////////////////
+
+/// It asks the user how to resolve the given conflict hunk
+///
+/// Returns:
+///
+/// A Resolution enum, or an error when the prompt was interrupted
+fn get_resolution(hunk: &ConflictHunk) -> ScudResult<Resolution> {
+    let resolution_options = &[
+        format!("Keep ours  ({})", hunk.ours_label),
+        format!("Keep theirs  ({})", hunk.theirs_label),
+        "Keep both  (ours first, then theirs)".to_string(),
+        "Edit in editor  (opens $EDITOR with the conflict markers)".to_string(),
+        "Skip  (leave the conflict unresolved)".to_string(),
+    ];
+
+    let selected_resolution = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "{}{}{}",
+            "How do you want to ".bright_yellow().italic(),
+            "resolve".yellow().italic(),
+            " this conflict?".bright_yellow().italic()
+        ))
+        .default(0)
+        .items(&resolution_options[..])
+        .interact()?;
+
+    let resolution = match selected_resolution {
+        0 => Resolution::Ours,
+        1 => Resolution::Theirs,
+        2 => Resolution::Both,
+        3 => match Editor::new().edit(&render_conflict(hunk)) {
+            Ok(Some(edited)) => Resolution::Custom(edited),
+            Ok(None) => Resolution::Unresolved,
+            Err(error) => {
+                log_diagnostic(DiagnosticKind::Error {
+                    subject: "failed to open editor",
+                    body: &format!("{}", error),
+                });
+                Resolution::Unresolved
+            }
+        },
+        _ => Resolution::Unresolved,
+    };
+
+    Ok(resolution)
+}
+
+/// Asks the user how to resolve a conflicted file which has no conflict
+/// markers to step through (e.g. a file deleted on one side and changed on
+/// the other, or a binary file), and then resolves it as a whole.
+///
+/// Arguments:
+///
+/// * `file`: The path of the file, relative to the root of the repository.
+/// * `(position, total)`: Which of the conflicted files it is.
+/// * `working_dir`: The directory within the repository to run git in.
+fn resolve_whole_file_git(
+    file: &str,
+    (position, total): (usize, usize),
+    working_dir: &Path,
+) -> ScudResult {
+    let repository_root = get_repository_root_git(working_dir);
+    let sides = get_conflict_sides_git(file, working_dir);
+    let options =
+        get_file_resolution_options(sides, repository_root.join(file).exists());
+
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{}{}{}{}{}",
+            file.cyan().italic(),
+            format!(" has no conflict markers, {} ", describe_sides(sides)).yellow(),
+            "(file ".yellow(),
+            format!("{} of {}", position, total).bright_yellow(),
+            ")".yellow(),
+        ),
+    });
+
+    let labels = options
+        .iter()
+        .map(|(label, _)| label.as_str())
+        .collect::<Vec<&str>>();
+    let selected = FuzzySelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "{}{}{}",
+            "How do you want to ".bright_yellow().italic(),
+            "resolve".yellow().italic(),
+            " this file?".bright_yellow().italic()
+        ))
+        .default(0)
+        .items(&labels)
+        .interact()?;
+
+    let commands: &[&[&str]] = match options[selected].1 {
+        FileResolution::Ours => &[&["checkout", "--ours", "--"], &["add", "--"]],
+        FileResolution::Theirs => &[&["checkout", "--theirs", "--"], &["add", "--"]],
+        FileResolution::Delete => &[&["rm", "--quiet", "--"]],
+        FileResolution::AsIs => &[&["add", "--"]],
+        FileResolution::Unresolved => return Ok(()),
+    };
+
+    for args in commands {
+        if let Err(error) = ExternalCommand::git(*args)
+            .arg(file)
+            .current_dir(&repository_root)
+            .run()
+        {
+            error.log();
+            return Ok(());
+        }
+    }
+
+    println!(
+        "    {}  {}",
+        " RESOLVED ".black().on_bright_green(),
+        file.bright_green().italic()
+    );
+
+    Ok(())
+}
+
+/// Describes why a file without conflict markers is conflicted (e.g. "theirs
+/// deleted it").
+fn describe_sides(sides: ConflictSides) -> &'static str {
+    match (sides.ours, sides.theirs) {
+        (true, true) => "both sides changed it (e.g. it's binary)",
+        (true, false) => "ours changed it while theirs deleted it",
+        (false, true) => "ours deleted it while theirs changed it",
+        (false, false) => "both sides deleted or renamed it",
+    }
+}
+
+/// Marks the given file as resolved by staging it.
+fn stage_resolved_file_git(file: &str, working_dir: &Path) -> bool {
+    match ExternalCommand::git(["add", "--", file])
+        .current_dir(get_repository_root_git(working_dir))
+        .run()
+    {
+        Ok(_) => true,
+        Err(error) => {
+            error.log();
+            false
+        }
+    }
+}
+
+/// Asks the user to confirm, then continues the operation which stopped
+/// because of the (now resolved) conflicts.
+fn continue_operation_git(
+    operation: &ConflictOperation,
+    working_dir: &Path,
+) -> ScudResult {
+    let continue_args: &[&str] = match operation {
+        ConflictOperation::Merge { .. } => &["commit", "--no-edit"],
+        ConflictOperation::Rebase { .. } => &["rebase", "--continue"],
+        ConflictOperation::CherryPick { .. } => &["cherry-pick", "--continue"],
+        ConflictOperation::Revert { .. } => &["revert", "--continue"],
+        ConflictOperation::Unknown => {
+            log_diagnostic(DiagnosticKind::Note {
+                body: &format!(
+                    "{}",
+                    "All conflicts are resolved. If they came from a stash pop, \
+                     the stash was kept and can be dropped once you're happy with \
+                     the result"
+                        .yellow()
+                ),
+            });
+            log_diagnostic(DiagnosticKind::Hint {
+                body: &format!("{}", "Drop the stash with".yellow()),
+                command: "git stash drop",
+            });
+            return Ok(());
+        }
+    };
+
+    let continue_operation = Confirm::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "{}{}{}",
+            "All conflicts are resolved, ".bright_yellow().italic(),
+            format!("continue the {}", operation_name(operation))
+                .yellow()
+                .italic(),
+            "?".bright_yellow().italic()
+        ))
+        .default(true)
+        .wait_for_newline(true)
+        .interact()?;
+
+    if !continue_operation {
+        log_diagnostic(DiagnosticKind::Hint {
+            body: &format!("{}", "When you're ready, continue with".yellow()),
+            command: &format!("git {}", continue_args.join(" ")),
+        });
+        return Ok(());
+    }
+
+    // Keep the default commit messages instead of opening an editor
+    let output = ExternalCommand::git(continue_args)
+        .current_dir(working_dir)
+        .env("GIT_EDITOR", "true")
+        .output()?;
+
+    // Running into new conflicts isn't a failure, they're resolved next
+    if get_conflicted_files_git(working_dir).is_empty() {
+        output.check()?;
+    }
+
+    // A rebase can stop again on the next commit
+    let next_operation = detect_conflict_operation_git(working_dir);
+    if !get_conflicted_files_git(working_dir).is_empty() {
+        log_conflict_operation(&next_operation);
+        log_diagnostic(DiagnosticKind::Tip {
+            body: &format!(
+                "{} {}{}{} {}",
+                "The next step ran into new conflicts, use".yellow(),
+                backtick(),
+                "scud conflicts".green().italic(),
+                backtick(),
+                "again to resolve them".yellow(),
+            ),
+        });
+        return Ok(());
+    }
+
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{}{}",
+            format!("The {} ", operation_name(operation)).yellow(),
+            "completed successfully".bright_green().italic()
+        ),
+    });
+
+    execute_state_git(working_dir)
+}
+
+fn operation_name(operation: &ConflictOperation) -> &'static str {
+    match operation {
+        ConflictOperation::Merge { .. } => "merge",
+        ConflictOperation::Rebase { .. } => "rebase",
+        ConflictOperation::CherryPick { .. } => "cherry-pick",
+        ConflictOperation::Revert { .. } => "revert",
+        ConflictOperation::Unknown => "operation",
+    }
+}
+
+//////////////////////////////////////////
+// Logging helpers for conflicts output //
+//////////////////////////////////////////
+
+/// Logs a diagnostic explaining which operation stopped and how far along it
+/// is.
+fn log_conflict_operation(operation: &ConflictOperation) {
+    let body = match operation {
+        ConflictOperation::Merge { branch } => format!(
+            "{}{}{}",
+            "Merging ".yellow(),
+            branch.cyan().italic(),
+            " into the current branch stopped because of conflicts".yellow()
+        ),
+        ConflictOperation::Rebase {
+            branch,
+            onto,
+            step,
+            total,
+        } => format!(
+            "{}{}{}{}{}{}",
+            "Rebasing ".yellow(),
+            branch.cyan().italic(),
+            " onto ".yellow(),
+            onto.cyan().italic(),
+            " stopped because of conflicts at ".yellow(),
+            format!("commit {} of {}", step, total).bright_yellow()
+        ),
+        ConflictOperation::CherryPick { commit } => format!(
+            "{}{}{}",
+            "Cherry-picking ".yellow(),
+            commit.cyan().italic(),
+            " stopped because of conflicts".yellow()
+        ),
+        ConflictOperation::Revert { commit } => format!(
+            "{}{}{}",
+            "Reverting ".yellow(),
+            commit.cyan().italic(),
+            " stopped because of conflicts".yellow()
+        ),
+        ConflictOperation::Unknown => return,
+    };
+
+    log_diagnostic(DiagnosticKind::Note { body: &body });
+}
+
+fn log_conflicted_files(conflicted_files: &[String], working_dir: &Path) {
+    let repository_root = get_repository_root_git(working_dir);
+
+    println!("{}\n", " CONFLICTED FILES: ".black().on_cyan());
+
+    for conflicted_file in conflicted_files {
+        let conflict_count =
+            fs::read_to_string(repository_root.join(conflicted_file))
+                .map(|content| count_conflicts(&parse_conflicts(&content)))
+                .unwrap_or_default();
+
+        println!(
+            "    {}  {} {}",
+            " U ".black().on_bright_red(),
+            conflicted_file.bright_red().italic(),
+            format!("({} conflict(s))", conflict_count).muted().italic()
+        );
+    }
+
+    println!();
+}
+
+fn log_conflict_hunk(hunk: &ConflictHunk) {
+    println!(
+        "  {}\n",
+        format!(" OURS ({}) ", hunk.ours_label)
+            .black()
+            .on_bright_green()
+    );
+    for line in &hunk.ours {
+        print!("    {}", line.green());
+    }
+
+    if let Some(base) = &hunk.base {
+        println!("\n  {}\n", " BASE ".black().on_bright_white());
+        for line in base {
+            print!("    {}", line.white().italic());
+        }
+    }
+
+    println!(
+        "\n  {}\n",
+        format!(" THEIRS ({}) ", hunk.theirs_label)
+            .black()
+            .on_bright_blue()
+    );
+    for line in &hunk.theirs {
+        print!("    {}", line.bright_blue());
+    }
+
+    println!();
+}
+
+fn log_no_conflicts() {
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{}{}{}",
+            "No ".magenta().italic(),
+            "conflicted files ".cyan().italic(),
+            "in the current repository".magenta().italic()
+        ),
+    });
+}
@@ -0,0 +1,664 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::runner::ExternalCommand;
+
+//////////////////////////////////////////////////////
+// Parsing of conflict markers left in files by the //
+// underlying VCS                                   //
+//////////////////////////////////////////////////////
+
+/// A single conflicted region of a file.
+///
+/// Properties:
+///
+/// * `ours_label`: The label following `<<<<<<<` (e.g. `HEAD`).
+/// * `ours`: The lines from our side of the conflict.
+/// * `base`: The lines from the common ancestor, if known (diff3 style).
+/// * `theirs_label`: The label following `>>>>>>>` (e.g. the merged branch).
+/// * `theirs`: The lines from their side of the conflict.
+/// * `original`: The hunk as it is in the file, markers and line endings
+///   included, which is written back while the hunk is unresolved.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConflictHunk {
+    pub ours_label: String,
+    pub ours: Vec<String>,
+    pub base: Option<Vec<String>>,
+    pub theirs_label: String,
+    pub theirs: Vec<String>,
+    pub original: String,
+}
+
+/// A file split into regions which are either free of conflicts or conflicted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictSegment {
+    Text(Vec<String>),
+    Conflict(ConflictHunk),
+}
+
+/// How the user decided to resolve a conflict hunk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    Ours,
+    Theirs,
+    Both,
+    Custom(String),
+    Unresolved,
+}
+
+/// Which versions of a conflicted file the index has, from its stages (base
+/// :1, ours :2 and theirs :3). A missing side deleted the file.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ConflictSides {
+    pub base: bool,
+    pub ours: bool,
+    pub theirs: bool,
+}
+
+/// How to resolve a conflicted file as a whole, for conflicts without any
+/// markers to step through (e.g. modify/delete conflicts or binary files).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileResolution {
+    Ours,
+    Theirs,
+    Delete,
+    AsIs,
+    Unresolved,
+}
+
+/// The ways a conflicted file without conflict markers can be resolved, along
+/// with how each of them is described to the user.
+///
+/// Arguments:
+///
+/// * `sides`: The versions of the file the index has.
+/// * `exists`: Whether the file is in the working tree.
+pub fn get_file_resolution_options(
+    sides: ConflictSides,
+    exists: bool,
+) -> Vec<(String, FileResolution)> {
+    let mut options = match (sides.ours, sides.theirs) {
+        (true, true) => vec![
+            ("Keep ours".to_string(), FileResolution::Ours),
+            ("Keep theirs".to_string(), FileResolution::Theirs),
+        ],
+        (true, false) => vec![
+            (
+                "Keep the file  (with our changes, theirs deleted it)".to_string(),
+                FileResolution::Ours,
+            ),
+            ("Delete the file".to_string(), FileResolution::Delete),
+        ],
+        (false, true) => vec![
+            (
+                "Keep the file  (with their changes, ours deleted it)".to_string(),
+                FileResolution::Theirs,
+            ),
+            ("Delete the file".to_string(), FileResolution::Delete),
+        ],
+        (false, false) => {
+            vec![("Delete the file".to_string(), FileResolution::Delete)]
+        }
+    };
+
+    if exists {
+        options.push((
+            "Mark as resolved as it is  (the file in the working tree)".to_string(),
+            FileResolution::AsIs,
+        ));
+    }
+    options.push((
+        "Skip  (leave the conflict unresolved)".to_string(),
+        FileResolution::Unresolved,
+    ));
+
+    options
+}
+
+/// Splits the content of a conflicted file into its segments. Lines keep their
+/// line endings so an unresolved file is written back byte for byte. A hunk
+/// which isn't closed by the end of the file (e.g. a `<<<<<<<` line which
+/// happens to be part of the content) is kept as text.
+///
+/// Arguments:
+///
+/// * `content`: The content of the file containing conflict markers.
+///
+/// Returns:
+///
+/// A vector of ConflictSegments
+pub fn parse_conflicts(content: &str) -> Vec<ConflictSegment> {
+    enum Section {
+        Text,
+        Ours,
+        Base,
+        Theirs,
+    }
+
+    let mut segments = vec![];
+    let mut section = Section::Text;
+    let mut text = vec![];
+    // The lines of the hunk being parsed, as they are in the file
+    let mut hunk_lines = vec![];
+    let mut hunk = ConflictHunk::default();
+
+    for line in content.split_inclusive('\n') {
+        if !matches!(section, Section::Text) || line.starts_with("<<<<<<<") {
+            hunk_lines.push(line.to_string());
+        }
+
+        match section {
+            Section::Text if line.starts_with("<<<<<<<") => {
+                if !text.is_empty() {
+                    segments.push(ConflictSegment::Text(text));
+                    text = vec![];
+                }
+                hunk.ours_label = line[7..].trim().to_string();
+                section = Section::Ours;
+            }
+            Section::Text => text.push(line.to_string()),
+            Section::Ours if line.starts_with("|||||||") => {
+                hunk.base = Some(vec![]);
+                section = Section::Base;
+            }
+            Section::Ours | Section::Base if line.starts_with("=======") => {
+                section = Section::Theirs;
+            }
+            Section::Ours => hunk.ours.push(line.to_string()),
+            Section::Base => {
+                if let Some(base) = hunk.base.as_mut() {
+                    base.push(line.to_string());
+                }
+            }
+            Section::Theirs if line.starts_with(">>>>>>>") => {
+                hunk.theirs_label = line[7..].trim().to_string();
+                hunk.original = hunk_lines.concat();
+                segments.push(ConflictSegment::Conflict(hunk));
+                hunk_lines.clear();
+                hunk = ConflictHunk::default();
+                section = Section::Text;
+            }
+            Section::Theirs => hunk.theirs.push(line.to_string()),
+        }
+    }
+
+    // Only a closed hunk is a conflict, so an open one goes back to being text
+    if !matches!(section, Section::Text) {
+        text.append(&mut hunk_lines);
+    }
+
+    if !text.is_empty() {
+        segments.push(ConflictSegment::Text(text));
+    }
+
+    segments
+}
+
+/// Number of conflict hunks in the given segments.
+pub fn count_conflicts(segments: &[ConflictSegment]) -> usize {
+    segments
+        .iter()
+        .filter(|segment| matches!(segment, ConflictSegment::Conflict(_)))
+        .count()
+}
+
+/// Renders a conflict hunk back into its conflict markers, exactly as they
+/// were in the file (a base filled in by `fill_in_bases_git` is left out).
+pub fn render_conflict(hunk: &ConflictHunk) -> String {
+    hunk.original.clone()
+}
+
+/// Writes the segments back out, replacing each conflict hunk with the content
+/// of its resolution (unresolved hunks keep their conflict markers).
+///
+/// Arguments:
+///
+/// * `segments`: The segments of the file.
+/// * `resolutions`: One resolution per conflict hunk, in order.
+///
+/// Returns:
+///
+/// The resolved content of the file
+pub fn apply_resolutions(
+    segments: &[ConflictSegment],
+    resolutions: &[Resolution],
+) -> String {
+    let mut resolutions = resolutions.iter();
+    let mut content = String::new();
+
+    for segment in segments {
+        match segment {
+            ConflictSegment::Text(lines) => content.push_str(&lines.concat()),
+            ConflictSegment::Conflict(hunk) => {
+                match resolutions.next().unwrap_or(&Resolution::Unresolved) {
+                    Resolution::Ours => content.push_str(&hunk.ours.concat()),
+                    Resolution::Theirs => content.push_str(&hunk.theirs.concat()),
+                    Resolution::Both => {
+                        content.push_str(&hunk.ours.concat());
+                        content.push_str(&hunk.theirs.concat());
+                    }
+                    Resolution::Custom(custom) => content.push_str(custom),
+                    Resolution::Unresolved => {
+                        content.push_str(&render_conflict(hunk))
+                    }
+                }
+            }
+        }
+    }
+
+    content
+}
+
+/// Fills in the base of each conflict hunk when the file was written with the
+/// default two-way conflict style, so it can be shown while resolving. The
+/// diff3 version of the file is recomputed from the index stages and only
+/// used when the regions outside of the conflicts match the working file.
+/// Unresolved hunks are still written back without the base.
+pub fn fill_in_bases_git(
+    path: &str,
+    segments: &mut [ConflictSegment],
+    working_dir: &Path,
+) {
+    let needs_base = segments.iter().any(|segment| match segment {
+        ConflictSegment::Conflict(hunk) => hunk.base.is_none(),
+        ConflictSegment::Text(_) => false,
+    });
+
+    if !needs_base {
+        return;
+    }
+
+    let diff3_segments = match merge_file_diff3_git(path, working_dir) {
+        Some(diff3) => parse_conflicts(&diff3),
+        None => return,
+    };
+
+    let texts = |segments: &[ConflictSegment]| {
+        segments
+            .iter()
+            .map(|segment| match segment {
+                ConflictSegment::Text(lines) => Some(lines.clone()),
+                ConflictSegment::Conflict(_) => None,
+            })
+            .collect::<Vec<Option<Vec<String>>>>()
+    };
+
+    if texts(segments) != texts(&diff3_segments) {
+        return;
+    }
+
+    for (segment, diff3_segment) in segments.iter_mut().zip(diff3_segments) {
+        if let (ConflictSegment::Conflict(hunk), ConflictSegment::Conflict(diff3)) =
+            (segment, diff3_segment)
+        {
+            if hunk.base.is_none() {
+                hunk.base = diff3.base;
+            }
+        }
+    }
+}
+
+/// Recomputes the merge of the given path in diff3 style from the base (:1),
+/// ours (:2) and theirs (:3) stages of the index.
+fn merge_file_diff3_git(path: &str, working_dir: &Path) -> Option<String> {
+    let scratch_dir =
+        std::env::temp_dir().join(format!("scud-conflicts-{}", std::process::id()));
+    fs::create_dir_all(&scratch_dir).ok()?;
+
+    let mut stage_files = vec![];
+    for (stage, name) in [(2, "ours"), (1, "base"), (3, "theirs")] {
+        // A missing base stage (add/add conflict) is an empty base
+        let stage_file = scratch_dir.join(name);
+        fs::write(
+            &stage_file,
+            ExternalCommand::git(["show", &format!(":{}:{}", stage, path)])
+                .current_dir(working_dir)
+                .stdout()
+                .unwrap_or_default(),
+        )
+        .ok()?;
+        stage_files.push(stage_file);
+    }
+
+    // git merge-file exits with the number of conflicts, so only a failure to
+    // run it at all is an error
+    let output = ExternalCommand::git(["merge-file", "-p", "--diff3"])
+        .current_dir(working_dir)
+        .args(["-L", "HEAD", "-L", "base", "-L", "theirs"])
+        .args(&stage_files)
+        .output()
+        .ok();
+
+    let _ = fs::remove_dir_all(&scratch_dir);
+
+    output.map(|output| output.stdout)
+}
+
+/////////////////////////////////////////////////////
+// Helpers for inspecting the operation which left //
+// the repository in a conflicted state            //
+/////////////////////////////////////////////////////
+
+/// The operation which stopped because of conflicts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConflictOperation {
+    Merge {
+        branch: String,
+    },
+    Rebase {
+        branch: String,
+        onto: String,
+        step: String,
+        total: String,
+    },
+    CherryPick {
+        commit: String,
+    },
+    Revert {
+        commit: String,
+    },
+    // e.g. `git stash pop`, which doesn't record any state
+    Unknown,
+}
+
+/// Resolves the path of a file within the git directory (`git rev-parse
+/// --git-path`), which works from subdirectories and worktrees alike.
+fn git_path(name: &str, working_dir: &Path) -> PathBuf {
+    ExternalCommand::git(["rev-parse", "--git-path", name])
+        .current_dir(working_dir)
+        .stdout()
+        .map(|path| working_dir.join(path))
+        .unwrap_or_else(|| working_dir.join(".git").join(name))
+}
+
+fn read_git_file(path: &Path) -> String {
+    fs::read_to_string(path)
+        .map(|content| content.trim().to_string())
+        .unwrap_or_default()
+}
+
+fn short_name(revision: &str, working_dir: &Path) -> String {
+    ExternalCommand::git(["name-rev", "--name-only", "--always", revision])
+        .current_dir(working_dir)
+        .stdout()
+        .unwrap_or_else(|| revision.to_string())
+}
+
+/// Detects which operation left the repository in a conflicted state (git).
+pub fn detect_conflict_operation_git(working_dir: &Path) -> ConflictOperation {
+    let rebase_dir = [
+        git_path("rebase-merge", working_dir),
+        git_path("rebase-apply", working_dir),
+    ]
+    .into_iter()
+    .find(|rebase_dir| rebase_dir.is_dir());
+
+    if let Some(rebase_dir) = rebase_dir {
+        let (step, total) = if rebase_dir.join("msgnum").exists() {
+            (
+                read_git_file(&rebase_dir.join("msgnum")),
+                read_git_file(&rebase_dir.join("end")),
+            )
+        } else {
+            (
+                read_git_file(&rebase_dir.join("next")),
+                read_git_file(&rebase_dir.join("last")),
+            )
+        };
+        let onto = read_git_file(&rebase_dir.join("onto"));
+
+        return ConflictOperation::Rebase {
+            branch: read_git_file(&rebase_dir.join("head-name"))
+                .trim_start_matches("refs/heads/")
+                .to_string(),
+            onto: short_name(&onto, working_dir),
+            step,
+            total,
+        };
+    }
+
+    if git_path("MERGE_HEAD", working_dir).exists() {
+        return ConflictOperation::Merge {
+            branch: short_name("MERGE_HEAD", working_dir),
+        };
+    }
+
+    if git_path("CHERRY_PICK_HEAD", working_dir).exists() {
+        return ConflictOperation::CherryPick {
+            commit: short_name("CHERRY_PICK_HEAD", working_dir),
+        };
+    }
+
+    if git_path("REVERT_HEAD", working_dir).exists() {
+        return ConflictOperation::Revert {
+            commit: short_name("REVERT_HEAD", working_dir),
+        };
+    }
+
+    ConflictOperation::Unknown
+}
+
+/// Lists the files with unresolved conflicts (git).
+pub fn get_conflicted_files_git(working_dir: &Path) -> Vec<String> {
+    match ExternalCommand::git(["diff", "--name-only", "--diff-filter=U"])
+        .current_dir(working_dir)
+        .run()
+    {
+        Ok(output) => output
+            .stdout
+            .lines()
+            .map(|line| line.to_string())
+            .collect::<Vec<String>>(),
+        Err(error) => {
+            error.log();
+            vec![]
+        }
+    }
+}
+
+/// Gets which versions of the given conflicted file the index has.
+///
+/// Arguments:
+///
+/// * `path`: The path of the file, relative to the root of the repository.
+/// * `working_dir`: The directory within the repository to run git in.
+pub fn get_conflict_sides_git(path: &str, working_dir: &Path) -> ConflictSides {
+    let stages = ExternalCommand::git(["ls-files", "--unmerged", "--"])
+        .arg(format!(":(top,literal){}", path))
+        .current_dir(working_dir)
+        .stdout()
+        .unwrap_or_default();
+
+    // Each line is "<mode> <object> <stage>\t<path>"
+    let mut sides = ConflictSides::default();
+    for line in stages.lines() {
+        match line
+            .split('\t')
+            .next()
+            .and_then(|line| line.split(' ').nth(2))
+        {
+            Some("1") => sides.base = true,
+            Some("2") => sides.ours = true,
+            Some("3") => sides.theirs = true,
+            _ => {}
+        }
+    }
+
+    sides
+}
+
+/// The root of the working tree, which the paths reported by git are relative
+/// to.
+pub fn get_repository_root_git(working_dir: &Path) -> PathBuf {
+    ExternalCommand::git(["rev-parse", "--show-toplevel"])
+        .current_dir(working_dir)
+        .stdout()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| working_dir.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hunk(ours: &[&str], theirs: &[&str]) -> ConflictHunk {
+        ConflictHunk {
+            ours_label: "HEAD".to_string(),
+            ours: ours.iter().map(|line| line.to_string()).collect(),
+            base: None,
+            theirs_label: "feature".to_string(),
+            theirs: theirs.iter().map(|line| line.to_string()).collect(),
+            original: format!(
+                "<<<<<<< HEAD\n{}=======\n{}>>>>>>> feature\n",
+                ours.concat(),
+                theirs.concat()
+            ),
+        }
+    }
+
+    #[test]
+    fn parses_conflict_hunks_between_text() {
+        let content = "a\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\nb\n";
+
+        assert_eq!(
+            parse_conflicts(content),
+            vec![
+                ConflictSegment::Text(vec!["a\n".to_string()]),
+                ConflictSegment::Conflict(hunk(&["ours\n"], &["theirs\n"])),
+                ConflictSegment::Text(vec!["b\n".to_string()]),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_the_base_of_diff3_hunks() {
+        let content = "<<<<<<< HEAD\nours\n||||||| base\nbase\n=======\ntheirs\n\
+                       >>>>>>> feature\n";
+
+        let segments = parse_conflicts(content);
+
+        assert_eq!(
+            segments,
+            vec![ConflictSegment::Conflict(ConflictHunk {
+                base: Some(vec!["base\n".to_string()]),
+                original: content.to_string(),
+                ..hunk(&["ours\n"], &["theirs\n"])
+            })]
+        );
+    }
+
+    #[test]
+    fn keeps_hunks_left_open_at_the_end_of_the_file_as_text() {
+        for content in [
+            "a\n<<<<<<< HEAD\nours\n",
+            "a\n<<<<<<< HEAD\nours\n=======\ntheirs",
+            "<<<<<<< HEAD\n||||||| base\nbase\n",
+        ] {
+            let segments = parse_conflicts(content);
+
+            assert_eq!(count_conflicts(&segments), 0, "{:?}", content);
+            assert_eq!(apply_resolutions(&segments, &[]), content);
+        }
+    }
+
+    #[test]
+    fn files_deleted_on_one_side_can_be_kept_or_deleted() {
+        let deleted_by_theirs = ConflictSides {
+            base: true,
+            ours: true,
+            theirs: false,
+        };
+
+        let options = get_file_resolution_options(deleted_by_theirs, true)
+            .into_iter()
+            .map(|(_, resolution)| resolution)
+            .collect::<Vec<FileResolution>>();
+
+        assert_eq!(
+            options,
+            [
+                FileResolution::Ours,
+                FileResolution::Delete,
+                FileResolution::AsIs,
+                FileResolution::Unresolved
+            ]
+        );
+    }
+
+    #[test]
+    fn files_changed_on_both_sides_pick_a_side() {
+        let binary = ConflictSides {
+            base: true,
+            ours: true,
+            theirs: true,
+        };
+
+        let options = get_file_resolution_options(binary, false)
+            .into_iter()
+            .map(|(_, resolution)| resolution)
+            .collect::<Vec<FileResolution>>();
+
+        // Without the file in the working tree, there's nothing to keep as is
+        assert_eq!(
+            options,
+            [
+                FileResolution::Ours,
+                FileResolution::Theirs,
+                FileResolution::Unresolved
+            ]
+        );
+    }
+
+    #[test]
+    fn unresolved_hunks_are_written_back_as_they_were() {
+        let content = "a\n<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> feature\n\
+                       <<<<<<< HEAD\nunclosed\n";
+
+        let segments = parse_conflicts(content);
+
+        assert_eq!(count_conflicts(&segments), 1);
+        assert_eq!(
+            apply_resolutions(&segments, &[Resolution::Unresolved]),
+            content
+        );
+        assert_eq!(
+            apply_resolutions(&segments, &[Resolution::Theirs]),
+            "a\ntheirs\n<<<<<<< HEAD\nunclosed\n"
+        );
+    }
+
+    #[test]
+    fn crlf_hunks_keep_their_markers_and_line_endings() {
+        let content =
+            "a\r\n<<<<<<< HEAD\r\nours\r\n||||||| merged common ancestors\r\n\
+                       base\r\n=======\r\ntheirs\r\n>>>>>>> feature\r\n\
+                       b\r\n<<<<<<< HEAD\r\nmore ours\r\n=======\r\nmore theirs\r\n\
+                       >>>>>>> feature\r\n";
+
+        let mut segments = parse_conflicts(content);
+
+        assert_eq!(count_conflicts(&segments), 2);
+        assert_eq!(
+            apply_resolutions(
+                &segments,
+                &[Resolution::Unresolved, Resolution::Unresolved]
+            ),
+            content
+        );
+
+        // A base filled in for the two-way hunk isn't written back
+        if let ConflictSegment::Conflict(hunk) = &mut segments[3] {
+            hunk.base = Some(vec!["more base\r\n".to_string()]);
+        }
+        assert_eq!(
+            apply_resolutions(
+                &segments,
+                &[Resolution::Ours, Resolution::Unresolved]
+            ),
+            "a\r\nours\r\nb\r\n<<<<<<< HEAD\r\nmore ours\r\n=======\r\n\
+             more theirs\r\n>>>>>>> feature\r\n"
+        );
+    }
+}
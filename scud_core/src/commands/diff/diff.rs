@@ -1,28 +1,36 @@
+use std::path::Path;
+
 use crate::{
     cli::cli::VCS,
     commands::diff::executors::{
-        execute_diff_breezy, execute_diff_git, execute_diff_info,
-        execute_diff_mercurial,
+        execute_diff_breezy, execute_diff_fossil, execute_diff_git,
+        execute_diff_info, execute_diff_jujutsu, execute_diff_mercurial,
+        execute_diff_subversion,
     },
-    helpers::detect_vcs,
+    error::ScudResult,
+    repository::Repository,
     version_control::Diff,
 };
 
-pub fn diff_command(diff_options: Diff) {
+pub fn diff_command(diff_options: Diff, working_dir: &Path) -> ScudResult {
     if diff_options.info {
         execute_diff_info();
+        Ok(())
     } else {
-        execute_diff();
+        execute_diff(working_dir)
     }
 }
 
-pub fn execute_diff() {
-    let vcs = detect_vcs();
+pub fn execute_diff(working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
 
     match vcs {
         VCS::Git => execute_diff_git(),
-        VCS::Mercurial => execute_diff_mercurial(),
-        VCS::Breezy => execute_diff_breezy(),
+        VCS::Mercurial => execute_diff_mercurial(working_dir)?,
+        VCS::Breezy => execute_diff_breezy(working_dir)?,
+        VCS::Subversion => execute_diff_subversion(working_dir)?,
+        VCS::Jujutsu => execute_diff_jujutsu(working_dir)?,
+        VCS::Fossil => execute_diff_fossil(working_dir)?,
     }
 
     // TODO look at adding diagnostic here
@@ -31,10 +39,12 @@ pub fn execute_diff() {
     //     body: &format!(
     //         "{} {}{}{} {}",
     //         "Use".yellow(),
-    //         bright_yellow_backtick(),
+    //         backtick(),
     //         "scud commit".green().italic(),
-    //         bright_yellow_backtick(),
+    //         backtick(),
     //         "to commit any staged changes".yellow()
     //     ),
     // });
+
+    Ok(())
 }
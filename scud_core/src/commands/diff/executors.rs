@@ -1,9 +1,77 @@
-use crate::diagnostics::{log_diagnostic, DiagnosticKind};
+use std::path::Path;
 
-pub fn execute_diff_breezy() {
-    log_diagnostic(DiagnosticKind::WorkInProgress {
-        feature: "diff (breezy)",
-    });
+use colored::Colorize;
+
+use crate::{
+    cli::cli::VCS,
+    commands::hooks::helpers::get_repository_root,
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::ScudResult,
+    runner::ExternalCommand,
+};
+
+/// Shows the changes of the working tree since the last commit with
+/// `brz diff`, colouring the lines the way git does.
+pub fn execute_diff_breezy(working_dir: &Path) -> ScudResult {
+    let output = ExternalCommand::brz(["diff"])
+        .current_dir(working_dir)
+        .output()?;
+
+    // brz diff exits with 1 when there are changes, and only fails above that
+    let output = if output.status == Some(1) {
+        output
+    } else {
+        output.check()?
+    };
+    print_colored_diff(&output.stdout);
+
+    Ok(())
+}
+
+/// Shows the changes of the working copy since the last update with
+/// `svn diff`, colouring the lines the way git does.
+pub fn execute_diff_subversion(working_dir: &Path) -> ScudResult {
+    // svn diff only shows the changes below the directory it's run from
+    let output = ExternalCommand::svn(["diff"])
+        .current_dir(get_repository_root(&VCS::Subversion, working_dir))
+        .run()?;
+    print_colored_diff(&output.stdout);
+
+    Ok(())
+}
+
+/// Shows the changes of the checkout since the last commit with
+/// `fossil diff`, colouring the lines the way git does.
+pub fn execute_diff_fossil(working_dir: &Path) -> ScudResult {
+    // --internal so the output is a unified diff even with a diff-command set
+    let output = ExternalCommand::fossil(["diff", "--internal"])
+        .current_dir(get_repository_root(&VCS::Fossil, working_dir))
+        .run()?;
+    print_colored_diff(&output.stdout);
+
+    Ok(())
+}
+
+/// Prints a unified diff from a VCS that can't colour its own, colouring the
+/// lines the way git does.
+fn print_colored_diff(diff: &str) {
+    for line in diff.lines() {
+        if line.starts_with("+++")
+            || line.starts_with("---")
+            || line.starts_with("===")
+            || line.starts_with("Index: ")
+        {
+            println!("{}", line.bold());
+        } else if line.starts_with('+') {
+            println!("{}", line.green());
+        } else if line.starts_with('-') {
+            println!("{}", line.red());
+        } else if line.starts_with("@@") {
+            println!("{}", line.cyan());
+        } else {
+            println!("{}", line);
+        }
+    }
 }
 
 pub fn execute_diff_dry_run() {
@@ -24,8 +92,29 @@ pub fn execute_diff_info() {
     });
 }
 
-pub fn execute_diff_mercurial() {
-    log_diagnostic(DiagnosticKind::WorkInProgress {
-        feature: "diff (mercurial)",
-    });
+/// Shows the changes of the working directory since the last commit with
+/// `hg diff`, coloured unless scud's output isn't.
+pub fn execute_diff_mercurial(working_dir: &Path) -> ScudResult {
+    // Not HGPLAIN, so the diff follows the user's settings (e.g. diff.git)
+    ExternalCommand::new("hg")
+        .current_dir(working_dir)
+        .args(["diff", "--color", color_when()])
+        .run_attached()
+}
+
+/// Shows the changes of the working-copy commit with `jj diff`, coloured
+/// unless scud's output isn't.
+pub fn execute_diff_jujutsu(working_dir: &Path) -> ScudResult {
+    ExternalCommand::jj(["diff", "--color", color_when()])
+        .current_dir(working_dir)
+        .run_attached()
+}
+
+/// The `--color` of VCSs colouring their own diffs, following scud's output.
+fn color_when() -> &'static str {
+    if colored::control::SHOULD_COLORIZE.should_colorize() {
+        "always"
+    } else {
+        "never"
+    }
 }
@@ -1,3 +1,4 @@
 pub mod healthcheck;
 pub mod helpers;
 pub mod logging;
+pub mod repository;
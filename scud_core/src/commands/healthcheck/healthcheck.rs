@@ -1,45 +1,56 @@
-use colored::Colorize;
+use std::path::Path;
 
-use crate::commands::healthcheck::{
-    helpers::{
-        healthcheck_sc_github, healthcheck_sc_gitlab, healthcheck_vcs_brz,
-        healthcheck_vcs_git, healthcheck_vcs_hg,
+use crate::{
+    cli::cli::{Healthcheck, OutputFormat},
+    commands::healthcheck::{
+        helpers::{healthchecks_to_json, is_healthy, run_system_healthchecks},
+        logging::{
+            log_healthcheck_hints, log_healthcheck_note, log_healthcheck_summary,
+            log_healthcheck_table,
+        },
+        repository::run_repository_healthchecks,
     },
-    logging::log_healthcheck_note,
+    error::{ScudError, ScudResult},
 };
 
-pub fn healthcheck_command() {
-    log_healthcheck_note();
+/// Executes the healthcheck command with the given options, failing when a
+/// required check fails so CI can gate on the exit code.
+///
+/// Arguments:
+///
+/// * `healthcheck_options`: Struct that contains the options that were passed
+///   to the command via user input (e.g. format, repository).
+/// * `working_dir`: The directory to operate on, which is the current one
+///   unless given with --directory.
+pub fn healthcheck_command(
+    healthcheck_options: Healthcheck,
+    working_dir: &Path,
+) -> ScudResult {
+    let (title, checks) = if healthcheck_options.repo {
+        (
+            "REPOSITORY HEALTHCHECK:",
+            run_repository_healthchecks(working_dir),
+        )
+    } else {
+        ("HEALTHCHECK:", run_system_healthchecks(working_dir))
+    };
 
-    // Check for installations of VCSs
-    execute_healthcheck_vcs();
+    match healthcheck_options.format {
+        OutputFormat::Json => {
+            println!("{}", healthchecks_to_json(&checks));
+        }
+        OutputFormat::Text => {
+            log_healthcheck_note();
+            log_healthcheck_table(title, &checks);
+            log_healthcheck_hints(&checks);
+            log_healthcheck_summary(&checks);
+        }
+    }
 
-    // Check for installations of CLIs for source control providers
-    execute_healthcheck_source_control();
-}
-
-pub fn execute_healthcheck_vcs() {
-    println!(
-        "{}{}\n",
-        "Version Control Systems: ".bright_yellow(),
-        "(Git, Mercurial, and Breezy are currently supported)"
-            .blue()
-            .italic()
-    );
-    healthcheck_vcs_git();
-    healthcheck_vcs_hg();
-    healthcheck_vcs_brz();
-}
+    // The failed checks were already reported in the table (or document)
+    if !is_healthy(&checks) {
+        return Err(ScudError::Reported { exit_code: 1 });
+    }
 
-pub fn execute_healthcheck_source_control() {
-    println!(
-        "\n{}{}",
-        "Source Control Providers:".bright_yellow(),
-        " (GitHub, GitLab are currently supported)".blue().italic()
-    );
-    healthcheck_sc_github();
-    healthcheck_sc_gitlab();
+    Ok(())
 }
-
-// TODO print after all are successful
-// println!("{}", "All supported Version Control Systems are healthy".green());
@@ -1,157 +1,386 @@
-use std::process::Command;
+use std::path::Path;
 
-use colored::Colorize;
+use crate::runner::ExternalCommand;
 
-use crate::diagnostics::{log_diagnostic, DiagnosticKind};
+///////////////////////////////////////////
+// Structured results of the healthcheck //
+///////////////////////////////////////////
 
-////////////////////////////////////
-// Version Control System helpers //
-////////////////////////////////////
-
-pub fn healthcheck_vcs_git() {
-    println!("{}", "Git:".bright_green());
+/// Outcome of a single healthcheck.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthStatus {
+    Pass,
+    Warn,
+    Fail,
+}
 
-    // TODO refactor to use log_diagnostic
-    match Command::new("git").arg("--version").output() {
-        Ok(output) => {
-            let git_version = String::from_utf8_lossy(&output.stdout).to_string();
-            print!("\n{}", git_version.black().italic());
-        }
-        Err(_) => {
-            println!(
-                "\n{}{}{}{}{}{}",
-                " ERROR ".on_red(),
-                " Git is not installed on system locally or not found in PATH.\n\n"
-                    .bright_red()
-                    .italic(),
-                "Hint: ".green().bold(),
-                "Try installing it with scud's built-in setup command `".yellow(),
-                "scud setup vcs git".italic().green(),
-                "`.".yellow()
-            );
+impl HealthStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HealthStatus::Pass => "pass",
+            HealthStatus::Warn => "warn",
+            HealthStatus::Fail => "fail",
         }
     }
 }
 
-pub fn healthcheck_vcs_hg() {
-    println!("\n{}", "Mercurial:".bright_green());
-
-    // TODO refactor to use log_diagnostic
+/// The result of a single healthcheck.
+///
+/// Properties:
+///
+/// * `name`: Name of what was checked (e.g. `git`, `user.email`).
+/// * `category`: Group the check belongs to (e.g. `vcs`, `config`).
+/// * `required`: Whether scud can't work properly if the check fails.
+///   Failing optional checks only result in a warning.
+/// * `status`: Whether the check passed, warned or failed.
+/// * `version`: The version found, for tools.
+/// * `minimum_version`: The minimum version scud supports, for tools.
+/// * `message`: Short description of the outcome.
+/// * `hint`: Description and scud command fixing the problem, if any.
+#[derive(Debug, Clone)]
+pub struct HealthCheck {
+    pub name: String,
+    pub category: &'static str,
+    pub required: bool,
+    pub status: HealthStatus,
+    pub version: Option<String>,
+    pub minimum_version: Option<String>,
+    pub message: String,
+    pub hint: Option<(String, String)>,
+}
 
-    match Command::new("hg").arg("--version").output() {
-        Ok(output) => {
-            let hg_version = String::from_utf8_lossy(&output.stdout).to_string();
-            println!(
-                "\n{}",
-                hg_version.split("\n").next().unwrap().italic().black()
-            );
+impl HealthCheck {
+    fn passed(
+        name: &str,
+        category: &'static str,
+        required: bool,
+        message: &str,
+    ) -> Self {
+        HealthCheck {
+            name: name.to_string(),
+            category,
+            required,
+            status: HealthStatus::Pass,
+            version: None,
+            minimum_version: None,
+            message: message.to_string(),
+            hint: None,
         }
-        Err(_) => {
-            println!("\n{}{}{}{}{}{}", " ERROR ".on_red(), " Mercurial is not installed on system locally or not found in PATH.\n\n".bright_red().italic(), "Hint: ".green().italic(), "If you wish to use Mercurial for your underlying VCS with scud, try installing it on your local machine with scud's builtin setup command `".yellow(), "scud setup vcs mercurial".italic().green(), "`.".yellow());
+    }
+
+    /// A failed check, downgraded to a warning when it isn't required.
+    fn failed(
+        name: &str,
+        category: &'static str,
+        required: bool,
+        message: &str,
+        hint: (&str, &str),
+    ) -> Self {
+        HealthCheck {
+            status: if required {
+                HealthStatus::Fail
+            } else {
+                HealthStatus::Warn
+            },
+            hint: Some((hint.0.to_string(), hint.1.to_string())),
+            ..HealthCheck::passed(name, category, required, message)
         }
     }
 }
 
-pub fn healthcheck_vcs_brz() {
-    println!("\n{}", "Breezy:".bright_green());
+/// Whether none of the given checks failed.
+pub fn is_healthy(checks: &[HealthCheck]) -> bool {
+    checks
+        .iter()
+        .all(|check| check.status != HealthStatus::Fail)
+}
+
+/////////////////////////////////////////////
+// Tools (version control systems and CLIs //
+// for source control providers)           //
+/////////////////////////////////////////////
+
+/// A tool scud relies on.
+///
+/// Properties:
+///
+/// * `name`: Display name of the tool.
+/// * `program`: Name of the executable.
+/// * `alternative_programs`: Other names the executable is installed under
+/// (e.g. bzr for Breezy).
+/// * `category`: `vcs` or `source-control`.
+/// * `required`: Whether scud can't work properly without it.
+/// * `minimum_version`: Oldest supported version, if any.
+/// * `setup_command`: scud command installing the tool.
+pub struct Tool {
+    pub name: &'static str,
+    pub program: &'static str,
+    pub alternative_programs: &'static [&'static str],
+    pub category: &'static str,
+    pub required: bool,
+    pub minimum_version: Option<&'static str>,
+    pub setup_command: &'static str,
+}
+
+pub const TOOLS: &[Tool] = &[
+    Tool {
+        name: "Git",
+        program: "git",
+        alternative_programs: &[],
+        category: "vcs",
+        required: true,
+        // `git switch` and `git restore` were added in 2.23
+        minimum_version: Some("2.23.0"),
+        setup_command: "scud setup vcs git",
+    },
+    Tool {
+        name: "Mercurial",
+        program: "hg",
+        alternative_programs: &[],
+        category: "vcs",
+        required: false,
+        minimum_version: None,
+        setup_command: "scud setup vcs mercurial",
+    },
+    Tool {
+        name: "Breezy",
+        program: "brz",
+        alternative_programs: &["bzr"],
+        category: "vcs",
+        required: false,
+        minimum_version: None,
+        setup_command: "scud setup vcs breezy",
+    },
+    Tool {
+        name: "Subversion",
+        program: "svn",
+        alternative_programs: &[],
+        category: "vcs",
+        required: false,
+        minimum_version: None,
+        setup_command: "scud setup vcs subversion",
+    },
+    Tool {
+        name: "Jujutsu",
+        program: "jj",
+        alternative_programs: &[],
+        category: "vcs",
+        required: false,
+        minimum_version: None,
+        setup_command: "scud setup vcs jujutsu",
+    },
+    Tool {
+        name: "Fossil",
+        program: "fossil",
+        alternative_programs: &[],
+        category: "vcs",
+        required: false,
+        minimum_version: None,
+        setup_command: "scud setup vcs fossil",
+    },
+    Tool {
+        name: "GitHub CLI",
+        program: "gh",
+        alternative_programs: &[],
+        category: "source-control",
+        required: false,
+        minimum_version: None,
+        setup_command: "scud setup sc github",
+    },
+    Tool {
+        name: "GitLab CLI",
+        program: "glab",
+        alternative_programs: &[],
+        category: "source-control",
+        required: false,
+        minimum_version: None,
+        setup_command: "scud setup sc gitlab",
+    },
+];
+
+/// Checks that the tool is installed and at least at its minimum version.
+pub fn check_tool(tool: &Tool) -> HealthCheck {
+    let hint_body = format!(
+        "Try installing {} with scud's built-in setup command",
+        tool.name
+    );
 
-    // TODO refactor to use log_diagnostic
+    // Whichever of the names the tool goes by is installed
+    let installed = std::iter::once(tool.program)
+        .chain(tool.alternative_programs.iter().copied())
+        .find_map(|program| ExternalCommand::new(program).arg("--version").stdout());
 
-    match Command::new("brz").arg("--version").output() {
-        Ok(output) => {
-            let brz_version = String::from_utf8_lossy(&output.stdout).to_string();
-            println!(
-                "\n{}",
-                brz_version.split("\n").next().unwrap().italic().black()
+    let output = match installed {
+        Some(output) => output,
+        None => {
+            return HealthCheck::failed(
+                tool.program,
+                tool.category,
+                tool.required,
+                "not installed or not found in PATH",
+                (&hint_body, tool.setup_command),
             );
         }
-        Err(_) => {
-            println!(
-                "\n{}{}{}{}{}{}",
-                " ERROR ".on_red(),
-                " Breezy is not installed on system locally or not found in PATH.\n\n"
-                    .bright_red()
-                    .italic(),
-                "Hint: ".bright_green(),
-                "Try installing it with scud's builtin setup command `".yellow(),
-                "scud setup vcs breezy".italic().green(),
-                "`.".yellow()
+    };
+
+    let version_line = output.lines().next().unwrap_or_default().to_string();
+    let version = parse_version(&version_line);
+
+    let mut check = HealthCheck::passed(
+        tool.program,
+        tool.category,
+        tool.required,
+        &version_line,
+    );
+    check.version = version
+        .map(|(major, minor, patch)| format!("{}.{}.{}", major, minor, patch));
+    check.minimum_version = tool.minimum_version.map(|minimum| minimum.to_string());
+
+    if let (Some(version), Some(minimum)) =
+        (version, tool.minimum_version.and_then(parse_version))
+    {
+        if version < minimum {
+            let message = format!(
+                "version {} is older than the minimum supported version {}",
+                check.version.clone().unwrap_or_default(),
+                check.minimum_version.clone().unwrap_or_default()
             );
+            let hint_body = format!(
+                "Try upgrading {} with scud's built-in setup command",
+                tool.name
+            );
+
+            return HealthCheck {
+                version: check.version,
+                minimum_version: check.minimum_version,
+                ..HealthCheck::failed(
+                    tool.program,
+                    tool.category,
+                    tool.required,
+                    &message,
+                    (&hint_body, tool.setup_command),
+                )
+            };
         }
     }
+
+    check
 }
 
-////////////////////////////
-// Source Control helpers //
-////////////////////////////
+/// Extracts the first `major.minor[.patch]` version from a `--version` line
+/// (e.g. `git version 2.39.2 (Apple Git-143)` or `Mercurial Distributed SCM
+/// (version 6.3.2)`).
+pub fn parse_version(line: &str) -> Option<(u64, u64, u64)> {
+    line.split(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .filter(|token| token.contains('.'))
+        .find_map(|token| {
+            let mut parts = token.split('.').map(|part| part.parse::<u64>());
+            let major = parts.next()?.ok()?;
+            let minor = parts.next()?.ok()?;
+            let patch = parts.next().and_then(|patch| patch.ok()).unwrap_or(0);
+            Some((major, minor, patch))
+        })
+}
 
-pub fn healthcheck_sc_github() {
-    println!("\n{}", "GitHub:".bright_green());
+/////////////////////////
+// Configuration       //
+/////////////////////////
 
-    match Command::new("gh").arg("--version").output() {
-        Ok(output) => {
-            let github_version = String::from_utf8_lossy(&output.stdout).to_string();
-            println!(
-                "\n{}",
-                github_version.split("\n").next().unwrap().italic().black()
-            );
-        }
-        Err(_) => {
-            log_diagnostic(DiagnosticKind::Error {
-                subject: "Github CLI",
-                body: "is not installed on system locally or not found in PATH.",
-            });
-
-            log_diagnostic(
-                    DiagnosticKind::Hint {
-                        body: &format!(
-                            "{}{}{}",
-                            "If you wish to use "
-                                .yellow(),
-                            "GitHub"
-                                .bright_yellow()
-                                .italic(),
-                            " for your underlying source control provider within scud, try installing it with scud's built-in setup command "
-                                .yellow(),
-                        ),
-                        command: "scud setup sc github"
-                    }
-                );
-        }
+/// Checks that git knows who the author of commits is.
+pub fn check_identity_git(working_dir: &Path) -> Vec<HealthCheck> {
+    ["user.name", "user.email"]
+        .iter()
+        .map(|key| {
+            let value = ExternalCommand::git(["config", "--get", key])
+                .current_dir(working_dir)
+                .stdout()
+                .unwrap_or_default();
+
+            if value.is_empty() {
+                HealthCheck::failed(
+                    key,
+                    "config",
+                    true,
+                    "not set, git refuses to commit without it",
+                    ("Configure your identity with", "scud setup identity"),
+                )
+            } else {
+                HealthCheck::passed(key, "config", true, &value)
+            }
+        })
+        .collect()
+}
+
+/// Runs every check ensuring the system is set up for scud: the tools it
+/// relies on and the configuration of git.
+pub fn run_system_healthchecks(working_dir: &Path) -> Vec<HealthCheck> {
+    let mut checks = TOOLS.iter().map(check_tool).collect::<Vec<HealthCheck>>();
+
+    let git_installed = checks
+        .iter()
+        .any(|check| check.name == "git" && check.version.is_some());
+
+    if git_installed {
+        checks.extend(check_identity_git(working_dir));
     }
+
+    checks
 }
 
-pub fn healthcheck_sc_gitlab() {
-    println!("\n{}", "GitLab:".green().bold());
+//////////////////////
+// JSON output      //
+//////////////////////
 
-    match Command::new("glab").arg("--version").output() {
-        Ok(output) => {
-            let gitlab_version = String::from_utf8_lossy(&output.stdout).to_string();
-            println!(
-                "\n{}\n",
-                gitlab_version.split("\n").next().unwrap().italic().black()
-            );
-        }
-        Err(_) => {
-            log_diagnostic(DiagnosticKind::Error {
-                subject: "GitLab CLI",
-                body: "is not installed on system locally or not found in PATH.",
-            });
-            log_diagnostic(
-                    DiagnosticKind::Hint {
-                        body: &format!(
-                            "{}{}{}",
-                            "If you wish to use "
-                                .yellow(),
-                            "GitLab"
-                                .bright_yellow()
-                                .italic(),
-                            " for your underlying source control provider within scud, try installing it with scud's built-in setup command"
-                                .yellow(),
-                        ),
-                        command: "scud setup sc gitlab"
-                    }
-                );
+/// Serializes the checks into a JSON document for consumption by CI.
+pub fn healthchecks_to_json(checks: &[HealthCheck]) -> String {
+    let optional = |value: &Option<String>| match value {
+        Some(value) => json_string(value),
+        None => "null".to_string(),
+    };
+
+    let checks_json = checks
+        .iter()
+        .map(|check| {
+            format!(
+                "    {{\"name\": {}, \"category\": {}, \"required\": {}, \"status\": {}, \
+                 \"version\": {}, \"minimum_version\": {}, \"message\": {}, \"fix\": {}}}",
+                json_string(&check.name),
+                json_string(check.category),
+                check.required,
+                json_string(check.status.as_str()),
+                optional(&check.version),
+                optional(&check.minimum_version),
+                json_string(&check.message),
+                optional(&check.hint.as_ref().map(|(_, command)| command.clone())),
+            )
+        })
+        .collect::<Vec<String>>()
+        .join(",\n");
+
+    format!(
+        "{{\n  \"healthy\": {},\n  \"checks\": [\n{}\n  ]\n}}",
+        is_healthy(checks),
+        checks_json
+    )
+}
+
+fn json_string(value: &str) -> String {
+    let mut escaped = String::from("\"");
+
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                escaped.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => escaped.push(c),
         }
     }
+
+    escaped.push('"');
+    escaped
 }
@@ -1,4 +1,10 @@
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
+
+use crate::{
+    logging::theme::Themed,
+    commands::healthcheck::helpers::{is_healthy, HealthCheck, HealthStatus},
+    diagnostics::{log_diagnostic, DiagnosticKind},
+};
 
 pub fn log_healthcheck_note() {
     const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -15,3 +21,119 @@ pub fn log_healthcheck_note() {
             .italic()
     );
 }
+
+fn status_badge(status: HealthStatus) -> ColoredString {
+    match status {
+        HealthStatus::Pass => " PASS ".black().on_bright_green(),
+        HealthStatus::Warn => " WARN ".black().on_bright_yellow(),
+        HealthStatus::Fail => " FAIL ".black().on_bright_red(),
+    }
+}
+
+/// Logs the checks as a table with one row per check, grouped under a
+/// heading per category.
+pub fn log_healthcheck_table(title: &str, checks: &[HealthCheck]) {
+    let name_width = checks
+        .iter()
+        .map(|check| check.name.len())
+        .max()
+        .unwrap_or_default();
+
+    println!("{}\n", format!(" {} ", title).black().on_cyan());
+
+    let mut category = "";
+
+    for check in checks {
+        if check.category != category {
+            category = check.category;
+            println!("  {}", category_heading(category).bright_yellow());
+        }
+
+        println!(
+            "    {}  {:width$}  {}  {}",
+            status_badge(check.status),
+            check.name,
+            if check.required {
+                "required".cyan().italic()
+            } else {
+                "optional".muted().italic()
+            },
+            match check.status {
+                HealthStatus::Pass => check.message.muted().italic(),
+                HealthStatus::Warn => check.message.yellow().italic(),
+                HealthStatus::Fail => check.message.bright_red().italic(),
+            },
+            width = name_width
+        );
+    }
+
+    println!();
+}
+
+fn category_heading(category: &str) -> &str {
+    match category {
+        "vcs" => "Version Control Systems:",
+        "source-control" => "Source Control Providers:",
+        "config" => "Configuration:",
+        "repository" => "Repository:",
+        category => category,
+    }
+}
+
+/// Logs a hint for each failed or warned check naming the scud command which
+/// fixes it.
+pub fn log_healthcheck_hints(checks: &[HealthCheck]) {
+    for check in checks {
+        if let Some((body, command)) = &check.hint {
+            log_diagnostic(DiagnosticKind::Hint {
+                body: &format!(
+                    "{} {}",
+                    format!("{}:", check.name).cyan(),
+                    body.yellow()
+                ),
+                command,
+            });
+        }
+    }
+}
+
+/// Logs how many checks passed, warned and failed.
+pub fn log_healthcheck_summary(checks: &[HealthCheck]) {
+    let count = |status: HealthStatus| {
+        checks.iter().filter(|check| check.status == status).count()
+    };
+
+    // Optional systems which aren't installed don't make the others unhealthy
+    let mut vcs_checks = checks
+        .iter()
+        .filter(|check| check.category == "vcs")
+        .filter(|check| {
+            check.version.is_some() || check.status == HealthStatus::Fail
+        })
+        .peekable();
+    let vcs_healthy = vcs_checks.peek().is_some()
+        && vcs_checks.all(|check| check.status == HealthStatus::Pass);
+
+    if vcs_healthy {
+        println!(
+            "{}\n",
+            "All supported Version Control Systems are healthy".green()
+        );
+    }
+
+    println!(
+        "{} {}, {}, {}\n",
+        "Summary:".bright_yellow(),
+        format!("{} passed", count(HealthStatus::Pass)).bright_green(),
+        format!("{} warning(s)", count(HealthStatus::Warn)).yellow(),
+        format!("{} failed", count(HealthStatus::Fail)).bright_red(),
+    );
+
+    if !is_healthy(checks) {
+        log_diagnostic(DiagnosticKind::Error {
+            subject: "Healthcheck failed",
+            body: "Some of the required checks failed, see the hints above for \
+                   how to fix them.",
+        });
+    }
+}
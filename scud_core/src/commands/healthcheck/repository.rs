@@ -0,0 +1,437 @@
+use std::{path::Path, time::Duration};
+
+use crate::{
+    commands::{
+        commit::checks::PreCommitConfig,
+        conflicts::helpers::{detect_conflict_operation_git, ConflictOperation},
+        healthcheck::helpers::{check_identity_git, HealthCheck, HealthStatus},
+    },
+    runner::ExternalCommand,
+};
+
+/////////////////////////////////////////////////////
+// Checks diagnosing the current repository (git)  //
+/////////////////////////////////////////////////////
+
+/// How long to wait for a remote to answer when looking for stale
+/// remote-tracking refs.
+const REMOTE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Number of offending files listed in the message of a check.
+const LISTED_FILES: usize = 3;
+
+fn git_output(args: &[&str], working_dir: &Path) -> Option<String> {
+    ExternalCommand::git(args).current_dir(working_dir).stdout()
+}
+
+fn passed(name: &str, message: &str) -> HealthCheck {
+    HealthCheck {
+        name: name.to_string(),
+        category: "repository",
+        required: false,
+        status: HealthStatus::Pass,
+        version: None,
+        minimum_version: None,
+        message: message.to_string(),
+        hint: None,
+    }
+}
+
+fn problem(
+    name: &str,
+    status: HealthStatus,
+    message: &str,
+    hint: (&str, &str),
+) -> HealthCheck {
+    HealthCheck {
+        required: status == HealthStatus::Fail,
+        status,
+        hint: Some((hint.0.to_string(), hint.1.to_string())),
+        ..passed(name, message)
+    }
+}
+
+/// Lists the first few files, followed by how many were left out.
+fn list_files(files: &[&str]) -> String {
+    let mut listed = files
+        .iter()
+        .take(LISTED_FILES)
+        .copied()
+        .collect::<Vec<&str>>()
+        .join(", ");
+
+    if files.len() > LISTED_FILES {
+        listed.push_str(&format!(" and {} more", files.len() - LISTED_FILES));
+    }
+
+    listed
+}
+
+/// Runs every check diagnosing the repository of the current directory.
+/// Only git repositories are diagnosed in depth so far.
+pub fn run_repository_healthchecks(working_dir: &Path) -> Vec<HealthCheck> {
+    if git_output(&["rev-parse", "--is-inside-work-tree"], working_dir).as_deref()
+        == Some("true")
+    {
+        return run_repository_healthchecks_git(working_dir);
+    }
+
+    let other_vcs = [
+        (
+            "Mercurial",
+            ExternalCommand::hg(["root"]).current_dir(working_dir),
+        ),
+        (
+            "Breezy",
+            ExternalCommand::brz(["root"]).current_dir(working_dir),
+        ),
+        (
+            "Subversion",
+            ExternalCommand::svn(["info", "--show-item", "wc-root"])
+                .current_dir(working_dir),
+        ),
+        (
+            "Jujutsu",
+            ExternalCommand::jj(["root"]).current_dir(working_dir),
+        ),
+        (
+            "Fossil",
+            ExternalCommand::fossil(["status"]).current_dir(working_dir),
+        ),
+    ]
+    .into_iter()
+    .find(|(_, root_command)| root_command.stdout().is_some());
+
+    match other_vcs {
+        Some((name, _)) => vec![HealthCheck {
+            status: HealthStatus::Warn,
+            ..passed(
+                "repository",
+                &format!("repository checks aren't supported for {} yet", name),
+            )
+        }],
+        None => vec![problem(
+            "repository",
+            HealthStatus::Fail,
+            "not inside a repository",
+            ("Initialize a repository with", "scud init"),
+        )],
+    }
+}
+
+/// Runs every check diagnosing the current git repository.
+pub fn run_repository_healthchecks_git(working_dir: &Path) -> Vec<HealthCheck> {
+    let mut checks = vec![
+        check_head(working_dir),
+        check_upstream(working_dir),
+        check_operation_in_progress(working_dir),
+        check_shallow_clone(working_dir),
+        check_tracked_ignored_files(working_dir),
+        check_large_blobs(working_dir),
+        check_lfs_pointers(working_dir),
+    ];
+    checks.extend(check_stale_remote_refs(working_dir));
+    checks.extend(check_identity_git(working_dir));
+
+    checks
+}
+
+fn check_head(working_dir: &Path) -> HealthCheck {
+    match git_output(&["symbolic-ref", "--quiet", "--short", "HEAD"], working_dir) {
+        Some(branch) => passed("HEAD", &format!("on branch {}", branch)),
+        None => problem(
+            "HEAD",
+            HealthStatus::Warn,
+            "detached HEAD, new commits won't belong to any branch",
+            (
+                "scud can't create branches yet, create one for the current \
+                 commit with",
+                "git switch -c <branch>",
+            ),
+        ),
+    }
+}
+
+fn check_upstream(working_dir: &Path) -> HealthCheck {
+    let branch = match git_output(
+        &["symbolic-ref", "--quiet", "--short", "HEAD"],
+        working_dir,
+    ) {
+        Some(branch) => branch,
+        None => return passed("upstream", "skipped (detached HEAD)"),
+    };
+
+    if git_output(&["remote"], working_dir)
+        .unwrap_or_default()
+        .is_empty()
+    {
+        return problem(
+            "upstream",
+            HealthStatus::Warn,
+            "no remotes configured",
+            (
+                "scud can't add remotes yet, add one to push to with",
+                "git remote add origin <url>",
+            ),
+        );
+    }
+
+    match git_output(
+        &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"],
+        working_dir,
+    ) {
+        Some(upstream) => {
+            passed("upstream", &format!("{} tracks {}", branch, upstream))
+        }
+        None => problem(
+            "upstream",
+            HealthStatus::Warn,
+            &format!("{} has no upstream branch", branch),
+            ("Push the branch and set its upstream with", "scud push"),
+        ),
+    }
+}
+
+fn check_operation_in_progress(working_dir: &Path) -> HealthCheck {
+    let operation = match detect_conflict_operation_git(working_dir) {
+        ConflictOperation::Merge { branch } => format!("merge of {}", branch),
+        ConflictOperation::Rebase { branch, onto, .. } => {
+            format!("rebase of {} onto {}", branch, onto)
+        }
+        ConflictOperation::CherryPick { commit } => {
+            format!("cherry-pick of {}", commit)
+        }
+        ConflictOperation::Revert { commit } => format!("revert of {}", commit),
+        ConflictOperation::Unknown => {
+            return passed(
+                "operation",
+                "no merge, rebase, cherry-pick or revert in progress",
+            )
+        }
+    };
+
+    problem(
+        "operation",
+        HealthStatus::Warn,
+        &format!("{} in progress", operation),
+        ("Resolve any conflicts and finish it with", "scud conflicts"),
+    )
+}
+
+fn check_shallow_clone(working_dir: &Path) -> HealthCheck {
+    if git_output(&["rev-parse", "--is-shallow-repository"], working_dir).as_deref()
+        == Some("true")
+    {
+        problem(
+            "shallow",
+            HealthStatus::Warn,
+            "shallow clone, history is incomplete (e.g. for blame or merges)",
+            (
+                "scud pull only fetches new commits, fetch the complete history \
+                 with",
+                "git fetch --unshallow",
+            ),
+        )
+    } else {
+        passed("shallow", "complete history")
+    }
+}
+
+fn check_tracked_ignored_files(working_dir: &Path) -> HealthCheck {
+    let ignored = git_output(
+        &["ls-files", "--cached", "--ignored", "--exclude-standard"],
+        working_dir,
+    )
+    .unwrap_or_default();
+    let ignored = ignored.lines().collect::<Vec<&str>>();
+
+    if ignored.is_empty() {
+        return passed("ignored", "no tracked files match .gitignore");
+    }
+
+    problem(
+        "ignored",
+        HealthStatus::Warn,
+        &format!(
+            "{} tracked file(s) match .gitignore: {}",
+            ignored.len(),
+            list_files(&ignored)
+        ),
+        (
+            "scud unstage keeps files tracked, stop tracking them (keeping them \
+             on disk) with",
+            "git rm --cached <file>",
+        ),
+    )
+}
+
+/// Finds blobs anywhere in the history above the size limit of the
+/// pre-commit checks (`SCUD_MAX_FILE_SIZE`).
+fn check_large_blobs(working_dir: &Path) -> HealthCheck {
+    let max_file_size = PreCommitConfig::from_env().max_file_size;
+
+    let objects = git_output(&["rev-list", "--objects", "--all"], working_dir)
+        .unwrap_or_default();
+
+    let sizes = ExternalCommand::git([
+        "cat-file",
+        "--batch-check=%(objecttype) %(objectsize) %(rest)",
+    ])
+    .current_dir(working_dir)
+    .stdin(objects)
+    .stdout()
+    .unwrap_or_default();
+
+    let mut large_blobs = sizes
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, ' ');
+            match (fields.next(), fields.next(), fields.next()) {
+                (Some("blob"), Some(size), Some(path)) => {
+                    let size = size.parse::<u64>().ok()?;
+                    (size > max_file_size).then(|| path.to_string())
+                }
+                _ => None,
+            }
+        })
+        .collect::<Vec<String>>();
+    large_blobs.sort();
+    large_blobs.dedup();
+
+    if large_blobs.is_empty() {
+        return passed(
+            "large blobs",
+            &format!("no blobs over {} bytes in history", max_file_size),
+        );
+    }
+
+    let large_blobs = large_blobs
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<&str>>();
+
+    problem(
+        "large blobs",
+        HealthStatus::Warn,
+        &format!(
+            "{} file(s) over {} bytes in history: {}",
+            large_blobs.len(),
+            max_file_size,
+            list_files(&large_blobs)
+        ),
+        (
+            "scud can't rewrite history, move them to Git LFS (rewriting \
+             history) with",
+            "git lfs migrate import --include=<path>",
+        ),
+    )
+}
+
+/// Checks that files tracked by Git LFS contain their actual content instead
+/// of the pointers git stores.
+fn check_lfs_pointers(working_dir: &Path) -> HealthCheck {
+    let uses_lfs = git_output(
+        &["ls-files", "--", ".gitattributes", "**/.gitattributes"],
+        working_dir,
+    )
+    .unwrap_or_default()
+    .lines()
+    .any(|attributes| {
+        std::fs::read_to_string(working_dir.join(attributes))
+            .map(|content| content.contains("filter=lfs"))
+            .unwrap_or(false)
+    });
+
+    if !uses_lfs {
+        return passed("lfs", "repository doesn't use Git LFS");
+    }
+
+    let lfs_files =
+        match git_output(&["lfs", "ls-files"], working_dir) {
+            Some(lfs_files) => lfs_files,
+            None => return problem(
+                "lfs",
+                HealthStatus::Warn,
+                "repository uses Git LFS but git-lfs isn't installed, LFS files \
+                 are checked out as pointers",
+                (
+                    "scud setup can't install git-lfs yet, install it and then \
+                     fetch the files with",
+                    "git lfs pull",
+                ),
+            ),
+        };
+
+    // `<oid> - <path>` marks files which are still pointers, `<oid> * <path>`
+    // files whose content is checked out
+    let pointers = lfs_files
+        .lines()
+        .filter_map(|line| line.split_once(" - ").map(|(_, path)| path))
+        .collect::<Vec<&str>>();
+
+    if pointers.is_empty() {
+        return passed("lfs", "all Git LFS files are checked out");
+    }
+
+    problem(
+        "lfs",
+        HealthStatus::Warn,
+        &format!(
+            "{} Git LFS file(s) are pointers: {}",
+            pointers.len(),
+            list_files(&pointers)
+        ),
+        (
+            "scud pull doesn't check out Git LFS files, fetch their content with",
+            "git lfs pull",
+        ),
+    )
+}
+
+/// Looks for remote-tracking refs whose branches were deleted on the remote.
+fn check_stale_remote_refs(working_dir: &Path) -> Vec<HealthCheck> {
+    let remotes = git_output(&["remote"], working_dir).unwrap_or_default();
+
+    remotes
+        .lines()
+        .map(|remote| {
+            let name = format!("remote {}", remote);
+
+            let output =
+                match ExternalCommand::git(["remote", "prune", "--dry-run", remote])
+                    .current_dir(working_dir)
+                    .env("GIT_TERMINAL_PROMPT", "0")
+                    .timeout(REMOTE_TIMEOUT)
+                    .stdout()
+                {
+                    Some(output) => output,
+                    None => return passed(&name, "skipped (remote unreachable)"),
+                };
+
+            let stale_refs = output
+                .lines()
+                .filter_map(|line| line.split_once("[would prune] "))
+                .map(|(_, stale_ref)| stale_ref.trim())
+                .collect::<Vec<&str>>();
+
+            if stale_refs.is_empty() {
+                passed(&name, "no stale remote-tracking refs")
+            } else {
+                problem(
+                    &name,
+                    HealthStatus::Warn,
+                    &format!(
+                        "{} stale remote-tracking ref(s): {}",
+                        stale_refs.len(),
+                        list_files(&stale_refs)
+                    ),
+                    (
+                        "scud pull doesn't prune remote-tracking refs, remove \
+                         them with",
+                        &format!("git fetch --prune {}", remote),
+                    ),
+                )
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,831 @@
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use colored::{ColoredString, Colorize};
+
+use crate::{
+    cli::cli::VCS,
+    commands::hooks::helpers::{
+        discover_hook_scripts, get_hooks_dir, get_hooks_dir_git, get_hooks_path_git,
+        get_interpreter, get_repository_root, is_scud_hook, HookScript,
+        GIT_HOOK_TYPES, HOOKS_DIR_CONFIG_GIT, SCUD_HOOK_MARKER,
+    },
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::{ScudError, ScudResult},
+    logging::helpers::backtick,
+    logging::theme::Themed,
+    runner::ExternalCommand,
+};
+
+/// Options shared by the executors of `scud hooks install`.
+///
+/// Properties:
+///
+/// * `dir`: The hooks directory given via `--dir`, if any.
+/// * `copy`: Whether to copy the scripts instead of symlinking them.
+/// * `force`: Whether to replace hooks which weren't installed by scud.
+/// * `dry_run`: Whether to only show what would be installed.
+pub struct InstallOptions<'a> {
+    pub dir: Option<&'a str>,
+    pub copy: bool,
+    pub force: bool,
+    pub dry_run: bool,
+}
+
+pub fn execute_hooks_info() {
+    log_diagnostic(DiagnosticKind::ScudCommandInfo {
+        command: "hooks",
+        description: "This command is used to manage the hooks of the current \
+                      repository. Hook scripts are read from the hooks directory \
+                      (tools/hooks by default, configurable via --dir or \
+                      SCUD_HOOKS_DIR), either placed in a directory named after \
+                      their hook type (e.g. pre-commit/lint.sh) or named after it \
+                      (e.g. pre-commit.lint). scud commit also runs the pre-commit \
+                      and commit-msg scripts on its own to report their failures, \
+                      but only once they're installed (or SCUD_HOOKS_DIR is set).",
+    });
+    log_diagnostic(DiagnosticKind::VCSInfo {
+        command_name: "hooks install",
+        git_command: &format!(
+            "{} {}",
+            "ln -s <script> .git/hooks/scud/<hook>/ && <dispatcher> > \
+             .git/hooks/<hook>",
+            "(the dispatcher runs every script of the hook in order)"
+                .bright_yellow()
+        ),
+        mercurial_command: &format!(
+            "{} {}",
+            "[hooks] <hook>.scud-<script> = <script> >> .hg/hgrc",
+            "(e.g. pre-commit becomes precommit)".bright_yellow()
+        ),
+        breezy_command: &format!(
+            "{} {}",
+            "ln -s <script> .bzr/scud-hooks/<hook>/",
+            "(run by the scud_hooks plugin installed into the breezy plugins \
+             directory)"
+                .bright_yellow()
+        ),
+        other_commands: &[],
+    });
+}
+
+//////////////////////////////////////////////////////////
+// Functions handle the execution of each the supported //
+// underlying VCS implementations of the hooks command  //
+//////////////////////////////////////////////////////////
+
+/// Lists the scripts in the hooks directory along with whether they're
+/// installed into the current repository.
+pub fn execute_hooks_list(vcs: VCS, dir: Option<&str>, working_dir: &Path) {
+    let repository_root = get_repository_root(&vcs, working_dir);
+    let hooks_dir = match vcs {
+        VCS::Git => get_hooks_dir_git(&repository_root, dir),
+        _ => get_hooks_dir(&repository_root, dir),
+    };
+    let (scripts, unrecognized) = discover_hook_scripts(&hooks_dir);
+
+    if scripts.is_empty() {
+        log_no_hook_scripts(&hooks_dir);
+    } else {
+        println!(
+            "{} {}\n",
+            " HOOKS: ".black().on_cyan(),
+            hooks_dir.display().to_string().muted().italic()
+        );
+
+        for script in &scripts {
+            let installed_script = match vcs {
+                VCS::Git => Some(
+                    scud_hooks_dir_git(&repository_root)
+                        .join(Path::new(&script.hook_type).join(&script.name)),
+                ),
+                VCS::Mercurial => mercurial_hook_type(&script.hook_type).map(|_| {
+                    scud_hooks_dir_vcs(&repository_root, ".hg")
+                        .join(&script.hook_type)
+                        .join(&script.name)
+                }),
+                VCS::Breezy => breezy_hook_type(&script.hook_type).map(|_| {
+                    scud_hooks_dir_vcs(&repository_root, ".bzr")
+                        .join(&script.hook_type)
+                        .join(&script.name)
+                }),
+                VCS::Subversion | VCS::Jujutsu | VCS::Fossil => None,
+            };
+
+            let status = match installed_script {
+                None => " UNSUPPORTED ".black().on_bright_white(),
+                Some(installed_script) => {
+                    match fs::symlink_metadata(&installed_script) {
+                        Ok(metadata) if metadata.file_type().is_symlink() => {
+                            " SYMLINKED ".black().on_bright_green()
+                        }
+                        Ok(_) => " COPIED ".black().on_bright_green(),
+                        Err(_) => " NOT INSTALLED ".black().on_bright_yellow(),
+                    }
+                }
+            };
+
+            log_hook_script(status, script);
+        }
+
+        println!();
+    }
+
+    if let VCS::Git = vcs {
+        let hooks_path = get_hooks_path_git(&repository_root);
+        let unmanaged_hooks = GIT_HOOK_TYPES
+            .iter()
+            .filter(|hook_type| {
+                let hook = hooks_path.join(hook_type);
+                hook.exists() && !is_scud_hook(&hook)
+            })
+            .map(|hook_type| hook_type.to_string())
+            .collect::<Vec<String>>();
+
+        if !unmanaged_hooks.is_empty() {
+            log_diagnostic(DiagnosticKind::Note {
+                body: &format!(
+                    "{}{}",
+                    "Hooks not managed by scud are installed for: ".yellow(),
+                    unmanaged_hooks.join(", ").cyan().italic()
+                ),
+            });
+        }
+    }
+
+    if !unrecognized.is_empty() {
+        log_diagnostic(DiagnosticKind::Note {
+            body: &format!(
+                "{}{}",
+                "Skipped files which aren't named after a hook type: ".yellow(),
+                unrecognized.join(", ").cyan().italic()
+            ),
+        });
+        log_diagnostic(DiagnosticKind::Tip {
+            body: &format!(
+                "{}",
+                "Place scripts in a directory named after their hook type (e.g. \
+                 pre-commit/lint.sh) or name them after it (e.g. pre-commit.lint)"
+                    .yellow()
+            ),
+        });
+    }
+}
+
+/// Installs the hook scripts into `.git/hooks`. Each hook type gets a
+/// dispatcher script which runs every script installed for it (symlinked or
+/// copied into `.git/hooks/scud/<hook type>/`). Dispatchers for hook types
+/// without any scripts left are removed. The directory the scripts were
+/// installed from is kept in the `scud.hooksDir` git configuration, so `scud
+/// commit` runs the same scripts.
+pub fn execute_hooks_install_git(
+    options: InstallOptions,
+    working_dir: &Path,
+) -> ScudResult {
+    let repository_root = get_repository_root(&VCS::Git, working_dir);
+    let hooks_dir = get_hooks_dir_git(&repository_root, options.dir);
+    let scripts = get_hook_scripts(&hooks_dir)?;
+    let hooks_path = get_hooks_path_git(&repository_root);
+    let scud_hooks_dir = scud_hooks_dir_git(&repository_root);
+
+    let mut failed = false;
+
+    for (hook_type, scripts) in group_by_hook_type(&scripts) {
+        let dispatcher = hooks_path.join(&hook_type);
+
+        if dispatcher.exists() && !is_scud_hook(&dispatcher) && !options.force {
+            log_existing_hook(&dispatcher);
+            failed = true;
+            continue;
+        }
+
+        if !options.dry_run {
+            let result = install_scripts(
+                &scripts,
+                &scud_hooks_dir.join(&hook_type),
+                options.copy,
+            )
+            .and_then(|_| {
+                write_executable(&dispatcher, &git_dispatcher(&hook_type))
+            });
+
+            if let Err(error) = result {
+                log_install_error(&hook_type, error);
+                failed = true;
+                continue;
+            }
+        }
+
+        for script in scripts {
+            log_hook_script(installed_label(options.copy, options.dry_run), script);
+        }
+    }
+
+    // Remove the dispatchers of hook types whose scripts were removed
+    for hook_type in GIT_HOOK_TYPES {
+        let dispatcher = hooks_path.join(hook_type);
+
+        if is_scud_hook(&dispatcher)
+            && !scripts.iter().any(|s| s.hook_type == *hook_type)
+        {
+            if !options.dry_run {
+                let _ = fs::remove_file(&dispatcher);
+                let _ = fs::remove_dir_all(scud_hooks_dir.join(hook_type));
+            }
+            log_removed_hook(hook_type, options.dry_run);
+        }
+    }
+
+    if !options.dry_run {
+        // Relative to the root of the repository, so it survives moving it
+        let hooks_dir = hooks_dir
+            .strip_prefix(&repository_root)
+            .unwrap_or(&hooks_dir)
+            .to_string_lossy()
+            .to_string();
+
+        if let Err(error) =
+            ExternalCommand::git(["config", "--local", HOOKS_DIR_CONFIG_GIT])
+                .arg(&hooks_dir)
+                .current_dir(&repository_root)
+                .run()
+        {
+            log_diagnostic(DiagnosticKind::Error {
+                subject: "Failed to record the hooks directory",
+                body: &error.to_string(),
+            });
+            failed = true;
+        }
+    }
+
+    finish_install(failed, options.dry_run)
+}
+
+/// Installs the hook scripts into the `[hooks]` section of `.hg/hgrc`. The
+/// section is wrapped in marker comments so it can be replaced or removed
+/// later on without touching the rest of the configuration.
+pub fn execute_hooks_install_mercurial(
+    options: InstallOptions,
+    working_dir: &Path,
+) -> ScudResult {
+    let repository_root = get_repository_root(&VCS::Mercurial, working_dir);
+    let scripts = get_hook_scripts(&get_hooks_dir(&repository_root, options.dir))?;
+    let scud_hooks_dir = scud_hooks_dir_vcs(&repository_root, ".hg");
+    let hgrc = repository_root.join(".hg").join("hgrc");
+
+    let mut failed = false;
+    let mut hooks_section = vec![];
+
+    for (hook_type, scripts) in group_by_hook_type(&scripts) {
+        let mercurial_hook = match mercurial_hook_type(&hook_type) {
+            Some(mercurial_hook) => mercurial_hook,
+            None => {
+                log_unsupported_hook_type(&hook_type, "Mercurial");
+                continue;
+            }
+        };
+
+        let hook_dir = scud_hooks_dir.join(&hook_type);
+
+        if !options.dry_run {
+            if let Err(error) = install_scripts(&scripts, &hook_dir, options.copy) {
+                log_install_error(&hook_type, error);
+                failed = true;
+                continue;
+            }
+        }
+
+        for script in scripts {
+            hooks_section.push(format!(
+                "{}.scud-{} = {}",
+                mercurial_hook,
+                script.name,
+                script_command(&hook_dir.join(&script.name), &script.path)
+            ));
+            log_hook_script(installed_label(options.copy, options.dry_run), script);
+        }
+    }
+
+    if !options.dry_run {
+        let mut config =
+            remove_scud_section(&fs::read_to_string(&hgrc).unwrap_or_default());
+
+        if !hooks_section.is_empty() {
+            config.push_str(&format!(
+                "{}\n[hooks]\n{}\n{}\n",
+                SCUD_HOOK_MARKER,
+                hooks_section.join("\n"),
+                SCUD_SECTION_END
+            ));
+        }
+
+        if let Err(error) = fs::write(&hgrc, config) {
+            log_install_error("hgrc", error);
+            failed = true;
+        }
+    }
+
+    finish_install(failed, options.dry_run)
+}
+
+/// Installs the hook scripts into `.bzr/scud-hooks/<hook type>/`. Breezy has
+/// no shell hooks, so a small `scud_hooks` plugin is installed into the user's
+/// plugin directory which runs them for every branch that has any.
+pub fn execute_hooks_install_breezy(
+    options: InstallOptions,
+    working_dir: &Path,
+) -> ScudResult {
+    let repository_root = get_repository_root(&VCS::Breezy, working_dir);
+    let scripts = get_hook_scripts(&get_hooks_dir(&repository_root, options.dir))?;
+    let scud_hooks_dir = scud_hooks_dir_vcs(&repository_root, ".bzr");
+
+    let mut failed = false;
+
+    for (hook_type, scripts) in group_by_hook_type(&scripts) {
+        if breezy_hook_type(&hook_type).is_none() {
+            log_unsupported_hook_type(&hook_type, "Breezy");
+            continue;
+        }
+
+        if !options.dry_run {
+            if let Err(error) = install_scripts(
+                &scripts,
+                &scud_hooks_dir.join(&hook_type),
+                options.copy,
+            ) {
+                log_install_error(&hook_type, error);
+                failed = true;
+                continue;
+            }
+        }
+
+        for script in scripts {
+            log_hook_script(installed_label(options.copy, options.dry_run), script);
+        }
+    }
+
+    let plugin = breezy_plugin_dir().join("scud_hooks").join("__init__.py");
+
+    if !options.dry_run {
+        let result = fs::create_dir_all(plugin.parent().unwrap_or(Path::new(".")))
+            .and_then(|_| fs::write(&plugin, BREEZY_PLUGIN));
+
+        if let Err(error) = result {
+            log_install_error("scud_hooks plugin", error);
+            failed = true;
+        }
+    }
+
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{}{}",
+            "The hooks are run by the scud_hooks breezy plugin at ".yellow(),
+            plugin.display().to_string().cyan().italic()
+        ),
+    });
+
+    finish_install(failed, options.dry_run)
+}
+
+/// Removes the dispatchers and scripts installed by scud from `.git/hooks`,
+/// leaving any other hooks untouched.
+pub fn execute_hooks_uninstall_git(dry_run: bool, working_dir: &Path) {
+    let repository_root = get_repository_root(&VCS::Git, working_dir);
+    let hooks_path = get_hooks_path_git(&repository_root);
+
+    for hook_type in GIT_HOOK_TYPES {
+        let dispatcher = hooks_path.join(hook_type);
+
+        if is_scud_hook(&dispatcher) {
+            if !dry_run {
+                let _ = fs::remove_file(&dispatcher);
+            }
+            log_removed_hook(hook_type, dry_run);
+        }
+    }
+
+    if !dry_run {
+        let _ = fs::remove_dir_all(scud_hooks_dir_git(&repository_root));
+        let _ = ExternalCommand::git([
+            "config",
+            "--local",
+            "--unset",
+            HOOKS_DIR_CONFIG_GIT,
+        ])
+        .current_dir(&repository_root)
+        .output();
+    }
+
+    finish_uninstall(dry_run);
+}
+
+/// Removes the section scud added to `.hg/hgrc` along with the installed
+/// scripts.
+pub fn execute_hooks_uninstall_mercurial(
+    dry_run: bool,
+    working_dir: &Path,
+) -> ScudResult {
+    let repository_root = get_repository_root(&VCS::Mercurial, working_dir);
+    let hgrc = repository_root.join(".hg").join("hgrc");
+    let config = fs::read_to_string(&hgrc).unwrap_or_default();
+
+    if config.contains(SCUD_HOOK_MARKER) {
+        if !dry_run {
+            fs::write(&hgrc, remove_scud_section(&config)).map_err(|error| {
+                ScudError::io("failed to remove the hooks from .hg/hgrc", error)
+            })?;
+            let _ = fs::remove_dir_all(scud_hooks_dir_vcs(&repository_root, ".hg"));
+        }
+        log_removed_hook("[hooks] section of .hg/hgrc", dry_run);
+    }
+
+    finish_uninstall(dry_run);
+
+    Ok(())
+}
+
+/// Subversion only runs hooks on the server, from the hooks directory of the
+/// repository itself, so there's nothing to install in a working copy.
+pub fn execute_hooks_subversion() -> ScudResult {
+    Err(ScudError::failed(
+        "Subversion working copies don't run hooks",
+        "Subversion only runs the hooks of the repository, on the server",
+    ))
+}
+
+/// jj doesn't run the hooks of git, even in a repository colocated with one,
+/// and has no hooks of its own yet.
+pub fn execute_hooks_jujutsu() -> ScudResult {
+    Err(ScudError::failed(
+        "Jujutsu repositories don't run hooks",
+        "jj has no hooks of its own and doesn't run the hooks of git, even when \
+         the repository is colocated with a git one",
+    ))
+}
+
+/// Removes the scripts installed into `.bzr/scud-hooks`. The scud_hooks
+/// plugin is left in place since other branches may still rely on it, and it
+/// does nothing for branches without any scripts.
+pub fn execute_hooks_uninstall_breezy(dry_run: bool, working_dir: &Path) {
+    let repository_root = get_repository_root(&VCS::Breezy, working_dir);
+    let scud_hooks_dir = scud_hooks_dir_vcs(&repository_root, ".bzr");
+
+    if scud_hooks_dir.exists() {
+        if !dry_run {
+            let _ = fs::remove_dir_all(&scud_hooks_dir);
+        }
+        log_removed_hook(".bzr/scud-hooks", dry_run);
+    }
+
+    finish_uninstall(dry_run);
+}
+
+////////////////////////////////////////////////
+// Helpers for installing hook scripts        //
+////////////////////////////////////////////////
+
+const SCUD_SECTION_END: &str = "# End of hooks installed by scud";
+
+/// Directory the scripts are symlinked or copied into for git.
+fn scud_hooks_dir_git(repository_root: &Path) -> PathBuf {
+    get_hooks_path_git(repository_root).join("scud")
+}
+
+/// Directory the scripts are symlinked or copied into for Mercurial and
+/// Breezy (e.g. `.hg/scud-hooks`).
+fn scud_hooks_dir_vcs(repository_root: &Path, vcs_dir: &str) -> PathBuf {
+    repository_root.join(vcs_dir).join("scud-hooks")
+}
+
+/// The Mercurial hook equivalent to the given git hook type.
+fn mercurial_hook_type(hook_type: &str) -> Option<&'static str> {
+    match hook_type {
+        "pre-commit" => Some("precommit"),
+        "post-commit" => Some("commit"),
+        "pre-push" => Some("preoutgoing"),
+        "post-checkout" | "post-merge" => Some("update"),
+        _ => None,
+    }
+}
+
+/// The Breezy branch hook equivalent to the given git hook type.
+fn breezy_hook_type(hook_type: &str) -> Option<&'static str> {
+    match hook_type {
+        "pre-commit" => Some("pre_commit"),
+        "post-commit" => Some("post_commit"),
+        _ => None,
+    }
+}
+
+/// The user's breezy plugin directory.
+fn breezy_plugin_dir() -> PathBuf {
+    let config_dir = std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .unwrap_or_else(|_| {
+            PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".config")
+        });
+
+    config_dir.join("breezy").join("plugins")
+}
+
+fn group_by_hook_type(scripts: &[HookScript]) -> BTreeMap<String, Vec<&HookScript>> {
+    let mut grouped: BTreeMap<String, Vec<&HookScript>> = BTreeMap::new();
+
+    for script in scripts {
+        grouped
+            .entry(script.hook_type.clone())
+            .or_default()
+            .push(script);
+    }
+
+    grouped
+}
+
+/// The hook scripts to install from the given directory, failing when there
+/// are none.
+fn get_hook_scripts(hooks_dir: &Path) -> ScudResult<Vec<HookScript>> {
+    let (scripts, _) = discover_hook_scripts(hooks_dir);
+
+    if scripts.is_empty() {
+        log_no_hook_scripts(hooks_dir);
+        return Err(ScudError::Reported { exit_code: 1 });
+    }
+
+    Ok(scripts)
+}
+
+/// Replaces the contents of `hook_dir` with the given scripts, either
+/// symlinked or copied.
+fn install_scripts(
+    scripts: &[&HookScript],
+    hook_dir: &Path,
+    copy: bool,
+) -> io::Result<()> {
+    if hook_dir.exists() {
+        fs::remove_dir_all(hook_dir)?;
+    }
+    fs::create_dir_all(hook_dir)?;
+
+    for script in scripts {
+        let destination = hook_dir.join(&script.name);
+
+        #[cfg(unix)]
+        if !copy {
+            std::os::unix::fs::symlink(&script.path, &destination)?;
+            continue;
+        }
+
+        fs::copy(&script.path, &destination)?;
+        set_executable(&destination)?;
+    }
+
+    Ok(())
+}
+
+fn write_executable(path: &Path, content: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, content)?;
+    set_executable(path)
+}
+
+fn set_executable(path: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    #[cfg(not(unix))]
+    let _ = path;
+
+    Ok(())
+}
+
+/// The command Mercurial runs for an installed script, prefixed with its
+/// interpreter when the script isn't executable.
+fn script_command(installed_script: &Path, source: &Path) -> String {
+    match get_interpreter(source) {
+        Some(interpreter) => {
+            format!("{} {}", interpreter, installed_script.display())
+        }
+        None => installed_script.display().to_string(),
+    }
+}
+
+/// Removes the section previously added by scud from a configuration file.
+fn remove_scud_section(config: &str) -> String {
+    let mut in_scud_section = false;
+    let mut cleaned = String::new();
+
+    for line in config.lines() {
+        if line == SCUD_HOOK_MARKER {
+            in_scud_section = true;
+        } else if line == SCUD_SECTION_END {
+            in_scud_section = false;
+        } else if !in_scud_section {
+            cleaned.push_str(line);
+            cleaned.push('\n');
+        }
+    }
+
+    cleaned
+}
+
+/// The dispatcher script installed as `.git/hooks/<hook type>`, which runs
+/// every script installed for the hook type in order, stopping at the first
+/// failure. Hooks like pre-push receive input on stdin, which is handed to
+/// each of the scripts.
+fn git_dispatcher(hook_type: &str) -> String {
+    GIT_DISPATCHER
+        .replace("{marker}", SCUD_HOOK_MARKER)
+        .replace("{hook_type}", hook_type)
+}
+
+const GIT_DISPATCHER: &str = r#"#!/bin/sh
+{marker}, do not edit.
+# Runs every script in scud/{hook_type}/, stopping at the first failure.
+
+hook_dir="$(dirname "$0")/scud/{hook_type}"
+input=""
+if [ ! -t 0 ]; then
+    input="$(cat)"
+fi
+
+for script in "$hook_dir"/*; do
+    [ -e "$script" ] || continue
+    interpreter=""
+    if [ ! -x "$script" ]; then
+        interpreter="$(sed -n '1s/^#!//p' "$script")"
+        interpreter="${interpreter:-sh}"
+    fi
+    if [ -n "$input" ]; then printf '%s\n' "$input"; fi \
+        | $interpreter "$script" "$@" || exit $?
+done
+"#;
+
+/// Plugin installed into the breezy plugin directory, which runs the scripts
+/// in `.bzr/scud-hooks/<hook type>/` of the branch being committed to.
+const BREEZY_PLUGIN: &str = r##"# Installed by scud (scud hooks install)
+"""Runs the hooks installed by `scud hooks install` for Breezy branches."""
+
+import os
+import subprocess
+
+from breezy import errors
+from breezy.branch import Branch
+
+
+def _run_hooks(hook_type, branch):
+    try:
+        root = branch.controldir.root_transport.local_abspath(".")
+    except errors.NotLocalUrl:
+        return
+
+    hook_dir = os.path.join(root, ".bzr", "scud-hooks", hook_type)
+    if not os.path.isdir(hook_dir):
+        return
+
+    for name in sorted(os.listdir(hook_dir)):
+        script = os.path.join(hook_dir, name)
+        command = [script]
+        if not os.access(script, os.X_OK):
+            with open(script) as f:
+                first_line = f.readline()
+            interpreter = first_line[2:].split() if first_line.startswith("#!") else []
+            command = (interpreter or ["sh"]) + [script]
+        if subprocess.call(command, cwd=root) != 0:
+            raise errors.BzrError("The %s hook %s failed" % (hook_type, name))
+
+
+Branch.hooks.install_named_hook(
+    "pre_commit",
+    lambda local, master, *args: _run_hooks("pre-commit", master),
+    "scud pre-commit hooks",
+)
+Branch.hooks.install_named_hook(
+    "post_commit",
+    lambda local, master, *args: _run_hooks("post-commit", master),
+    "scud post-commit hooks",
+)
+"##;
+
+//////////////////////////////////////
+// Logging helpers for hooks output //
+//////////////////////////////////////
+
+fn installed_label(copy: bool, dry_run: bool) -> ColoredString {
+    match (copy, dry_run) {
+        (true, true) => " WOULD COPY ".black().on_bright_yellow(),
+        (false, true) => " WOULD SYMLINK ".black().on_bright_yellow(),
+        (true, false) => " COPIED ".black().on_bright_green(),
+        (false, false) => " SYMLINKED ".black().on_bright_green(),
+    }
+}
+
+fn log_hook_script(status: ColoredString, script: &HookScript) {
+    println!(
+        "    {}  {} {}",
+        status,
+        script.hook_type.cyan().italic(),
+        script.name.muted().italic()
+    );
+}
+
+fn log_removed_hook(hook: &str, dry_run: bool) {
+    println!(
+        "    {}  {}",
+        if dry_run {
+            " WOULD REMOVE ".black().on_bright_yellow()
+        } else {
+            " REMOVED ".black().on_bright_red()
+        },
+        hook.cyan().italic()
+    );
+}
+
+fn log_existing_hook(hook: &Path) {
+    log_diagnostic(DiagnosticKind::Error {
+        subject: &format!("{} already exists", hook.display()),
+        body: "The hook wasn't installed by scud, so it was left untouched to \
+               avoid losing it.",
+    });
+    log_diagnostic(DiagnosticKind::Hint {
+        body: &format!(
+            "{}",
+            "Move it into the hooks directory so scud manages it, or replace it \
+             with"
+                .yellow()
+        ),
+        command: "scud hooks install --force",
+    });
+}
+
+fn log_install_error(hook: &str, error: io::Error) {
+    log_diagnostic(DiagnosticKind::Error {
+        subject: &format!("failed to install {}", hook),
+        body: &format!("{}", error),
+    });
+}
+
+fn log_unsupported_hook_type(hook_type: &str, vcs: &str) {
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{}{}{}",
+            "Skipped ".yellow(),
+            hook_type.cyan().italic(),
+            format!(" which has no {} equivalent", vcs).yellow()
+        ),
+    });
+}
+
+fn log_no_hook_scripts(hooks_dir: &Path) {
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{}{}",
+            "No hook scripts found in ".magenta().italic(),
+            hooks_dir.display().to_string().cyan().italic()
+        ),
+    });
+    log_diagnostic(DiagnosticKind::Tip {
+        body: &format!(
+            "{} {}{}{} {}",
+            "Point scud at another directory with".yellow(),
+            backtick(),
+            "--dir <path>".green().italic(),
+            backtick(),
+            "or the SCUD_HOOKS_DIR environment variable".yellow(),
+        ),
+    });
+}
+
+/// Fails when any hook couldn't be installed, each of which was already
+/// reported.
+fn finish_install(failed: bool, dry_run: bool) -> ScudResult {
+    println!();
+
+    if dry_run {
+        log_diagnostic(DiagnosticKind::DryRun {
+            command: "hooks install",
+        });
+    }
+
+    if failed {
+        return Err(ScudError::Reported { exit_code: 1 });
+    }
+
+    Ok(())
+}
+
+fn finish_uninstall(dry_run: bool) {
+    println!();
+
+    if dry_run {
+        log_diagnostic(DiagnosticKind::DryRun {
+            command: "hooks uninstall",
+        });
+    }
+}
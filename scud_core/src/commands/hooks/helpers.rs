@@ -0,0 +1,418 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use colored::Colorize;
+
+use crate::{
+    cli::cli::VCS,
+    error::{ScudError, ScudResult},
+    logging::theme::Themed,
+    runner::{breezy_program, CommandOutput, ExternalCommand},
+};
+
+/// Directory (relative to the root of the repository) hook scripts are read
+/// from when neither `--dir` nor `SCUD_HOOKS_DIR` is given.
+pub const DEFAULT_HOOKS_DIR: &str = "tools/hooks";
+
+/// Git configuration key (of the repository) holding the directory the hook
+/// scripts were installed from, so scud runs the same scripts as git does.
+pub const HOOKS_DIR_CONFIG_GIT: &str = "scud.hooksDir";
+
+/// First line of every file scud generates for the hooks it manages, used to
+/// tell them apart from hooks installed by other tools.
+pub const SCUD_HOOK_MARKER: &str = "# Installed by scud (scud hooks install)";
+
+/// The client side hooks git supports.
+pub const GIT_HOOK_TYPES: &[&str] = &[
+    "applypatch-msg",
+    "pre-applypatch",
+    "post-applypatch",
+    "pre-commit",
+    "pre-merge-commit",
+    "prepare-commit-msg",
+    "commit-msg",
+    "post-commit",
+    "pre-rebase",
+    "post-checkout",
+    "post-merge",
+    "pre-push",
+    "post-rewrite",
+    "pre-auto-gc",
+];
+
+/// A script found in the hooks directory.
+///
+/// Properties:
+///
+/// * `hook_type`: The type of hook the script runs as (e.g. `pre-commit`).
+/// * `name`: The file name of the script.
+/// * `path`: The absolute path of the script.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HookScript {
+    pub hook_type: String,
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Resolves the directory hook scripts are read from. The `--dir` flag takes
+/// precedence over the `SCUD_HOOKS_DIR` environment variable, which takes
+/// precedence over the default of `tools/hooks`. Relative directories are
+/// relative to the root of the repository.
+pub fn get_hooks_dir(repository_root: &Path, dir: Option<&str>) -> PathBuf {
+    let dir = match dir {
+        Some(dir) => dir.to_string(),
+        None => std::env::var("SCUD_HOOKS_DIR")
+            .unwrap_or_else(|_| DEFAULT_HOOKS_DIR.to_string()),
+    };
+
+    repository_root.join(dir)
+}
+
+/// Resolves the directory the hook scripts of a git repository are read
+/// from. On top of `get_hooks_dir`, the directory the scripts were installed
+/// from (kept in the `scud.hooksDir` git configuration) is used when neither
+/// `--dir` nor `SCUD_HOOKS_DIR` is given.
+pub fn get_hooks_dir_git(repository_root: &Path, dir: Option<&str>) -> PathBuf {
+    let dir = dir
+        .map(str::to_string)
+        .or_else(|| std::env::var("SCUD_HOOKS_DIR").ok())
+        .or_else(|| get_installed_hooks_dir_git(repository_root));
+
+    get_hooks_dir(repository_root, dir.as_deref())
+}
+
+/// The directory scud runs the hook scripts of a git repository from on its
+/// own (e.g. during `scud commit`): the one given via `SCUD_HOOKS_DIR` or the
+/// one the scripts were installed from. Unlike `get_hooks_dir_git` there's no
+/// default, so scripts a repository merely ships aren't run unless the user
+/// opted into them.
+pub fn get_configured_hooks_dir_git(repository_root: &Path) -> Option<PathBuf> {
+    std::env::var("SCUD_HOOKS_DIR")
+        .ok()
+        .or_else(|| get_installed_hooks_dir_git(repository_root))
+        .map(|dir| get_hooks_dir(repository_root, Some(&dir)))
+}
+
+/// The directory `scud hooks install` installed the scripts of the git
+/// repository from, as given (i.e. relative to the root of the repository
+/// unless absolute).
+pub fn get_installed_hooks_dir_git(repository_root: &Path) -> Option<String> {
+    ExternalCommand::git(["config", "--local", "--get", HOOKS_DIR_CONFIG_GIT])
+        .current_dir(repository_root)
+        .stdout()
+        .filter(|dir| !dir.is_empty())
+}
+
+/// Finds every hook script within the hooks directory. Scripts are either
+/// placed in a directory named after their hook type (e.g.
+/// `pre-commit/search-term.hook`), which allows for multiple scripts per hook
+/// type, or named after their hook type directly (e.g. `pre-commit` or
+/// `pre-commit.lint`).
+///
+/// Returns:
+///
+/// A tuple of the hook scripts, sorted by hook type and name, and the files
+/// which didn't match a hook type
+pub fn discover_hook_scripts(hooks_dir: &Path) -> (Vec<HookScript>, Vec<String>) {
+    let mut scripts = vec![];
+    let mut unrecognized = vec![];
+
+    for entry in sorted_entries(hooks_dir) {
+        let name = file_name(&entry);
+        let is_hook_type = |candidate: &str| GIT_HOOK_TYPES.contains(&candidate);
+
+        if entry.is_dir() && is_hook_type(&name) {
+            for script in sorted_entries(&entry) {
+                if script.is_file() {
+                    scripts.push(HookScript {
+                        hook_type: name.clone(),
+                        name: file_name(&script),
+                        path: script,
+                    });
+                }
+            }
+        } else if entry.is_file()
+            && is_hook_type(name.split('.').next().unwrap_or_default())
+        {
+            scripts.push(HookScript {
+                hook_type: name.split('.').next().unwrap_or_default().to_string(),
+                name,
+                path: entry,
+            });
+        } else {
+            unrecognized.push(name);
+        }
+    }
+
+    scripts.sort_by(|a, b| (&a.hook_type, &a.name).cmp(&(&b.hook_type, &b.name)));
+
+    (scripts, unrecognized)
+}
+
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut entries = fs::read_dir(dir)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .collect::<Vec<PathBuf>>()
+        })
+        .unwrap_or_default();
+    entries.sort();
+    entries
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Gets the root of the repository the given directory belongs to, as
+/// reported by the given VCS (falling back to the directory itself).
+pub fn get_repository_root(vcs: &VCS, working_dir: &Path) -> PathBuf {
+    let (program, args): (&str, &[&str]) = match vcs {
+        VCS::Git => ("git", &["rev-parse", "--show-toplevel"]),
+        VCS::Mercurial => ("hg", &["root"]),
+        VCS::Breezy => (breezy_program(), &["root"]),
+        VCS::Subversion => ("svn", &["info", "--show-item", "wc-root"]),
+        VCS::Jujutsu => ("jj", &["root"]),
+        VCS::Fossil => return get_repository_root_fossil(working_dir),
+    };
+
+    match ExternalCommand::new(program)
+        .args(args)
+        .current_dir(working_dir)
+        .stdout()
+    {
+        Some(repository_root) => PathBuf::from(repository_root),
+        None => working_dir.to_path_buf(),
+    }
+}
+
+/// Gets the root of the current Fossil checkout from the local-root line of
+/// `fossil info` (e.g. "local-root:   /home/user/project/").
+fn get_repository_root_fossil(working_dir: &Path) -> PathBuf {
+    let local_root = ExternalCommand::fossil(["info"])
+        .current_dir(working_dir)
+        .stdout()
+        .and_then(|info| {
+            info.lines().find_map(|line| {
+                line.strip_prefix("local-root:")
+                    .map(|root| root.trim().to_string())
+            })
+        });
+
+    match local_root {
+        Some(repository_root) => PathBuf::from(repository_root),
+        None => working_dir.to_path_buf(),
+    }
+}
+
+/// The directory git runs hooks from (respecting `core.hooksPath`).
+pub fn get_hooks_path_git(repository_root: &Path) -> PathBuf {
+    let hooks_path = ExternalCommand::git(["rev-parse", "--git-path", "hooks"])
+        .current_dir(repository_root)
+        .stdout()
+        .unwrap_or_default();
+
+    if hooks_path.is_empty() {
+        repository_root.join(".git").join("hooks")
+    } else {
+        repository_root.join(hooks_path)
+    }
+}
+
+/// Whether the given file was generated by `scud hooks install`.
+pub fn is_scud_hook(path: &Path) -> bool {
+    fs::read_to_string(path)
+        .map(|content| content.contains(SCUD_HOOK_MARKER))
+        .unwrap_or(false)
+}
+
+/// Whether `git commit --no-verify` can be used because the only pre-commit
+/// and commit-msg hooks git would run are the ones scud already ran itself,
+/// i.e. the dispatchers scud installed from the same directory scud ran the
+/// scripts of.
+///
+/// Arguments:
+///
+/// * `handled_hook_types`: The hook types scud ran the scripts of.
+/// * `working_dir`: The directory within the repository to run git in.
+pub fn can_skip_verification_git(
+    handled_hook_types: &[&str],
+    working_dir: &Path,
+) -> bool {
+    let repository_root = get_repository_root(&VCS::Git, working_dir);
+    let hooks_path = get_hooks_path_git(&repository_root);
+
+    // scud doesn't run the scripts of dispatchers installed before the
+    // directory was kept, so git has to
+    let installed_from = get_installed_hooks_dir_git(&repository_root)
+        .map(|dir| get_hooks_dir(&repository_root, Some(&dir)));
+    let same_scripts = installed_from.is_some()
+        && installed_from == get_configured_hooks_dir_git(&repository_root);
+
+    ["pre-commit", "commit-msg"].iter().all(|hook_type| {
+        let hook = hooks_path.join(hook_type);
+        !hook.exists()
+            || (handled_hook_types.contains(hook_type)
+                && is_scud_hook(&hook)
+                && same_scripts)
+    })
+}
+
+/////////////////////////////////////////////////////
+// Helpers for running hook scripts from scud      //
+/////////////////////////////////////////////////////
+
+/// The interpreter a script has to be run with because it isn't executable,
+/// taken from its shebang (falling back to `sh`).
+///
+/// Returns:
+///
+/// None when the script is executable and can be run directly
+pub fn get_interpreter(path: &Path) -> Option<String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let is_executable = fs::metadata(path)
+            .map(|metadata| metadata.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false);
+
+        if is_executable {
+            return None;
+        }
+    }
+
+    let interpreter = fs::read_to_string(path).ok().and_then(|content| {
+        content
+            .lines()
+            .next()
+            .and_then(|line| line.strip_prefix("#!"))
+            .map(|interpreter| interpreter.trim().to_string())
+    });
+
+    Some(interpreter.unwrap_or_else(|| "sh".to_string()))
+}
+
+/// Runs a single hook script from the root of the repository.
+fn run_hook_script(
+    script: &HookScript,
+    repository_root: &Path,
+    args: &[&str],
+) -> ScudResult<CommandOutput> {
+    let command = match get_interpreter(&script.path) {
+        None => ExternalCommand::new(script.path.to_string_lossy()),
+        Some(interpreter) => {
+            let mut interpreter = interpreter.split_whitespace();
+            ExternalCommand::new(interpreter.next().unwrap_or("sh"))
+                .args(interpreter)
+                .arg(&script.path)
+        }
+    };
+
+    command.args(args).current_dir(repository_root).output()
+}
+
+/// Runs every script of the given hook type found in the configured hooks
+/// directory (see `get_configured_hooks_dir_git`), if there's one. The first
+/// failing script is returned as an error along with its output.
+///
+/// Arguments:
+///
+/// * `hook_type`: The type of hook to run (e.g. `pre-commit`).
+/// * `args`: The arguments git would pass to the hook.
+/// * `working_dir`: The directory within the repository to run git in.
+pub fn run_hooks_git(
+    hook_type: &str,
+    args: &[&str],
+    working_dir: &Path,
+) -> ScudResult {
+    let repository_root = get_repository_root(&VCS::Git, working_dir);
+    let hooks_dir = match get_configured_hooks_dir_git(&repository_root) {
+        Some(hooks_dir) => hooks_dir,
+        None => return Ok(()),
+    };
+    let (scripts, _) = discover_hook_scripts(&hooks_dir);
+
+    let scripts = scripts
+        .into_iter()
+        .filter(|script| script.hook_type == hook_type)
+        .collect::<Vec<HookScript>>();
+
+    for script in &scripts {
+        let output = run_hook_script(script, &repository_root, args)?;
+
+        if !output.success() {
+            let hook_output = format!("{}{}", output.stdout, output.stderr);
+            let status = match output.status {
+                Some(code) => format!("exit code {}", code),
+                None => "terminated by a signal".to_string(),
+            };
+
+            return Err(ScudError::failed(
+                format!(
+                    "The {} hook {} failed ({})",
+                    hook_type, script.name, status
+                ),
+                hook_output.trim(),
+            )
+            .with_hint(
+                format!(
+                    "{}",
+                    "Fix the problems reported above and try again, the hook \
+                     lives at"
+                        .yellow()
+                ),
+                script.path.display().to_string(),
+            ));
+        }
+
+        println!(
+            "    {}  {} {}",
+            " PASSED ".black().on_bright_green(),
+            hook_type.bright_green().italic(),
+            script.name.muted().italic()
+        );
+    }
+
+    if !scripts.is_empty() {
+        println!();
+    }
+
+    Ok(())
+}
+
+/// Runs the `commit-msg` scripts against the given commit message, which is
+/// written to `COMMIT_EDITMSG` in the git directory the same way git does.
+///
+/// Returns:
+///
+/// The commit message, which the hooks are allowed to edit
+pub fn run_commit_msg_hooks_git(
+    commit_message: &str,
+    working_dir: &Path,
+) -> ScudResult<String> {
+    let repository_root = get_repository_root(&VCS::Git, working_dir);
+    let message_path =
+        ExternalCommand::git(["rev-parse", "--git-path", "COMMIT_EDITMSG"])
+            .current_dir(&repository_root)
+            .stdout()
+            .map(|message_path| repository_root.join(message_path))
+            .unwrap_or_else(|| repository_root.join(".git").join("COMMIT_EDITMSG"));
+
+    if fs::write(&message_path, commit_message).is_err() {
+        return Ok(commit_message.to_string());
+    }
+
+    run_hooks_git(
+        "commit-msg",
+        &[&message_path.to_string_lossy()],
+        working_dir,
+    )?;
+
+    Ok(fs::read_to_string(&message_path)
+        .unwrap_or_else(|_| commit_message.to_string()))
+}
@@ -0,0 +1,101 @@
+use std::path::Path;
+
+use crate::{
+    cli::cli::{Hooks, HooksCommands, HooksInstall, VCS},
+    commands::hooks::executors::{
+        execute_hooks_info, execute_hooks_install_breezy, execute_hooks_install_git,
+        execute_hooks_install_mercurial, execute_hooks_jujutsu, execute_hooks_list,
+        execute_hooks_subversion, execute_hooks_uninstall_breezy,
+        execute_hooks_uninstall_git, execute_hooks_uninstall_mercurial,
+        InstallOptions,
+    },
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::ScudResult,
+    repository::Repository,
+};
+
+/// Executes the hooks command along with its subcommands
+///
+/// Arguments:
+///
+/// * `hooks_options`: Struct that contains the subcommand and options that were
+///   passed to the command via user input (e.g. install, --copy, --info).
+/// * `working_dir`: The directory to operate on, which is the current one
+///   unless given with --directory.
+pub fn process_hooks_commands(
+    hooks_options: Hooks,
+    working_dir: &Path,
+) -> ScudResult {
+    if hooks_options.info {
+        execute_hooks_info();
+        return Ok(());
+    }
+
+    match hooks_options.command {
+        Some(HooksCommands::List(list_options)) => {
+            execute_hooks_list(
+                Repository::discover(working_dir)?.vcs,
+                list_options.dir.as_deref(),
+                working_dir,
+            );
+        }
+        Some(HooksCommands::Install(install_options)) => {
+            execute_hooks_install(install_options, working_dir)?;
+        }
+        Some(HooksCommands::Uninstall(uninstall_options)) => {
+            execute_hooks_uninstall(uninstall_options.dry_run, working_dir)?;
+        }
+        None => execute_hooks_list(
+            Repository::discover(working_dir)?.vcs,
+            None,
+            working_dir,
+        ),
+    }
+
+    Ok(())
+}
+
+/// Detects the version control system, and then calls the appropriate
+/// function to install the hooks
+fn execute_hooks_install(
+    install_options: HooksInstall,
+    working_dir: &Path,
+) -> ScudResult {
+    let options = InstallOptions {
+        dir: install_options.dir.as_deref(),
+        copy: install_options.copy,
+        force: install_options.force,
+        dry_run: install_options.dry_run,
+    };
+
+    match Repository::discover(working_dir)?.vcs {
+        VCS::Git => execute_hooks_install_git(options, working_dir),
+        VCS::Mercurial => execute_hooks_install_mercurial(options, working_dir),
+        VCS::Breezy => execute_hooks_install_breezy(options, working_dir),
+        VCS::Subversion => execute_hooks_subversion(),
+        VCS::Jujutsu => execute_hooks_jujutsu(),
+        VCS::Fossil => {
+            log_diagnostic(DiagnosticKind::WorkInProgress {
+                feature: "scud hooks install (fossil)",
+            });
+            Ok(())
+        }
+    }
+}
+
+/// Detects the version control system, and then calls the appropriate
+/// function to uninstall the hooks
+fn execute_hooks_uninstall(dry_run: bool, working_dir: &Path) -> ScudResult {
+    match Repository::discover(working_dir)?.vcs {
+        VCS::Git => execute_hooks_uninstall_git(dry_run, working_dir),
+        VCS::Mercurial => execute_hooks_uninstall_mercurial(dry_run, working_dir)?,
+        VCS::Breezy => execute_hooks_uninstall_breezy(dry_run, working_dir),
+        VCS::Subversion => execute_hooks_subversion()?,
+        VCS::Jujutsu => execute_hooks_jujutsu()?,
+        VCS::Fossil => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud hooks uninstall (fossil)",
+        }),
+    }
+
+    Ok(())
+}
@@ -1,10 +1,10 @@
-use std::process::Command;
-
 use colored::Colorize;
 
 use crate::{
     diagnostics::{log_diagnostic, DiagnosticKind, ExternalCommandInfo},
     information::Codebase,
+    logging::theme::Themed,
+    runner::ExternalCommand,
 };
 
 // TODO refactor this command to follow pattern of others (stage, state)
@@ -29,15 +29,15 @@ pub fn info_codebase_command(codebase_options: Codebase) {
             .to_vec(),
         });
     } else {
-        match Command::new("onefetch").output() {
+        match ExternalCommand::new("onefetch").output() {
             Ok(output) => {
-                println!("{}", String::from_utf8_lossy(&output.stdout));
+                println!("{}", output.stdout);
             }
             Err(_) => {
                 log_diagnostic(DiagnosticKind::Error {
                     subject: &format!(
                         "onefetch {}",
-                        "(https://github.com/o2sh/onefetch)".black().italic()
+                        "(https://github.com/o2sh/onefetch)".muted().italic()
                     ),
                     body: "is not installed on system locally or not found in \
                               PATH.",
@@ -51,7 +51,7 @@ pub fn info_codebase_command(codebase_options: Codebase) {
                          command"
                             .yellow(),
                     ),
-                    command: "scud setup info codebase",
+                    command: "scud setup tool onefetch",
                 });
                 // log_diagnostic(DiagnosticKind::Error {
                 //     // TODO refactor
@@ -7,10 +7,11 @@ use crate::{
         system::system::info_system_command,
     },
     diagnostics::{log_diagnostic, DiagnosticKind},
+    error::ScudResult,
     information::{Codebase, Info, InfoCommands},
 };
 
-pub fn process_info_commands(info_commands: Info) {
+pub fn process_info_commands(info_commands: Info) -> ScudResult {
     {
         match info_commands.command {
             Some(info_command) => match info_command {
@@ -22,13 +23,15 @@ pub fn process_info_commands(info_commands: Info) {
                 }
             },
             None => {
-                get_branch_command();
+                get_branch_command()?;
             }
         }
     }
+
+    Ok(())
 }
 
-pub fn get_branch_command() {
+pub fn get_branch_command() -> ScudResult {
     let branch_subcommand_options = &[
         "Codebase [alias: cb]: Information about the contents of the codebase \
          within the
@@ -48,10 +51,9 @@ pub fn get_branch_command() {
         ))
         .default(0)
         .items(&branch_subcommand_options[..])
-        .interact()
-        .unwrap();
+        .interact()?;
 
-    clearscreen::clear().expect("failed to clear screen");
+    let _ = clearscreen::clear();
 
     match selected_subcommand {
         0 => {
@@ -64,4 +66,6 @@ pub fn get_branch_command() {
         }
         _ => unreachable!(),
     }
+
+    Ok(())
 }
@@ -6,9 +6,9 @@ use dialoguer::{
     FuzzySelect,
 };
 
-use crate::version_control::Init;
+use crate::{error::ScudResult, version_control::Init};
 
-pub fn init_command(init_options: Init) {
+pub fn init_command(init_options: Init) -> ScudResult {
     let vcs_options = &["Git", "Mercurial", "Breezy"];
 
     // println!("{}", "Initializing new repository...".green());
@@ -30,8 +30,7 @@ pub fn init_command(init_options: Init) {
         )
         .default(0)
         .items(&vcs_options[..])
-        .interact()
-        .unwrap();
+        .interact()?;
 
     match selected_vcs {
         0 => {
@@ -47,4 +46,6 @@ pub fn init_command(init_options: Init) {
             println!("Unknown");
         }
     }
+
+    Ok(())
 }
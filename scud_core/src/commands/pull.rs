@@ -0,0 +1,2 @@
+pub mod executors;
+pub mod pull;
@@ -0,0 +1,89 @@
+use std::path::Path;
+
+use colored::Colorize;
+
+use crate::{
+    cli::cli::VCS,
+    commands::hooks::helpers::get_repository_root,
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::ScudResult,
+    runner::ExternalCommand,
+};
+
+pub fn execute_pull_info() {
+    log_diagnostic(DiagnosticKind::ScudCommandInfo {
+        command: "pull",
+        description: "This command is used to pull the commits of the remote \
+                      repository into the current branch of the local \
+                      repository.",
+    });
+    log_diagnostic(DiagnosticKind::VCSInfo {
+        command_name: "pull",
+        git_command: "git pull",
+        mercurial_command: "hg pull --update",
+        breezy_command: &format!(
+            "{} {}",
+            "brz pull",
+            "(from the parent branch, which Breezy remembers)".bright_yellow()
+        ),
+        other_commands: &[
+            (
+                "Subversion",
+                &format!(
+                    "{} {}",
+                    "svn update",
+                    "(from the root of the working copy)".bright_yellow()
+                ),
+            ),
+            (
+                "Jujutsu",
+                &format!(
+                    "{} {}",
+                    "jj git fetch",
+                    "(leaving the working-copy commit where it is)".bright_yellow()
+                ),
+            ),
+        ],
+    });
+}
+
+/// Pulls the commits of the upstream of the current branch with `git pull`.
+pub fn execute_pull_git(working_dir: &Path) -> ScudResult {
+    ExternalCommand::git(["pull"])
+        .current_dir(working_dir)
+        .run_attached()
+}
+
+/// Pulls the changesets of the default path with `hg pull --update`, which
+/// only updates the working directory when it doesn't cross branches.
+pub fn execute_pull_mercurial(working_dir: &Path) -> ScudResult {
+    ExternalCommand::hg(["pull", "--update"])
+        .current_dir(working_dir)
+        .run_attached()
+}
+
+/// Pulls the revisions of the parent branch with `brz pull`. When the
+/// branches have diverged, brz asks for them to be merged instead.
+pub fn execute_pull_breezy(working_dir: &Path) -> ScudResult {
+    ExternalCommand::brz(["pull"])
+        .current_dir(working_dir)
+        .run_attached()
+}
+
+/// Brings the whole working copy up to date with the repository with
+/// `svn update`, which merges the new revisions into any local changes.
+pub fn execute_pull_subversion(working_dir: &Path) -> ScudResult {
+    // svn update only updates the directory it's run from
+    ExternalCommand::svn(["update"])
+        .current_dir(get_repository_root(&VCS::Subversion, working_dir))
+        .run_attached()
+}
+
+/// Fetches the commits and bookmarks of the git remote with `jj git fetch`.
+/// The working-copy commit stays where it is, so it can be rebased onto the
+/// fetched commits with `jj rebase`.
+pub fn execute_pull_jujutsu(working_dir: &Path) -> ScudResult {
+    ExternalCommand::jj(["git", "fetch"])
+        .current_dir(working_dir)
+        .run_attached()
+}
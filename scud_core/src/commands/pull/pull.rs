@@ -0,0 +1,40 @@
+use std::path::Path;
+
+use crate::{
+    cli::cli::{Pull, VCS},
+    commands::pull::executors::{
+        execute_pull_breezy, execute_pull_git, execute_pull_info,
+        execute_pull_jujutsu, execute_pull_mercurial, execute_pull_subversion,
+    },
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::ScudResult,
+    repository::Repository,
+};
+
+pub fn pull_command(pull_options: Pull, working_dir: &Path) -> ScudResult {
+    if pull_options.info {
+        execute_pull_info();
+        Ok(())
+    } else {
+        execute_pull(working_dir)
+    }
+}
+
+/// Detects the version control system, and then calls the appropriate
+/// function to pull the commits of the remote repository
+fn execute_pull(working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
+
+    match vcs {
+        VCS::Git => execute_pull_git(working_dir)?,
+        VCS::Mercurial => execute_pull_mercurial(working_dir)?,
+        VCS::Breezy => execute_pull_breezy(working_dir)?,
+        VCS::Subversion => execute_pull_subversion(working_dir)?,
+        VCS::Jujutsu => execute_pull_jujutsu(working_dir)?,
+        VCS::Fossil => log_diagnostic(DiagnosticKind::WorkInProgress {
+            feature: "scud pull (fossil)",
+        }),
+    }
+
+    Ok(())
+}
@@ -1,18 +1,97 @@
-use std::process::Command;
+use std::path::Path;
 
-use crate::diagnostics::{log_diagnostic, DiagnosticKind};
+use colored::Colorize;
 
-pub fn execute_push_dry_run() {
-    match Command::new("git").args(["push", "--dry-run"]).status() {
-        Ok(status) => {
-            if !status.success() {
-                panic!("Failed to push files");
-            }
-        }
-        Err(error) => {
-            panic!("Failed to push files: {}", error);
+use crate::{
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::ScudResult,
+    logging::helpers::backtick,
+    runner::ExternalCommand,
+};
+
+pub fn execute_push_dry_run(working_dir: &Path) -> ScudResult {
+    ExternalCommand::git(["push", "--dry-run"])
+        .current_dir(working_dir)
+        .run_attached()?;
+
+    log_diagnostic(DiagnosticKind::DryRun { command: "push" });
+
+    Ok(())
+}
+
+/// Arguments for `git push`. When the current branch has no upstream yet, it
+/// is pushed to the default remote (`origin`, or the only remote) and set as
+/// the upstream of the branch.
+pub fn get_push_args_git(working_dir: &Path) -> Vec<String> {
+    let git_output =
+        |args: &[&str]| ExternalCommand::git(args).current_dir(working_dir).stdout();
+
+    let has_upstream =
+        git_output(&["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"])
+            .is_some();
+    let branch = git_output(&["symbolic-ref", "--quiet", "--short", "HEAD"]);
+    let remotes = git_output(&["remote"]).unwrap_or_default();
+    let remotes = remotes.lines().collect::<Vec<&str>>();
+
+    let remote = if remotes.contains(&"origin") {
+        Some("origin")
+    } else if remotes.len() == 1 {
+        Some(remotes[0])
+    } else {
+        None
+    };
+
+    match (has_upstream, branch, remote) {
+        (false, Some(branch), Some(remote)) => {
+            log_diagnostic(DiagnosticKind::Note {
+                body: &format!(
+                    "{}{}{}{}",
+                    "Branch ".yellow(),
+                    branch.cyan().italic(),
+                    " has no upstream yet, setting it to ".yellow(),
+                    format!("{}/{}", remote, branch).cyan().italic()
+                ),
+            });
+
+            vec![
+                "push".to_string(),
+                "--set-upstream".to_string(),
+                remote.to_string(),
+                branch,
+            ]
         }
+        _ => vec!["push".to_string()],
     }
+}
 
-    log_diagnostic(DiagnosticKind::DryRun { command: "push" });
+/// Pushes the current branch to its push location with `brz push`. Breezy
+/// remembers the location the first time a branch is pushed somewhere
+/// (`brz push <location>`), so there's nothing to set up here.
+pub fn execute_push_breezy(working_dir: &Path) -> ScudResult {
+    ExternalCommand::brz(["push"])
+        .current_dir(working_dir)
+        .run_attached()
+}
+
+/// Pushes the bookmarks that moved since they were last pushed to the git
+/// remote with `jj git push`.
+pub fn execute_push_jujutsu(working_dir: &Path) -> ScudResult {
+    ExternalCommand::jj(["git", "push"])
+        .current_dir(working_dir)
+        .run_attached()
+}
+
+/// Subversion has nothing to push, as `svn commit` sends every commit
+/// straight to the repository.
+pub fn execute_push_subversion() {
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{} {}{}{} {}",
+            "Nothing to push, since".yellow(),
+            backtick(),
+            "svn commit".green().italic(),
+            backtick(),
+            "already sends every commit to the repository".yellow()
+        ),
+    });
 }
@@ -1,17 +1,60 @@
-use crate::{cli::cli::Push, commands::push::executors::execute_push_dry_run};
-use std::process::Command;
+use std::path::Path;
 
-pub fn push_command(push_options: Push) {
+use crate::{
+    cli::cli::{Push, VCS},
+    commands::{
+        push::executors::{
+            execute_push_breezy, execute_push_dry_run, execute_push_jujutsu,
+            execute_push_subversion, get_push_args_git,
+        },
+        undo::journal::{record_operation_git, snapshot_git, Operation},
+    },
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::{ScudError, ScudResult},
+    repository::Repository,
+    runner::ExternalCommand,
+};
+use colored::Colorize;
+
+pub fn push_command(push_options: Push, working_dir: &Path) -> ScudResult {
     if push_options.dry_run {
-        execute_push_dry_run();
+        execute_push_dry_run(working_dir)?;
     } else if push_options.info {
         execute_push_info();
     } else {
-        execute_push();
+        execute_push(working_dir)?;
     }
+
+    Ok(())
 }
 
-fn execute_push() {
+fn execute_push(working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
+
+    match vcs {
+        VCS::Git => execute_push_git(working_dir),
+        VCS::Mercurial => {
+            log_diagnostic(DiagnosticKind::WorkInProgress {
+                feature: "scud push (mercurial)",
+            });
+            Ok(())
+        }
+        VCS::Breezy => execute_push_breezy(working_dir),
+        VCS::Subversion => {
+            execute_push_subversion();
+            Ok(())
+        }
+        VCS::Jujutsu => execute_push_jujutsu(working_dir),
+        VCS::Fossil => {
+            log_diagnostic(DiagnosticKind::WorkInProgress {
+                feature: "scud push (fossil)",
+            });
+            Ok(())
+        }
+    }
+}
+
+fn execute_push_git(working_dir: &Path) -> ScudResult {
     // TODO: pipe output
     // EXAMPLE output to parse:
     //     Enumerating objects: 194, done.
@@ -24,16 +67,34 @@ fn execute_push() {
     // To https://github.com/pulanski/scud.git
     //    2b03205..5f2d9da  main -> main
 
-    match Command::new("git").arg("push").status() {
-        Ok(status) => {
-            if !status.success() {
-                panic!("Failed to push files");
-            }
-        }
-        Err(error) => {
-            panic!("Failed to push files: {}", error);
-        }
+    // Record the upstream before pushing so `scud undo --force` can reset it
+    let snapshot = snapshot_git(Operation::Push, working_dir);
+
+    let push = ExternalCommand::git(get_push_args_git(working_dir))
+        .current_dir(working_dir)
+        .run_attached();
+
+    if let Err(ScudError::CommandFailed { .. }) = push {
+        return Err(ScudError::failed(
+            "Failed to push commits",
+            "git push was rejected (see the output above). This usually means \
+             the remote branch contains commits you don't have locally yet.",
+        )
+        .with_hint(
+            format!(
+                "{}",
+                "Integrate the remote changes with git pull, and if that runs into \
+                 conflicts, resolve them with"
+                    .yellow()
+            ),
+            "scud conflicts",
+        ));
     }
+    push?;
+
+    record_operation_git(snapshot, working_dir);
+
+    Ok(())
 }
 
 pub fn execute_push_info() {}
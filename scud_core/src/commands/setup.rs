@@ -1 +1,4 @@
-pub mod vcs_git;
+pub mod executors;
+pub mod helpers;
+pub mod identity;
+pub mod setup;
@@ -0,0 +1,253 @@
+use std::path::Path;
+
+use colored::Colorize;
+
+use crate::{
+    commands::{
+        healthcheck::{
+            helpers::{check_tool, run_system_healthchecks, HealthStatus, TOOLS},
+            logging::{
+                log_healthcheck_hints, log_healthcheck_summary,
+                log_healthcheck_table,
+            },
+        },
+        setup::helpers::{
+            detect_package_manager, get_install_command, is_in_path, Installable,
+            PackageManager, INSTALLABLES,
+        },
+    },
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::{ScudError, ScudResult},
+    logging::helpers::backtick,
+    logging::theme::Themed,
+    runner::ExternalCommand,
+};
+
+pub fn execute_setup_info() {
+    log_diagnostic(DiagnosticKind::ScudCommandInfo {
+        command: "setup",
+        description: "This command is used to install the version control \
+                      systems, source control provider CLIs and other tools scud \
+                      relies on with the package manager of the system (i.e. apt, \
+                      dnf, pacman, brew or nix) [alias: su]",
+    });
+
+    let package_manager = match detect_package_manager() {
+        Some(package_manager) => package_manager,
+        None => {
+            no_package_manager().log();
+            return;
+        }
+    };
+
+    println!(
+        "{}\n",
+        format!(
+            "Commands run with {} (the package manager detected on this system):",
+            package_manager.name()
+        )
+        .bright_yellow()
+    );
+
+    let name_width = INSTALLABLES
+        .iter()
+        .map(|installable| installable.name.len())
+        .max()
+        .unwrap_or_default();
+
+    for installable in INSTALLABLES {
+        println!(
+            "    {:width$}  {}",
+            installable.name,
+            install_plan(package_manager, installable),
+            width = name_width
+        );
+    }
+
+    println!();
+}
+
+/// Lists the tools scud can install, whether they are installed already, and
+/// the scud command installing the ones which aren't.
+pub fn execute_setup_list() {
+    let package_manager = detect_package_manager();
+
+    println!("{}\n", " SETUP: ".black().on_cyan());
+    println!(
+        "  {} {}\n",
+        "Package manager:".bright_yellow(),
+        match package_manager {
+            Some(package_manager) => package_manager.name().cyan().italic(),
+            None => "none detected".bright_red().italic(),
+        }
+    );
+
+    let name_width = INSTALLABLES
+        .iter()
+        .map(|installable| installable.name.len())
+        .max()
+        .unwrap_or_default();
+
+    for installable in INSTALLABLES {
+        if is_in_path(installable.program) {
+            println!(
+                "    {}  {:width$}  {}",
+                " INSTALLED ".black().on_bright_green(),
+                installable.name,
+                installable.program.muted().italic(),
+                width = name_width
+            );
+        } else {
+            println!(
+                "    {}  {:width$}  {}",
+                "  MISSING  ".black().on_bright_yellow(),
+                installable.name,
+                installable.setup_command.green().italic(),
+                width = name_width
+            );
+        }
+    }
+
+    println!();
+
+    if package_manager.is_none() {
+        no_package_manager().log();
+    }
+}
+
+/// Installs the tool with the package manager of the system, and then re-runs
+/// the healthcheck to confirm the system is set up.
+///
+/// Arguments:
+///
+/// * `installable`: The tool to install.
+/// * `dry_run`: Whether to only show the command which would be run.
+/// * `working_dir`: The directory the healthcheck reads the git identity of.
+pub fn execute_setup_install(
+    installable: &Installable,
+    dry_run: bool,
+    working_dir: &Path,
+) -> ScudResult {
+    let package_manager = detect_package_manager()
+        .ok_or_else(|| install_manually(no_package_manager(), installable))?;
+
+    if is_installed(installable) {
+        log_diagnostic(DiagnosticKind::Note {
+            body: &format!(
+                "{}{}",
+                installable.name.cyan().italic(),
+                " is already installed, nothing to do".yellow()
+            ),
+        });
+        return Ok(());
+    }
+
+    let install_command = get_install_command(package_manager, installable)
+        .ok_or_else(|| {
+            install_manually(
+                ScudError::failed(
+                    format!(
+                        "{} isn't packaged for {}",
+                        installable.name,
+                        package_manager.name()
+                    ),
+                    "scud doesn't know of a package providing it for the package \
+                     manager of this system.",
+                ),
+                installable,
+            )
+        })?;
+
+    println!(
+        "{} {} {} {}{}{}\n",
+        "Installing".bright_yellow(),
+        installable.name.cyan(),
+        format!("with {}:", package_manager.name()).bright_yellow(),
+        backtick(),
+        install_command.to_string().green().italic(),
+        backtick(),
+    );
+
+    if dry_run {
+        log_diagnostic(DiagnosticKind::DryRun {
+            command: installable.setup_command.trim_start_matches("scud "),
+        });
+        return Ok(());
+    }
+
+    let install = ExternalCommand::new(&install_command.program)
+        .args(&install_command.args)
+        .run_attached();
+
+    if let Err(error @ ScudError::CommandFailed { .. }) = install {
+        return Err(install_manually(
+            ScudError::failed(
+                format!("Failed to install {}", installable.name),
+                format!("{}, see its output above.", error),
+            ),
+            installable,
+        ));
+    }
+    install?;
+
+    println!();
+
+    // Confirm the system is set up now
+    let checks = run_system_healthchecks(working_dir);
+    log_healthcheck_table("HEALTHCHECK:", &checks);
+    log_healthcheck_hints(&checks);
+    log_healthcheck_summary(&checks);
+
+    Ok(())
+}
+
+/// Whether the tool is installed, and for the tools the healthcheck knows
+/// about, at least at its minimum version.
+fn is_installed(installable: &Installable) -> bool {
+    match TOOLS
+        .iter()
+        .find(|tool| tool.program == installable.program)
+    {
+        Some(tool) => check_tool(tool).status == HealthStatus::Pass,
+        None => is_in_path(installable.program),
+    }
+}
+
+fn install_plan(
+    package_manager: PackageManager,
+    installable: &Installable,
+) -> String {
+    match get_install_command(package_manager, installable) {
+        Some(install_command) => {
+            install_command.to_string().green().italic().to_string()
+        }
+        None => format!(
+            "{} {}",
+            "not packaged, see".muted().italic(),
+            installable.install_url.cyan().italic()
+        ),
+    }
+}
+
+fn no_package_manager() -> ScudError {
+    ScudError::failed(
+        "Could not detect a supported package manager",
+        "scud installs tools with apt, dnf, pacman, brew or nix, none of which \
+         were found in PATH.",
+    )
+}
+
+/// Points the user at the install instructions of the tool.
+fn install_manually(error: ScudError, installable: &Installable) -> ScudError {
+    error.with_hint(
+        format!(
+            "{}",
+            format!(
+                "Install {} by following the instructions at",
+                installable.name
+            )
+            .yellow()
+        ),
+        installable.install_url,
+    )
+}
@@ -0,0 +1,330 @@
+use std::{env, fmt, path::Path};
+
+use crate::runner::ExternalCommand;
+
+/////////////////////////////
+// Package managers        //
+/////////////////////////////
+
+// Where there are options for pure shell scripts, those are chosen
+// and associated command is shown to the user (maybe ?)
+// general approach will be
+// TODO list for supported package managers under the hood for various setup
+// commands TODO brew for mac, choco for windows
+// make assumption that most people in software dev space are familiar
+// with package manager and use the underlying package managers provided
+// for usage
+// show relevant documentation for installing each package manager
+// provide general advice that package managers will save you time
+// can be thought of in a manner analogous to app stores for phones
+// centralized authority which distributes software (NOTE: can still use more traditional installation techniques as well, e.g. installing Docker from https://www.docker.com/).
+
+/// The system package managers scud knows how to install tools with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackageManager {
+    Apt,
+    Dnf,
+    Pacman,
+    Brew,
+    Nix,
+}
+
+impl PackageManager {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PackageManager::Apt => "apt",
+            PackageManager::Dnf => "dnf",
+            PackageManager::Pacman => "pacman",
+            PackageManager::Brew => "brew",
+            PackageManager::Nix => "nix",
+        }
+    }
+
+    /// The executable installing packages.
+    fn program(&self) -> &'static str {
+        match self {
+            PackageManager::Apt => "apt-get",
+            PackageManager::Dnf => "dnf",
+            PackageManager::Pacman => "pacman",
+            PackageManager::Brew => "brew",
+            PackageManager::Nix => "nix-env",
+        }
+    }
+
+    /// The arguments preceding the package name, answering yes to any prompts.
+    fn install_args(&self) -> &'static [&'static str] {
+        match self {
+            PackageManager::Apt | PackageManager::Dnf => &["install", "-y"],
+            PackageManager::Pacman => &["-S", "--noconfirm"],
+            PackageManager::Brew => &["install"],
+            PackageManager::Nix => &["-iA"],
+        }
+    }
+
+    /// Whether installing packages requires root (brew and nix install into
+    /// the user's prefix/profile).
+    fn needs_root(&self) -> bool {
+        matches!(
+            self,
+            PackageManager::Apt | PackageManager::Dnf | PackageManager::Pacman
+        )
+    }
+}
+
+/// The package managers in the order they are looked for. Homebrew is
+/// preferred on macOS, where it's the de facto standard.
+fn package_managers() -> [PackageManager; 5] {
+    if cfg!(target_os = "macos") {
+        [
+            PackageManager::Brew,
+            PackageManager::Nix,
+            PackageManager::Apt,
+            PackageManager::Dnf,
+            PackageManager::Pacman,
+        ]
+    } else {
+        [
+            PackageManager::Apt,
+            PackageManager::Dnf,
+            PackageManager::Pacman,
+            PackageManager::Brew,
+            PackageManager::Nix,
+        ]
+    }
+}
+
+/// Finds the first supported package manager installed on the system.
+pub fn detect_package_manager() -> Option<PackageManager> {
+    package_managers()
+        .into_iter()
+        .find(|package_manager| is_in_path(package_manager.program()))
+}
+
+/// Whether an executable with the given name is found in `PATH`.
+pub fn is_in_path(program: &str) -> bool {
+    env::var_os("PATH")
+        .map(|path| {
+            env::split_paths(&path).any(|dir| is_executable(&dir.join(program)))
+        })
+        .unwrap_or(false)
+}
+
+fn is_executable(path: &Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .map(|metadata| {
+                metadata.is_file() && metadata.permissions().mode() & 0o111 != 0
+            })
+            .unwrap_or(false)
+    }
+
+    #[cfg(not(unix))]
+    {
+        path.is_file() || path.with_extension("exe").is_file()
+    }
+}
+
+fn is_root() -> bool {
+    ExternalCommand::new("id").arg("-u").stdout().as_deref() == Some("0")
+}
+
+/////////////////////////////
+// Installable tools       //
+/////////////////////////////
+
+/// A tool scud can install.
+///
+/// Properties:
+///
+/// * `name`: Display name of the tool.
+/// * `program`: Name of the executable.
+/// * `setup_command`: scud command installing the tool.
+/// * `apt`, `dnf`, `pacman`, `brew`, `nix`: Name of the package (attribute
+///   for nix) per package manager, if the tool is packaged for it.
+/// * `install_url`: Installation instructions for when it isn't.
+pub struct Installable {
+    pub name: &'static str,
+    pub program: &'static str,
+    pub setup_command: &'static str,
+    pub apt: Option<&'static str>,
+    pub dnf: Option<&'static str>,
+    pub pacman: Option<&'static str>,
+    pub brew: Option<&'static str>,
+    pub nix: Option<&'static str>,
+    pub install_url: &'static str,
+}
+
+impl Installable {
+    /// The package providing the tool for the given package manager.
+    pub fn package(&self, package_manager: PackageManager) -> Option<&'static str> {
+        match package_manager {
+            PackageManager::Apt => self.apt,
+            PackageManager::Dnf => self.dnf,
+            PackageManager::Pacman => self.pacman,
+            PackageManager::Brew => self.brew,
+            PackageManager::Nix => self.nix,
+        }
+    }
+}
+
+pub const GIT: Installable = Installable {
+    name: "Git",
+    program: "git",
+    setup_command: "scud setup vcs git",
+    apt: Some("git"),
+    dnf: Some("git"),
+    pacman: Some("git"),
+    brew: Some("git"),
+    nix: Some("nixpkgs.git"),
+    install_url: "https://git-scm.com/downloads",
+};
+
+pub const MERCURIAL: Installable = Installable {
+    name: "Mercurial",
+    program: "hg",
+    setup_command: "scud setup vcs mercurial",
+    apt: Some("mercurial"),
+    dnf: Some("mercurial"),
+    pacman: Some("mercurial"),
+    brew: Some("mercurial"),
+    nix: Some("nixpkgs.mercurial"),
+    install_url: "https://www.mercurial-scm.org/downloads",
+};
+
+pub const BREEZY: Installable = Installable {
+    name: "Breezy",
+    program: "brz",
+    setup_command: "scud setup vcs breezy",
+    apt: Some("brz"),
+    dnf: Some("breezy"),
+    pacman: Some("breezy"),
+    brew: Some("breezy"),
+    nix: Some("nixpkgs.breezy"),
+    install_url: "https://www.breezy-vcs.org/pages/download.html",
+};
+
+pub const SUBVERSION: Installable = Installable {
+    name: "Subversion",
+    program: "svn",
+    setup_command: "scud setup vcs subversion",
+    apt: Some("subversion"),
+    dnf: Some("subversion"),
+    pacman: Some("subversion"),
+    brew: Some("subversion"),
+    nix: Some("nixpkgs.subversion"),
+    install_url: "https://subversion.apache.org/packages.html",
+};
+
+pub const JUJUTSU: Installable = Installable {
+    name: "Jujutsu",
+    program: "jj",
+    setup_command: "scud setup vcs jujutsu",
+    apt: None,
+    dnf: None,
+    pacman: Some("jujutsu"),
+    brew: Some("jj"),
+    nix: Some("nixpkgs.jujutsu"),
+    install_url: "https://jj-vcs.github.io/jj/latest/install-and-setup/",
+};
+
+pub const FOSSIL: Installable = Installable {
+    name: "Fossil",
+    program: "fossil",
+    setup_command: "scud setup vcs fossil",
+    apt: Some("fossil"),
+    dnf: Some("fossil"),
+    pacman: Some("fossil"),
+    brew: Some("fossil"),
+    nix: Some("nixpkgs.fossil"),
+    install_url: "https://fossil-scm.org/home/uv/download.html",
+};
+
+pub const GITHUB_CLI: Installable = Installable {
+    name: "GitHub CLI",
+    program: "gh",
+    setup_command: "scud setup sc github",
+    apt: Some("gh"),
+    dnf: Some("gh"),
+    pacman: Some("github-cli"),
+    brew: Some("gh"),
+    nix: Some("nixpkgs.gh"),
+    install_url: "https://github.com/cli/cli#installation",
+};
+
+pub const GITLAB_CLI: Installable = Installable {
+    name: "GitLab CLI",
+    program: "glab",
+    setup_command: "scud setup sc gitlab",
+    apt: None,
+    dnf: Some("glab"),
+    pacman: Some("glab"),
+    brew: Some("glab"),
+    nix: Some("nixpkgs.glab"),
+    install_url: "https://gitlab.com/gitlab-org/cli#installation",
+};
+
+pub const ONEFETCH: Installable = Installable {
+    name: "onefetch",
+    program: "onefetch",
+    setup_command: "scud setup tool onefetch",
+    apt: None,
+    dnf: None,
+    pacman: Some("onefetch"),
+    brew: Some("onefetch"),
+    nix: Some("nixpkgs.onefetch"),
+    install_url: "https://github.com/o2sh/onefetch/wiki/Installation",
+};
+
+/// Every tool scud can install, in the order they are listed.
+pub const INSTALLABLES: &[Installable] = &[
+    GIT, MERCURIAL, BREEZY, SUBVERSION, JUJUTSU, FOSSIL, GITHUB_CLI, GITLAB_CLI,
+    ONEFETCH,
+];
+
+/// A command installing a tool with a package manager.
+pub struct InstallCommand {
+    pub program: String,
+    pub args: Vec<String>,
+}
+
+impl fmt::Display for InstallCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.program, self.args.join(" "))
+    }
+}
+
+/// Builds the command installing the tool with the given package manager,
+/// prefixed with `sudo` when the package manager needs root and scud isn't
+/// running as root already.
+///
+/// Returns:
+///
+/// None when the tool isn't packaged for the package manager
+pub fn get_install_command(
+    package_manager: PackageManager,
+    installable: &Installable,
+) -> Option<InstallCommand> {
+    let package = installable.package(package_manager)?;
+
+    let mut args = package_manager
+        .install_args()
+        .iter()
+        .map(|arg| arg.to_string())
+        .collect::<Vec<String>>();
+    args.push(package.to_string());
+
+    if package_manager.needs_root() && !is_root() && is_in_path("sudo") {
+        args.insert(0, package_manager.program().to_string());
+        Some(InstallCommand {
+            program: "sudo".to_string(),
+            args,
+        })
+    } else {
+        Some(InstallCommand {
+            program: package_manager.program().to_string(),
+            args,
+        })
+    }
+}
@@ -0,0 +1,914 @@
+use std::{
+    env, fs,
+    path::{Component, Path, PathBuf},
+};
+
+use colored::Colorize;
+use dialoguer::{theme::ColorfulTheme, Confirm, Input, Select};
+
+use crate::{
+    cli::cli::{IdentityScope, SetupIdentity, SigningFormat},
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::{ScudError, ScudResult},
+    logging::theme::Themed,
+    runner::ExternalCommand,
+};
+
+/// The keys making up an identity, copied into the temporary repository when
+/// verifying that commits can be signed.
+const IDENTITY_KEYS: &[&str] = &[
+    "user.name",
+    "user.email",
+    "user.signingkey",
+    "gpg.format",
+    "gpg.program",
+    "gpg.ssh.program",
+];
+
+/// Where `git config` reads and writes the identity.
+pub enum ConfigTarget {
+    /// ~/.gitconfig
+    Global,
+    /// .git/config of the current repository
+    Repo,
+    /// A file included for the repositories under a directory
+    File(PathBuf),
+}
+
+impl ConfigTarget {
+    fn args(&self) -> Vec<String> {
+        match self {
+            ConfigTarget::Global => vec!["--global".to_string()],
+            ConfigTarget::Repo => vec!["--local".to_string()],
+            ConfigTarget::File(path) => {
+                vec!["--file".to_string(), path.display().to_string()]
+            }
+        }
+    }
+}
+
+/// A secret GPG key commits can be signed with.
+struct GpgKey {
+    id: String,
+    user_id: String,
+}
+
+////////////////////////////////
+// Reading and writing config //
+////////////////////////////////
+
+/// Reads a config value, from the given target or, when None, the value git
+/// would use in the current directory.
+fn get_config(
+    target: Option<&ConfigTarget>,
+    key: &str,
+    working_dir: &Path,
+) -> Option<String> {
+    let mut command = ExternalCommand::git(["config"]).current_dir(working_dir);
+
+    if let Some(target) = target {
+        command = command.args(target.args());
+    }
+
+    command
+        .args(["--get", key])
+        .stdout()
+        .filter(|value| !value.is_empty())
+}
+
+/// Writes a config value, printing the equivalent git command. Fails when git
+/// refuses to write it.
+fn set_config(
+    target: &ConfigTarget,
+    key: &str,
+    value: &str,
+    dry_run: bool,
+    working_dir: &Path,
+) -> ScudResult {
+    let mut args = vec!["config".to_string()];
+    args.extend(target.args());
+    args.push(key.to_string());
+    args.push(value.to_string());
+
+    println!(
+        "    {} {}",
+        "git".green().italic(),
+        args.iter()
+            .map(|arg| quote(arg))
+            .collect::<Vec<String>>()
+            .join(" ")
+            .green()
+            .italic()
+    );
+
+    if dry_run {
+        return Ok(());
+    }
+
+    let output = ExternalCommand::git(&args)
+        .current_dir(working_dir)
+        .output()?;
+
+    if !output.success() {
+        return Err(ScudError::failed(
+            format!("Failed to set {}", key),
+            output.message(),
+        ));
+    }
+
+    Ok(())
+}
+
+fn quote(arg: &str) -> String {
+    if arg.contains(char::is_whitespace) || arg.is_empty() {
+        format!("'{}'", arg.replace('\'', "'\\''"))
+    } else {
+        arg.to_string()
+    }
+}
+
+fn home_dir() -> PathBuf {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from("."))
+}
+
+/// Expands a leading `~` and makes the path absolute, resolving relative
+/// paths against the given directory.
+fn expand_path(path: &str, relative_to: &Path) -> PathBuf {
+    let path = match path.strip_prefix('~') {
+        Some(rest) => home_dir().join(rest.trim_start_matches('/')),
+        None => relative_to.join(path),
+    };
+
+    if path.is_absolute() {
+        path
+    } else {
+        env::current_dir()
+            .map(|current_dir| current_dir.join(&path))
+            .unwrap_or(path)
+    }
+}
+
+fn is_inside_repository(working_dir: &Path) -> bool {
+    ExternalCommand::git(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(working_dir)
+        .stdout()
+        .is_some()
+}
+
+/// The per-directory identities configured via `includeIf` sections, as
+/// pairs of the directory and the included file.
+fn get_directory_identities() -> Vec<(String, PathBuf)> {
+    // With -z, the key ends with a newline and the value with a NUL, so
+    // neither can be confused with spaces in the paths
+    let output = ExternalCommand::git([
+        "config",
+        "--global",
+        "-z",
+        "--get-regexp",
+        r"^includeif\..*\.path$",
+    ])
+    .stdout()
+    .unwrap_or_default();
+
+    output
+        .split('\0')
+        .filter_map(|entry| {
+            let (key, path) = entry.split_once('\n')?;
+            let condition = key.strip_prefix("includeif.")?.strip_suffix(".path")?;
+            let directory = condition
+                .strip_prefix("gitdir:")
+                .or_else(|| condition.strip_prefix("gitdir/i:"))?;
+            // Git resolves relative includes against ~/.gitconfig
+            Some((directory.to_string(), expand_path(path, &home_dir())))
+        })
+        .collect()
+}
+
+/////////////////////////////////
+// Logging the current config  //
+/////////////////////////////////
+
+fn describe_identity(target: &ConfigTarget, working_dir: &Path) -> String {
+    let name = get_config(Some(target), "user.name", working_dir);
+    let email = get_config(Some(target), "user.email", working_dir);
+
+    let identity = match (name, email) {
+        (None, None) => "not set".muted().italic().to_string(),
+        (name, email) => format!(
+            "{} {}",
+            name.unwrap_or_else(|| "<no name>".to_string()).cyan(),
+            format!("<{}>", email.unwrap_or_default()).cyan().italic()
+        ),
+    };
+
+    let signing = match (
+        get_config(Some(target), "commit.gpgsign", working_dir).as_deref(),
+        get_config(Some(target), "user.signingkey", working_dir),
+    ) {
+        (Some("true"), Some(key)) => format!(
+            "{} {}",
+            format!(
+                "signed with {}",
+                get_config(Some(target), "gpg.format", working_dir)
+                    .unwrap_or_else(|| "openpgp".to_string())
+            )
+            .green(),
+            format!("({})", key).muted().italic()
+        ),
+        _ => "unsigned".muted().italic().to_string(),
+    };
+
+    format!("{}, {}", identity, signing)
+}
+
+/// Logs who commits are attributed to at each level of the configuration.
+pub fn log_current_identity(working_dir: &Path) {
+    println!("{}\n", " IDENTITY: ".black().on_cyan());
+    println!(
+        "  {} {}",
+        "Global:".bright_yellow(),
+        describe_identity(&ConfigTarget::Global, working_dir)
+    );
+
+    if is_inside_repository(working_dir) {
+        println!(
+            "  {} {}",
+            "Repository:".bright_yellow(),
+            describe_identity(&ConfigTarget::Repo, working_dir)
+        );
+    }
+
+    for (directory, path) in get_directory_identities() {
+        println!(
+            "  {} {}",
+            format!("{}:", directory).bright_yellow(),
+            describe_identity(&ConfigTarget::File(path), working_dir)
+        );
+    }
+
+    println!();
+}
+
+///////////////////////////
+// Configuring identity  //
+///////////////////////////
+
+/// The file holding the identity of the repositories under the directory,
+/// named after its path relative to the home directory (e.g.
+/// ~/.gitconfig-work-src for ~/work/src) so directories sharing a name don't
+/// share an identity.
+fn get_include_file(directory: &Path) -> PathBuf {
+    let home = home_dir();
+    let relative = directory.strip_prefix(&home).unwrap_or(directory);
+
+    let label = relative
+        .components()
+        .filter_map(|component| match component {
+            Component::Normal(name) => Some(
+                name.to_string_lossy()
+                    .chars()
+                    .map(|c| {
+                        if c.is_alphanumeric() || c == '-' {
+                            c
+                        } else {
+                            '_'
+                        }
+                    })
+                    .collect::<String>(),
+            ),
+            _ => None,
+        })
+        .collect::<Vec<String>>()
+        .join("-");
+
+    match label.is_empty() {
+        true => home.join(".gitconfig-directory"),
+        false => home.join(format!(".gitconfig-{}", label)),
+    }
+}
+
+/// Resolves where the identity is written to. A directory results in a
+/// separate file (e.g. ~/.gitconfig-work) which ~/.gitconfig includes for
+/// every repository under the directory.
+fn resolve_target(
+    scope: IdentityScope,
+    directory: Option<&str>,
+    dry_run: bool,
+    working_dir: &Path,
+) -> ScudResult<ConfigTarget> {
+    if let Some(directory) = directory {
+        let directory = expand_path(directory, working_dir);
+        let directory = directory.canonicalize().unwrap_or(directory);
+
+        let path = get_include_file(&directory);
+
+        // Different paths can still end up with the same name (e.g. ~/a-b
+        // and ~/a/b), and writing to the file would change both identities
+        let used_by =
+            get_directory_identities()
+                .into_iter()
+                .find(|(other, other_path)| {
+                    *other_path == path
+                        && expand_path(other.trim_end_matches('/'), &home_dir())
+                            != directory
+                });
+        if let Some((other, _)) = used_by {
+            return Err(ScudError::failed(
+                format!("{} is already used by {}", path.display(), other),
+                "Writing the identity to it would change the identity of the \
+                 repositories under both directories.",
+            ));
+        }
+
+        // The trailing slash matches every repository under the directory
+        let directory =
+            format!("{}/", directory.display().to_string().trim_end_matches('/'));
+
+        set_config(
+            &ConfigTarget::Global,
+            &format!("includeIf.gitdir:{}.path", directory),
+            &path.display().to_string(),
+            dry_run,
+            working_dir,
+        )?;
+
+        return Ok(ConfigTarget::File(path));
+    }
+
+    match scope {
+        IdentityScope::Global => Ok(ConfigTarget::Global),
+        IdentityScope::Repo => {
+            if !is_inside_repository(working_dir) {
+                return Err(ScudError::failed(
+                    "Not inside a git repository",
+                    "An identity scoped to the repository can only be set from \
+                     within one.",
+                )
+                .with_hint(
+                    format!(
+                        "{}",
+                        "Set the identity for every repository instead with"
+                            .yellow()
+                    ),
+                    "scud setup identity --scope global",
+                ));
+            }
+
+            Ok(ConfigTarget::Repo)
+        }
+    }
+}
+
+fn validate_email(email: &str) -> Result<(), &'static str> {
+    let valid = email
+        .split_once('@')
+        .map(|(user, domain)| !user.is_empty() && !domain.is_empty())
+        .unwrap_or(false);
+
+    if valid && !email.contains(char::is_whitespace) {
+        Ok(())
+    } else {
+        Err("Please enter a valid email address (e.g. jane@example.com)")
+    }
+}
+
+/////////////////////////
+// Signing keys        //
+/////////////////////////
+
+fn list_gpg_keys() -> Vec<GpgKey> {
+    let output = ExternalCommand::new("gpg")
+        .args(["--list-secret-keys", "--keyid-format=long", "--with-colons"])
+        .stdout()
+        .unwrap_or_default();
+
+    let mut keys: Vec<GpgKey> = vec![];
+
+    for line in output.lines() {
+        let fields = line.split(':').collect::<Vec<&str>>();
+
+        match fields.first() {
+            Some(&"sec") if fields.len() > 4 => keys.push(GpgKey {
+                id: fields[4].to_string(),
+                user_id: String::new(),
+            }),
+            // The first user id following a key belongs to it
+            Some(&"uid") if fields.len() > 9 => {
+                if let Some(key) = keys.last_mut() {
+                    if key.user_id.is_empty() {
+                        key.user_id = fields[9].to_string();
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    keys
+}
+
+fn list_ssh_keys() -> Vec<PathBuf> {
+    let mut keys = fs::read_dir(home_dir().join(".ssh"))
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.extension().map(|ext| ext == "pub").unwrap_or(false)
+                })
+                .collect::<Vec<PathBuf>>()
+        })
+        .unwrap_or_default();
+    keys.sort();
+    keys
+}
+
+fn no_signing_keys(format: SigningFormat) -> ScudError {
+    let (kind, command) = match format {
+        SigningFormat::Ssh => ("SSH", "ssh-keygen -t ed25519 -C <email>"),
+        _ => ("GPG", "gpg --full-generate-key"),
+    };
+
+    ScudError::failed(
+        format!("No {} keys found", kind),
+        "A key is needed to sign commits with.",
+    )
+    .with_hint(format!("{}", "Generate a key with".yellow()), command)
+}
+
+/// Picks the key commits are signed with, from the given key or the keys
+/// found locally (prompting when there's more than one and `interactive`).
+fn select_signing_key(
+    format: SigningFormat,
+    signing_key: Option<&str>,
+    interactive: bool,
+    working_dir: &Path,
+) -> ScudResult<Option<String>> {
+    if let Some(signing_key) = signing_key {
+        return Ok(Some(match format {
+            SigningFormat::Ssh if !signing_key.starts_with("key::") => {
+                expand_path(signing_key, working_dir).display().to_string()
+            }
+            _ => signing_key.to_string(),
+        }));
+    }
+
+    let keys = match format {
+        SigningFormat::Gpg => list_gpg_keys()
+            .into_iter()
+            .map(|key| (key.id.clone(), format!("{} {}", key.id, key.user_id)))
+            .collect::<Vec<(String, String)>>(),
+        SigningFormat::Ssh => list_ssh_keys()
+            .into_iter()
+            .map(|path| (path.display().to_string(), path.display().to_string()))
+            .collect(),
+        SigningFormat::None => return Ok(None),
+    };
+
+    match keys.len() {
+        0 => Err(no_signing_keys(format)),
+        1 => Ok(keys.into_iter().next().map(|(key, _)| key)),
+        _ if !interactive => Ok(keys.into_iter().next().map(|(key, _)| key)),
+        _ => {
+            let selection = Select::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!(
+                    "{}",
+                    "Select the key to sign commits with:"
+                        .bright_yellow()
+                        .italic()
+                ))
+                .items(
+                    &keys
+                        .iter()
+                        .map(|(_, description)| description.as_str())
+                        .collect::<Vec<&str>>(),
+                )
+                .default(0)
+                .interact()?;
+
+            Ok(Some(keys[selection].0.clone()))
+        }
+    }
+}
+
+/// Configures whether and how commits are signed.
+///
+/// Returns:
+///
+/// Whether commits are signed now
+fn configure_signing(
+    target: &ConfigTarget,
+    format: SigningFormat,
+    signing_key: Option<&str>,
+    interactive: bool,
+    dry_run: bool,
+    working_dir: &Path,
+) -> ScudResult<bool> {
+    if format == SigningFormat::None {
+        set_config(target, "commit.gpgsign", "false", dry_run, working_dir)?;
+        return Ok(false);
+    }
+
+    let key =
+        match select_signing_key(format, signing_key, interactive, working_dir)? {
+            Some(key) => key,
+            None => return Ok(false),
+        };
+
+    let gpg_format = match format {
+        SigningFormat::Ssh => "ssh",
+        _ => "openpgp",
+    };
+
+    set_config(target, "gpg.format", gpg_format, dry_run, working_dir)?;
+    set_config(target, "user.signingkey", &key, dry_run, working_dir)?;
+    set_config(target, "commit.gpgsign", "true", dry_run, working_dir)?;
+
+    Ok(true)
+}
+
+///////////////////////////////
+// Verifying signed commits  //
+///////////////////////////////
+
+/// Creates a signed commit in a temporary repository with the identity of the
+/// target, and verifies its signature.
+pub fn verify_signing(
+    target: Option<&ConfigTarget>,
+    working_dir: &Path,
+) -> ScudResult {
+    let config = IDENTITY_KEYS
+        .iter()
+        .filter_map(|key| {
+            target
+                .and_then(|target| get_config(Some(target), key, working_dir))
+                .or_else(|| get_config(None, key, working_dir))
+                .map(|value| (key.to_string(), value))
+        })
+        .collect::<Vec<(String, String)>>();
+    let get = |key: &str| {
+        config
+            .iter()
+            .find(|(config_key, _)| config_key == key)
+            .map(|(_, value)| value.clone())
+    };
+
+    let signing_key = get("user.signingkey").ok_or_else(|| {
+        ScudError::failed(
+            "No signing key configured",
+            "user.signingkey isn't set, so commits can't be signed.",
+        )
+        .with_hint(
+            format!("{}", "Configure commit signing with".yellow()),
+            "scud setup identity --signing ssh",
+        )
+    })?;
+
+    let temp_repository =
+        env::temp_dir().join(format!("scud-signing-test-{}", std::process::id()));
+    let _ = fs::remove_dir_all(&temp_repository);
+
+    let mut args = config
+        .iter()
+        .flat_map(|(key, value)| ["-c".to_string(), format!("{}={}", key, value)])
+        .collect::<Vec<String>>();
+
+    // Verifying SSH signatures requires a list of trusted keys
+    if get("gpg.format").as_deref() == Some("ssh") {
+        let public_key = match signing_key.strip_prefix("key::") {
+            Some(public_key) => public_key.to_string(),
+            None => fs::read_to_string(expand_path(&signing_key, working_dir))
+                .unwrap_or_default(),
+        };
+        let allowed_signers = temp_repository.join("allowed_signers");
+
+        if fs::create_dir_all(&temp_repository).is_ok() {
+            let _ = fs::write(
+                &allowed_signers,
+                format!(
+                    "{} {}\n",
+                    get("user.email").unwrap_or_else(|| "*".to_string()),
+                    public_key.trim()
+                ),
+            );
+        }
+
+        args.push("-c".to_string());
+        args.push(format!(
+            "gpg.ssh.allowedSignersFile={}",
+            allowed_signers.display()
+        ));
+    }
+
+    let run = |git_args: &[&str]| {
+        ExternalCommand::git(&args)
+            .args(git_args)
+            .current_dir(&temp_repository)
+            .output()
+    };
+
+    let _ = fs::create_dir_all(&temp_repository);
+
+    let result = run(&["init", "--quiet"])
+        .and_then(|_| {
+            run(&[
+                "commit",
+                "--allow-empty",
+                "--no-verify",
+                "--gpg-sign",
+                "--message",
+                "Test commit signed by scud setup identity",
+            ])
+        })
+        .and_then(|commit| {
+            if commit.success() {
+                run(&["verify-commit", "HEAD"]).map(|verify| (commit, Some(verify)))
+            } else {
+                Ok((commit, None))
+            }
+        });
+
+    let _ = fs::remove_dir_all(&temp_repository);
+
+    let (commit, verify) = result?;
+
+    match verify {
+        Some(verify) if verify.success() => {
+            println!(
+                "\n    {}  {} {}\n",
+                " PASS ".black().on_bright_green(),
+                "Signed test commit verified".bright_green(),
+                format!("(signed with {})", signing_key).muted().italic()
+            );
+
+            Ok(())
+        }
+        verify => {
+            let (subject, output) = match verify {
+                Some(verify) => ("Verifying the signed test commit failed", verify),
+                None => ("Signing a test commit failed", commit),
+            };
+
+            Err(ScudError::failed(subject, output.message()).with_hint(
+                format!(
+                    "{}",
+                    "Make sure the key is available to git (e.g. unlocked in \
+                     gpg-agent or added to ssh-agent) and check the key with"
+                        .yellow()
+                ),
+                match get("gpg.format").as_deref() {
+                    Some("ssh") => "ssh-keygen -y -f <private key>",
+                    _ => "echo test | gpg --clearsign",
+                },
+            ))
+        }
+    }
+}
+
+//////////////////////
+// Executors        //
+//////////////////////
+
+/// Configures the identity commits are attributed to along with commit
+/// signing. Walks through the setup interactively when no options are given.
+///
+/// Arguments:
+///
+/// * `identity_options`: Struct that contains the options that were passed to
+///   the command via user input (e.g. --email, --dir, --signing).
+/// * `working_dir`: The directory to operate on, which is the current one
+///   unless given with --directory.
+pub fn execute_setup_identity(
+    identity_options: SetupIdentity,
+    working_dir: &Path,
+) -> ScudResult {
+    log_current_identity(working_dir);
+
+    let interactive = identity_options.name.is_none()
+        && identity_options.email.is_none()
+        && identity_options.dir.is_none()
+        && identity_options.signing.is_none()
+        && identity_options.signing_key.is_none()
+        && !identity_options.verify;
+
+    if interactive {
+        return execute_setup_identity_interactive(
+            identity_options.dry_run,
+            working_dir,
+        );
+    }
+
+    if let Some(email) = &identity_options.email {
+        validate_email(email).map_err(|error| {
+            ScudError::failed(format!("Invalid email {}", email), error)
+        })?;
+    }
+
+    let changes_config = identity_options.name.is_some()
+        || identity_options.email.is_some()
+        || identity_options.dir.is_some()
+        || identity_options.signing.is_some()
+        || identity_options.signing_key.is_some();
+
+    let target = match changes_config {
+        true => Some(resolve_target(
+            identity_options.scope,
+            identity_options.dir.as_deref(),
+            identity_options.dry_run,
+            working_dir,
+        )?),
+        false => None,
+    };
+
+    let mut signs_commits = false;
+
+    if let Some(target) = &target {
+        if let Some(name) = &identity_options.name {
+            set_config(
+                target,
+                "user.name",
+                name,
+                identity_options.dry_run,
+                working_dir,
+            )?;
+        }
+
+        if let Some(email) = &identity_options.email {
+            set_config(
+                target,
+                "user.email",
+                email,
+                identity_options.dry_run,
+                working_dir,
+            )?;
+        }
+
+        // A key without a format is assumed to be an SSH key when it looks
+        // like a public key, or a GPG key id otherwise
+        let signing = identity_options.signing.or_else(|| {
+            identity_options.signing_key.as_ref().map(|key| {
+                if key.ends_with(".pub") || key.starts_with("key::") {
+                    SigningFormat::Ssh
+                } else {
+                    SigningFormat::Gpg
+                }
+            })
+        });
+
+        if let Some(signing) = signing {
+            signs_commits = configure_signing(
+                target,
+                signing,
+                identity_options.signing_key.as_deref(),
+                false,
+                identity_options.dry_run,
+                working_dir,
+            )?;
+        }
+    }
+
+    if identity_options.dry_run {
+        println!();
+        log_diagnostic(DiagnosticKind::DryRun {
+            command: "setup identity",
+        });
+        return Ok(());
+    }
+
+    if signs_commits || identity_options.verify {
+        verify_signing(target.as_ref(), working_dir)?;
+    }
+
+    Ok(())
+}
+
+fn execute_setup_identity_interactive(
+    dry_run: bool,
+    working_dir: &Path,
+) -> ScudResult {
+    let mut scopes = vec!["Every repository (~/.gitconfig)"];
+    if is_inside_repository(working_dir) {
+        scopes.push("This repository only (.git/config)");
+    }
+    scopes.push("Repositories under a directory (e.g. ~/work)");
+
+    let scope = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "{}",
+            "Where should the identity apply?".bright_yellow().italic()
+        ))
+        .items(&scopes)
+        .default(0)
+        .interact()?;
+
+    let target = match scopes[scope] {
+        scope if scope.starts_with("Every") => ConfigTarget::Global,
+        scope if scope.starts_with("This") => ConfigTarget::Repo,
+        _ => {
+            let directory: String = Input::with_theme(&ColorfulTheme::default())
+                .with_prompt(format!(
+                    "{}",
+                    "Directory containing the repositories:"
+                        .bright_yellow()
+                        .italic()
+                ))
+                .interact_text()?;
+
+            resolve_target(
+                IdentityScope::Global,
+                Some(&directory),
+                dry_run,
+                working_dir,
+            )?
+        }
+    };
+
+    let current_name = get_config(Some(&target), "user.name", working_dir)
+        .or_else(|| get_config(None, "user.name", working_dir))
+        .unwrap_or_default();
+    let current_email = get_config(Some(&target), "user.email", working_dir)
+        .or_else(|| get_config(None, "user.email", working_dir))
+        .unwrap_or_default();
+
+    let name: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("{}", "Name:".bright_yellow().italic()))
+        .with_initial_text(current_name)
+        .validate_with(|input: &String| -> Result<(), &str> {
+            if input.trim().is_empty() {
+                Err("Please enter a name")
+            } else {
+                Ok(())
+            }
+        })
+        .interact_text()?;
+
+    let email: String = Input::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!("{}", "Email:".bright_yellow().italic()))
+        .with_initial_text(current_email)
+        .validate_with(|input: &String| validate_email(input))
+        .interact_text()?;
+
+    println!();
+    set_config(&target, "user.name", name.trim(), dry_run, working_dir)?;
+    set_config(&target, "user.email", email.trim(), dry_run, working_dir)?;
+    println!();
+
+    let signing_options = [
+        "Keep the current signing configuration",
+        "Sign commits with an SSH key",
+        "Sign commits with a GPG key",
+        "Don't sign commits",
+    ];
+
+    let signing = Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "{}",
+            "How should commits be signed?".bright_yellow().italic()
+        ))
+        .items(&signing_options)
+        .default(0)
+        .interact()?;
+
+    let signing = match signing {
+        1 => Some(SigningFormat::Ssh),
+        2 => Some(SigningFormat::Gpg),
+        3 => Some(SigningFormat::None),
+        _ => None,
+    };
+
+    let signs_commits = match signing {
+        Some(signing) => {
+            println!();
+            configure_signing(&target, signing, None, true, dry_run, working_dir)?
+        }
+        None => false,
+    };
+
+    if dry_run {
+        println!();
+        log_diagnostic(DiagnosticKind::DryRun {
+            command: "setup identity",
+        });
+        return Ok(());
+    }
+
+    if signs_commits {
+        let verify = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "{}",
+                "Verify signing with a test commit in a temporary repository?"
+                    .bright_yellow()
+                    .italic()
+            ))
+            .default(true)
+            .interact()?;
+
+        if verify {
+            verify_signing(Some(&target), working_dir)?;
+        }
+    }
+
+    Ok(())
+}
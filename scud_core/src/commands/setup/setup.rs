@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use crate::{
+    cli::cli::{
+        Setup, SetupCommands, SetupToolKind, SetupVcsKind, SourceControlProvider,
+    },
+    commands::setup::{
+        executors::{execute_setup_info, execute_setup_install, execute_setup_list},
+        helpers::{
+            BREEZY, FOSSIL, GIT, GITHUB_CLI, GITLAB_CLI, JUJUTSU, MERCURIAL,
+            ONEFETCH, SUBVERSION,
+        },
+        identity::execute_setup_identity,
+    },
+    error::ScudResult,
+};
+
+/// Executes the setup command along with its subcommands
+///
+/// Arguments:
+///
+/// * `setup_options`: Struct that contains the subcommand and options that were
+///   passed to the command via user input (e.g. vcs git, --dry-run, --info).
+/// * `working_dir`: The directory to operate on, which is the current one
+///   unless given with --directory.
+pub fn process_setup_commands(
+    setup_options: Setup,
+    working_dir: &Path,
+) -> ScudResult {
+    if setup_options.info {
+        execute_setup_info();
+        return Ok(());
+    }
+
+    match setup_options.command {
+        Some(SetupCommands::Vcs(vcs_options)) => {
+            let installable = match vcs_options.vcs {
+                SetupVcsKind::Git => &GIT,
+                SetupVcsKind::Mercurial => &MERCURIAL,
+                SetupVcsKind::Breezy => &BREEZY,
+                SetupVcsKind::Subversion => &SUBVERSION,
+                SetupVcsKind::Jujutsu => &JUJUTSU,
+                SetupVcsKind::Fossil => &FOSSIL,
+            };
+            execute_setup_install(installable, vcs_options.dry_run, working_dir)?;
+        }
+        Some(SetupCommands::Sc(sc_options)) => {
+            let installable = match sc_options.provider {
+                SourceControlProvider::Github => &GITHUB_CLI,
+                SourceControlProvider::Gitlab => &GITLAB_CLI,
+            };
+            execute_setup_install(installable, sc_options.dry_run, working_dir)?;
+        }
+        Some(SetupCommands::Tool(tool_options)) => {
+            let installable = match tool_options.tool {
+                SetupToolKind::Onefetch => &ONEFETCH,
+            };
+            execute_setup_install(installable, tool_options.dry_run, working_dir)?;
+        }
+        Some(SetupCommands::Identity(identity_options)) => {
+            execute_setup_identity(identity_options, working_dir)?;
+        }
+        None => execute_setup_list(),
+    }
+
+    Ok(())
+}
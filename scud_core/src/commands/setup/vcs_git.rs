@@ -1,5 +0,0 @@
-// TODO potentially refactor to setup/vcs/git/git.rs
-
-pub fn setup_git_command(unstage_options: SetupGit, start_time: SystemTime) {
-
-}
@@ -0,0 +1,281 @@
+use std::path::Path;
+
+use colored::Colorize;
+
+use crate::{
+    commands::commit::helpers::is_commit_pushed_git,
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::{ScudError, ScudResult},
+    logging::theme::Themed,
+    runner::ExternalCommand,
+    state::executors::execute_state_git,
+};
+
+/// A `fixup!` (or `squash!`/`amend!`) commit along with the commit it targets.
+///
+/// Properties:
+///
+/// * `hash`: The hash of the fixup commit.
+/// * `subject`: The subject of the fixup commit (e.g. `fixup! feat: add x`).
+/// * `target_hash`: The hash of the commit being fixed up.
+/// * `target_subject`: The subject of the commit being fixed up.
+#[derive(Debug, Clone)]
+pub struct PendingFixup {
+    pub hash: String,
+    pub subject: String,
+    pub target_hash: String,
+    pub target_subject: String,
+}
+
+pub fn execute_squash_fixups_dry_run(working_dir: &Path) -> ScudResult {
+    let pending_fixups = get_pending_fixups_git(working_dir)?;
+
+    if pending_fixups.is_empty() {
+        log_no_pending_fixups();
+    } else {
+        log_pending_fixups(&pending_fixups);
+    }
+
+    log_diagnostic(DiagnosticKind::DryRun {
+        command: "squash-fixups",
+    });
+
+    Ok(())
+}
+
+pub fn execute_squash_fixups_info() {
+    log_diagnostic(DiagnosticKind::ScudCommandInfo {
+        command: "squash-fixups",
+        description: "This command is used to squash fixup commits (e.g. created \
+                      via scud commit --fixup) into the commits they target. The \
+                      rebase runs non-interactively, so the todo list generated by \
+                      the autosquash is applied as is.",
+    });
+    log_diagnostic(DiagnosticKind::VCSInfo {
+        command_name: "squash-fixups",
+        git_command: &format!(
+            "{} {}",
+            "GIT_SEQUENCE_EDITOR=: git rebase -i --autosquash --autostash <base>",
+            "(base is the parent of the oldest commit being fixed up)"
+                .bright_yellow()
+        ),
+        mercurial_command: &format!("{}", "(not yet supported)".bright_yellow()),
+        breezy_command: &format!("{}", "(not yet supported)".bright_yellow()),
+        other_commands: &[],
+    });
+}
+
+//////////////////////////////////////////////////////////////
+// Functions handle the execution of each the supported     //
+// underlying VCS implementations of the squash-fixups      //
+// command                                                  //
+//////////////////////////////////////////////////////////////
+
+/// Runs a non-interactive autosquash rebase starting from the parent of the
+/// oldest commit targeted by a fixup commit.
+pub fn execute_squash_fixups_git(working_dir: &Path) -> ScudResult {
+    let pending_fixups = get_pending_fixups_git(working_dir)?;
+
+    if pending_fixups.is_empty() {
+        log_no_pending_fixups();
+        return Ok(());
+    }
+
+    log_pending_fixups(&pending_fixups);
+
+    // The fixups are ordered by the position of their
+    // target in the history, so the oldest target is last
+    let oldest_target = &pending_fixups[pending_fixups.len() - 1].target_hash;
+
+    if pending_fixups.iter().any(|pending_fixup| {
+        is_commit_pushed_git(&pending_fixup.target_hash, working_dir)
+    }) {
+        log_diagnostic(DiagnosticKind::Warning {
+            subject: "Some of the commits being fixed up have already been pushed",
+            body: "Squashing rewrites history which already exists in the remote \
+                   repository. Pushing afterwards will require a force push.",
+        });
+    }
+
+    let base = ExternalCommand::git([
+        "rev-parse",
+        "--verify",
+        "--quiet",
+        &format!("{}^", oldest_target),
+    ])
+    .current_dir(working_dir)
+    .stdout()
+    // The oldest target is the root commit
+    .unwrap_or_else(|| "--root".to_string());
+
+    let output =
+        ExternalCommand::git(["rebase", "-i", "--autosquash", "--autostash", &base])
+            .current_dir(working_dir)
+            .env("GIT_SEQUENCE_EDITOR", ":")
+            .output()?;
+
+    if !output.success() {
+        return Err(ScudError::failed(
+            "git rebase --autosquash failed",
+            output.message(),
+        )
+        .with_hint(
+            format!(
+                "{}",
+                "Resolve the conflicts with scud conflicts, which continues the \
+                 rebase afterwards, or go back to where you started with"
+                    .yellow()
+            ),
+            "git rebase --abort",
+        ));
+    }
+
+    execute_state_git(working_dir)
+}
+
+pub fn execute_squash_fixups_mercurial() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud squash-fixups (mercurial)",
+    });
+}
+
+pub fn execute_squash_fixups_breezy() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud squash-fixups (breezy)",
+    });
+}
+
+pub fn execute_squash_fixups_subversion() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud squash-fixups (subversion)",
+    });
+}
+
+pub fn execute_squash_fixups_jujutsu() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud squash-fixups (jujutsu)",
+    });
+}
+
+pub fn execute_squash_fixups_fossil() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud squash-fixups (fossil)",
+    });
+}
+
+/// Finds every fixup commit in the recent history of the current branch along
+/// with the commit it targets (matched by subject or hash prefix, the same way
+/// `git rebase --autosquash` does).
+///
+/// Returns:
+///
+/// A vector of PendingFixup structs, most recent first
+pub fn get_pending_fixups_git(working_dir: &Path) -> ScudResult<Vec<PendingFixup>> {
+    let history = ExternalCommand::git(["log", "-n", "500", "--format=%H%x09%s"])
+        .current_dir(working_dir)
+        .output()?;
+
+    // An unborn branch has no history, and so no fixups either
+    let history = history
+        .stdout
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .map(|(hash, subject)| (hash.to_string(), subject.to_string()))
+        .collect::<Vec<(String, String)>>();
+
+    let mut pending_fixups = vec![];
+
+    for (index, (hash, subject)) in history.iter().enumerate() {
+        let target = match strip_fixup_prefixes(subject) {
+            Some(target) => target,
+            None => continue,
+        };
+
+        let target_commit =
+            history[index + 1..]
+                .iter()
+                .find(|(target_hash, target_subject)| {
+                    strip_fixup_prefixes(target_subject).is_none()
+                        && (*target_subject == target
+                            || (target.len() >= 7
+                                && target_hash.starts_with(target)))
+                });
+
+        if let Some((target_hash, target_subject)) = target_commit {
+            pending_fixups.push(PendingFixup {
+                hash: hash.clone(),
+                subject: subject.clone(),
+                target_hash: target_hash.clone(),
+                target_subject: target_subject.clone(),
+            });
+        }
+    }
+
+    // Order by position of the target so the oldest target is last
+    pending_fixups.sort_by_key(|pending_fixup| {
+        history
+            .iter()
+            .position(|(hash, _)| *hash == pending_fixup.target_hash)
+    });
+
+    Ok(pending_fixups)
+}
+
+/// Strips any number of `fixup! `, `squash! ` and `amend! ` prefixes from the
+/// subject of a commit.
+///
+/// Returns:
+///
+/// The subject of the targeted commit, or None if the commit isn't a fixup
+fn strip_fixup_prefixes(subject: &str) -> Option<&str> {
+    let mut stripped = subject;
+
+    while let Some(rest) = stripped
+        .strip_prefix("fixup! ")
+        .or_else(|| stripped.strip_prefix("squash! "))
+        .or_else(|| stripped.strip_prefix("amend! "))
+    {
+        stripped = rest;
+    }
+
+    if stripped.len() == subject.len() {
+        None
+    } else {
+        Some(stripped)
+    }
+}
+
+fn log_pending_fixups(pending_fixups: &[PendingFixup]) {
+    println!("\n{}\n", " FIXUP COMMITS: ".black().on_cyan());
+
+    for pending_fixup in pending_fixups {
+        println!(
+            "    {}  {} {} {}",
+            format!(" {} ", &pending_fixup.hash[..7])
+                .black()
+                .on_bright_yellow(),
+            pending_fixup.subject.bright_yellow().italic(),
+            "->".muted().italic(),
+            format!(
+                "{} {}",
+                &pending_fixup.target_hash[..7],
+                pending_fixup.target_subject
+            )
+            .cyan()
+            .italic()
+        );
+    }
+
+    println!();
+}
+
+fn log_no_pending_fixups() {
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{}{}{}",
+            "No ".magenta().italic(),
+            "fixup commits ".cyan().italic(),
+            "to squash on the current branch".magenta().italic()
+        ),
+    });
+}
@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use crate::{
+    cli::cli::{SquashFixups, VCS},
+    commands::squash_fixups::executors::{
+        execute_squash_fixups_breezy, execute_squash_fixups_dry_run,
+        execute_squash_fixups_fossil, execute_squash_fixups_git,
+        execute_squash_fixups_info, execute_squash_fixups_jujutsu,
+        execute_squash_fixups_mercurial, execute_squash_fixups_subversion,
+    },
+    error::ScudResult,
+    repository::Repository,
+};
+
+/// Executes the squash-fixups command with the given options
+///
+/// Arguments:
+///
+/// * `squash_fixups_options`: Struct that contains the options that were passed
+///   to the command via user input (e.g. dry-run, info).
+/// * `working_dir`: The directory to operate on, which is the current one
+///   unless given with --directory.
+pub fn squash_fixups_command(
+    squash_fixups_options: SquashFixups,
+    working_dir: &Path,
+) -> ScudResult {
+    if squash_fixups_options.dry_run {
+        execute_squash_fixups_dry_run(working_dir)?;
+    } else if squash_fixups_options.info {
+        execute_squash_fixups_info();
+    } else {
+        execute_squash_fixups(working_dir)?;
+    }
+
+    Ok(())
+}
+
+/// Detects the version control system, and then calls the appropriate
+/// function to squash the fixup commits
+fn execute_squash_fixups(working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
+
+    match vcs {
+        VCS::Git => execute_squash_fixups_git(working_dir)?,
+        VCS::Mercurial => execute_squash_fixups_mercurial(),
+        VCS::Breezy => execute_squash_fixups_breezy(),
+        VCS::Subversion => execute_squash_fixups_subversion(),
+        VCS::Jujutsu => execute_squash_fixups_jujutsu(),
+        VCS::Fossil => execute_squash_fixups_fossil(),
+    }
+
+    Ok(())
+}
@@ -1,3 +1,5 @@
 pub mod executors;
+pub mod helpers;
+pub mod interactive;
 pub mod logging;
 pub mod stage;
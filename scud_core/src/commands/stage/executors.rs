@@ -1,8 +1,31 @@
-use std::process::Command;
+use std::path::Path;
+
+use colored::Colorize;
 
 use crate::{
+    cli::cli::VCS,
+    commands::{
+        hooks::helpers::get_repository_root,
+        stage::{
+            helpers::{
+                apply_picks_git, get_diff_git, literal_pathspec, ChangeSelection,
+                PatchDirection,
+            },
+            interactive::{pick_files, pick_hunks},
+        },
+        state::helpers::{
+            escape_path_subversion, get_changes_breezy, get_changes_git,
+            get_changes_subversion, ChangeKind,
+        },
+        undo::journal::{record_operation_git, snapshot_git, Operation},
+    },
     diagnostics::{log_diagnostic, DiagnosticKind},
-    state::executors::execute_state_git,
+    error::ScudResult,
+    runner::ExternalCommand,
+    state::executors::{
+        execute_state_breezy, execute_state_fossil, execute_state_git,
+        execute_state_jujutsu, execute_state_mercurial, execute_state_subversion,
+    },
 };
 
 pub fn execute_stage_dry_run() {
@@ -23,41 +46,232 @@ pub fn execute_stage_info() {
     log_diagnostic(DiagnosticKind::VCSInfo {
         command_name: "stage",
         git_command: "git add -A",
-        mercurial_command: "Algorithm pseudocode:\n\t1. (Navigate to root of \
-                            repository)\n\t2. hg add .\n\t3. (Navigate back to \
-                            invocation directory)",
-        breezy_command: "Algorithm pseudocode:\n\t1. (Navigate to root of \
-                            repository)\n\t2. bzr add .\n\t3. (Navigate back to \
-                            invocation directory)",
+        mercurial_command: "hg addremove",
+        breezy_command: "brz add (from the root of the working tree), along \
+                         with brz remove for deleted files",
+        other_commands: &[
+            (
+                "Subversion",
+                "svn add --force . (from the root of the working copy), along with \
+                 svn delete for deleted files",
+            ),
+            (
+                "Jujutsu",
+                "none (jj snapshots every change into the working-copy commit)",
+            ),
+            ("Fossil", "fossil addremove (from the root of the checkout)"),
+        ],
     });
 }
 
-// TODO add doc comments
+/// Stages the changes of the current git repository: every change, the
+/// changes within the given paths, the files picked from a list
+/// (--interactive), or the hunks and lines picked from the changes
+/// (--patch).
+pub fn execute_stage_git(
+    selection: &ChangeSelection,
+    working_dir: &Path,
+) -> ScudResult {
+    // Record the index before staging so `scud undo` can restore it
+    let snapshot = snapshot_git(Operation::Stage, working_dir);
+
+    let staged = if selection.interactive || selection.patch {
+        stage_picked_changes_git(selection, working_dir)?
+    } else {
+        // git add -A doesn't emit any output unless it fails
+        ExternalCommand::git(["add", "-A", "--"])
+            .args(&selection.paths)
+            .current_dir(working_dir)
+            .run()?;
+        true
+    };
+
+    if staged {
+        record_operation_git(snapshot, working_dir);
+    }
+    execute_state_git(working_dir)
+}
+
+/// Asks which of the unstaged changes to stage, first by file and then by
+/// hunk when both --interactive and --patch are given.
+///
+/// Returns:
+///
+/// Whether anything was staged.
+fn stage_picked_changes_git(
+    selection: &ChangeSelection,
+    working_dir: &Path,
+) -> ScudResult<bool> {
+    let mut pathspecs = selection.paths.clone();
+    let mut staged = false;
+
+    if selection.interactive {
+        let changes = get_changes_git(working_dir, &selection.paths)?;
+
+        let mut files = changes
+            .unstaged
+            .iter()
+            .map(|change| {
+                (
+                    change.path.clone(),
+                    format!(
+                        "{}  {}",
+                        change.kind.label(),
+                        change.kind.describe(&change.path)
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+        files.extend(changes.untracked.iter().map(|path| {
+            (
+                path.clone(),
+                format!("{}  {}", " ?? ".black().on_yellow(), path.yellow()),
+            )
+        }));
+
+        if files.is_empty() {
+            log_nothing_to_stage();
+            return Ok(false);
+        }
+
+        let (untracked, tracked): (Vec<String>, Vec<String>) =
+            pick_files(&files, PatchDirection::Stage)?
+                .into_iter()
+                .partition(|path| changes.untracked.contains(path));
 
-pub fn execute_stage_git() {
-    // check for unstaged untracked files
+        // Without --patch the picked files are staged as a whole, and so are
+        // new files with it, as they have no hunks to pick from
+        let whole_files = if selection.patch {
+            untracked
+        } else {
+            [untracked, tracked.clone()].concat()
+        };
+        if !whole_files.is_empty() {
+            ExternalCommand::git(["add", "-A", "--"])
+                .args(whole_files.iter().map(|path| literal_pathspec(path)))
+                .current_dir(working_dir)
+                .run()?;
+            staged = true;
+        }
 
-    match Command::new("git").args(["add", "-A"]).output() {
-        Ok(_) /* git add -A doesn't emit any output */ => {
-            execute_state_git();
+        if !selection.patch || tracked.is_empty() {
+            return Ok(staged);
         }
-        Err(error) => log_diagnostic(
-            DiagnosticKind::Error {
-                subject: "failed to stage (git)",
-                body: &format!("{}", error)
-            }
-        )
+        pathspecs = tracked.iter().map(|path| literal_pathspec(path)).collect();
     }
+
+    let files = get_diff_git(PatchDirection::Stage, &pathspecs, working_dir)?;
+    if files.is_empty() {
+        if !staged {
+            log_nothing_to_stage();
+        }
+        return Ok(staged);
+    }
+
+    let picks = pick_hunks(&files, PatchDirection::Stage)?;
+    Ok(
+        apply_picks_git(&files, &picks, PatchDirection::Stage, working_dir)?
+            || staged,
+    )
 }
 
-pub fn execute_stage_mercurial() {
-    log_diagnostic(DiagnosticKind::WorkInProgress {
-        feature: "scud stage (mercurial)",
+fn log_nothing_to_stage() {
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{}",
+            "There are no unstaged changes to pick from. New files can't be \
+             picked with --patch alone, stage them by path or with --interactive"
+                .yellow()
+        ),
     });
 }
 
-pub fn execute_stage_breezy() {
-    log_diagnostic(DiagnosticKind::WorkInProgress {
-        feature: "scud stage (breezy)",
+/// Starts tracking new files and stops tracking deleted ones, which is all
+/// staging means in Mercurial since `hg commit` commits every change to a
+/// tracked file.
+pub fn execute_stage_mercurial(working_dir: &Path) -> ScudResult {
+    // hg addremove works on the whole repository, wherever it's run from
+    ExternalCommand::hg(["addremove"])
+        .current_dir(working_dir)
+        .run()?;
+
+    execute_state_mercurial(working_dir)
+}
+
+/// Starts versioning new files and stops versioning deleted ones, which is
+/// all staging means in Breezy since `brz commit` commits every change to a
+/// versioned file.
+pub fn execute_stage_breezy(working_dir: &Path) -> ScudResult {
+    let repository_root = get_repository_root(&VCS::Breezy, working_dir);
+
+    // brz add only adds the files below the directory it's run from
+    ExternalCommand::brz(["add"])
+        .current_dir(&repository_root)
+        .run()?;
+
+    let deleted = get_changes_breezy(&repository_root)?.unstaged;
+    if !deleted.is_empty() {
+        ExternalCommand::brz(["remove", "--"])
+            .args(deleted.iter().map(|change| &change.path))
+            .current_dir(&repository_root)
+            .run()?;
+    }
+
+    execute_state_breezy(working_dir)
+}
+
+/// Schedules new files for addition and deleted ones for deletion, which is
+/// all staging means in Subversion since `svn commit` commits every change to
+/// a versioned file.
+pub fn execute_stage_subversion(working_dir: &Path) -> ScudResult {
+    let repository_root = get_repository_root(&VCS::Subversion, working_dir);
+
+    // --force descends into directories that are already versioned instead
+    // of failing on them, while still skipping ignored files
+    ExternalCommand::svn(["add", "--force", "."])
+        .current_dir(&repository_root)
+        .run()?;
+
+    let deleted = get_changes_subversion(&repository_root)?.unstaged;
+    let deleted = deleted
+        .iter()
+        .filter(|change| change.kind == ChangeKind::Deleted)
+        .map(|change| escape_path_subversion(&change.path))
+        .collect::<Vec<_>>();
+    if !deleted.is_empty() {
+        ExternalCommand::svn(["delete", "--"])
+            .args(&deleted)
+            .current_dir(&repository_root)
+            .run()?;
+    }
+
+    execute_state_subversion(working_dir)
+}
+
+/// Shows the changes of the working-copy commit, as there's nothing to stage
+/// in Jujutsu: jj snapshots every change into the working-copy commit
+/// whenever it runs.
+pub fn execute_stage_jujutsu(working_dir: &Path) -> ScudResult {
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{}",
+            "Every change is already part of the working-copy commit, since jj \
+             snapshots the working copy whenever it runs"
+                .yellow()
+        ),
     });
+
+    execute_state_jujutsu(working_dir)
+}
+
+/// Starts managing new files and stops managing missing ones, which is all
+/// staging means in Fossil since `fossil commit` commits every change to a
+/// managed file.
+pub fn execute_stage_fossil(working_dir: &Path) -> ScudResult {
+    // fossil addremove leaves out the files matching the ignore-glob setting
+    ExternalCommand::fossil(["addremove"])
+        .current_dir(get_repository_root(&VCS::Fossil, working_dir))
+        .run()?;
+
+    execute_state_fossil(working_dir)
 }
@@ -0,0 +1,393 @@
+use std::path::Path;
+
+use crate::{
+    cli::cli::VCS,
+    commands::{hooks::helpers::get_repository_root, stage::interactive::FilePick},
+    error::ScudResult,
+    runner::ExternalCommand,
+};
+
+/// Which of the changes `scud stage` and `scud unstage` work on.
+///
+/// Properties:
+///
+/// * `paths`: The paths (or git pathspecs) the changes are limited to,
+/// relative to the working directory. None means every change.
+/// * `interactive`: Whether to ask which of the changed files to pick.
+/// * `patch`: Whether to ask which of the hunks (or lines) to pick.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangeSelection {
+    pub paths: Vec<String>,
+    pub interactive: bool,
+    pub patch: bool,
+}
+
+impl ChangeSelection {
+    /// Whether only some of the changes are picked, which only git supports.
+    pub fn is_partial(&self) -> bool {
+        !self.paths.is_empty() || self.interactive || self.patch
+    }
+}
+
+/// A pathspec matching exactly the given path relative to the root of the
+/// repository, wherever git runs from (i.e. `:(top,literal)src/main.rs`).
+pub fn literal_pathspec(path: &str) -> String {
+    format!(":(top,literal){}", path)
+}
+
+//////////////////////////////////////////////////////
+// Parsing of the hunks of `git diff`, and building //
+// patches out of the hunks and lines picked        //
+//////////////////////////////////////////////////////
+
+/// Which way the picked changes go, which decides which side of the diff
+/// stays put when only some of its lines are picked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PatchDirection {
+    /// From the working tree into the index (`git diff` applied to the
+    /// index).
+    Stage,
+    /// Out of the index (`git diff --cached` applied to the index in
+    /// reverse).
+    Unstage,
+}
+
+impl PatchDirection {
+    /// What's done to the picked changes (e.g. stage).
+    pub fn verb(&self) -> &'static str {
+        match self {
+            PatchDirection::Stage => "stage",
+            PatchDirection::Unstage => "unstage",
+        }
+    }
+}
+
+/// The changes of a single file in a diff.
+///
+/// Properties:
+///
+/// * `path`: The path of the file, relative to the root of the repository.
+/// * `header`: The lines preceding the first hunk (e.g. `diff --git ...`).
+/// * `hunks`: The hunks of the file.
+/// * `whole_file`: Whether the file can only be picked as a whole, since
+/// its hunks can't be applied on their own (e.g. new, deleted or binary
+/// files).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDiff {
+    pub path: String,
+    pub header: Vec<String>,
+    pub hunks: Vec<DiffHunk>,
+    pub whole_file: bool,
+}
+
+/// A hunk of a diff (i.e. the lines following `@@ -1,4 +1,5 @@`).
+///
+/// Properties:
+///
+/// * `old_start`: The first line of the hunk before the change.
+/// * `new_start`: The first line of the hunk after the change.
+/// * `section`: What follows the line numbers (e.g. the enclosing function).
+/// * `lines`: The lines of the hunk, each starting with ' ', '+', '-' or
+/// '\' (for "\ No newline at end of file").
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffHunk {
+    pub old_start: usize,
+    pub new_start: usize,
+    pub section: String,
+    pub lines: Vec<String>,
+}
+
+impl DiffHunk {
+    /// Whether the line at the given index adds or removes a line, rather
+    /// than being context.
+    pub fn is_change(&self, index: usize) -> bool {
+        self.lines
+            .get(index)
+            .map_or(false, |line| line.starts_with('+') || line.starts_with('-'))
+    }
+}
+
+/// Gets the changes of the given paths as a diff, which are the unstaged
+/// changes when staging and the staged ones when unstaging.
+///
+/// Arguments:
+///
+/// * `direction`: Whether the changes are about to be staged or unstaged.
+/// * `pathspecs`: The paths (relative to `working_dir`) to limit the diff
+/// to, or none for the whole repository.
+/// * `working_dir`: The directory within the repository to run git in.
+pub fn get_diff_git(
+    direction: PatchDirection,
+    pathspecs: &[String],
+    working_dir: &Path,
+) -> ScudResult<Vec<FileDiff>> {
+    // The output is parsed, so it mustn't depend on the user's configuration
+    // (e.g. diff.noprefix or diff.renames)
+    let mut diff = ExternalCommand::git([
+        "-c",
+        "core.quotePath=false",
+        "diff",
+        "--no-color",
+        "--no-ext-diff",
+        "--no-textconv",
+        "--no-renames",
+        "--src-prefix=a/",
+        "--dst-prefix=b/",
+    ]);
+    if direction == PatchDirection::Unstage {
+        diff = diff.arg("--cached");
+    }
+
+    let diff = diff
+        .arg("--")
+        .args(pathspecs)
+        .current_dir(working_dir)
+        .run()?
+        .stdout;
+
+    Ok(parse_diff(&diff))
+}
+
+/// Splits the output of `git diff` into its files and their hunks.
+pub fn parse_diff(diff: &str) -> Vec<FileDiff> {
+    let mut files: Vec<FileDiff> = vec![];
+
+    for line in diff.lines() {
+        if let Some(paths) = line.strip_prefix("diff --git ") {
+            files.push(FileDiff {
+                path: parse_diff_path(paths),
+                header: vec![line.to_string()],
+                hunks: vec![],
+                whole_file: false,
+            });
+            continue;
+        }
+
+        let file = match files.last_mut() {
+            Some(file) => file,
+            None => continue,
+        };
+
+        if let Some(hunk) = parse_hunk_header(line) {
+            file.hunks.push(hunk);
+        } else if let Some(hunk) = file.hunks.last_mut() {
+            // diff.suppressBlankEmpty leaves out the space of empty context
+            // lines
+            hunk.lines.push(if line.is_empty() {
+                " ".to_string()
+            } else {
+                line.to_string()
+            });
+        } else {
+            if line.starts_with("new file mode")
+                || line.starts_with("deleted file mode")
+                || line.starts_with("Binary files")
+            {
+                file.whole_file = true;
+            }
+            file.header.push(line.to_string());
+        }
+    }
+
+    // Files without hunks only change their mode (or are binary)
+    for file in &mut files {
+        if file.hunks.is_empty() {
+            file.whole_file = true;
+        }
+    }
+
+    files
+}
+
+/// The path of the file from the paths of `diff --git a/<path> b/<path>`,
+/// which are the same since renames aren't detected.
+fn parse_diff_path(paths: &str) -> String {
+    let path = if paths.starts_with('"') {
+        // Paths with special characters are quoted (e.g. "a/tab\there")
+        paths
+            .split("\" \"")
+            .next()
+            .unwrap_or(paths)
+            .trim_start_matches('"')
+            .replace("\\\"", "\"")
+            .replace("\\\\", "\\")
+    } else {
+        paths[..(paths.len().saturating_sub(1) / 2)].to_string()
+    };
+
+    path.strip_prefix("a/").unwrap_or(&path).to_string()
+}
+
+/// Parses a hunk header (e.g. "@@ -12,7 +12,8 @@ fn main() {").
+fn parse_hunk_header(line: &str) -> Option<DiffHunk> {
+    let ranges = line.strip_prefix("@@ -")?;
+    let (ranges, section) = ranges.split_once(" @@")?;
+    let (old_range, new_range) = ranges.split_once(" +")?;
+
+    let start = |range: &str| range.split(',').next()?.parse::<usize>().ok();
+
+    Some(DiffHunk {
+        old_start: start(old_range)?,
+        new_start: start(new_range)?,
+        section: section.to_string(),
+        lines: vec![],
+    })
+}
+
+/// Builds a patch of the picked lines of a file, which applies to the index
+/// (in reverse when unstaging).
+///
+/// Lines which aren't picked are left as they are in the index: when staging,
+/// a removed line which isn't picked stays as context and an added one is
+/// left out, and the other way around when unstaging.
+///
+/// Arguments:
+///
+/// * `file`: The changes of the file.
+/// * `picked`: Which lines of each hunk are picked, by the index of the line.
+/// * `direction`: Whether the picked lines are being staged or unstaged.
+///
+/// Returns:
+///
+/// The patch, or None when no line was picked.
+pub fn build_patch(
+    file: &FileDiff,
+    picked: &[Vec<bool>],
+    direction: PatchDirection,
+) -> Option<String> {
+    // Lines which stay as context, and lines which are left out
+    let (kept, left_out) = match direction {
+        PatchDirection::Stage => ('-', '+'),
+        PatchDirection::Unstage => ('+', '-'),
+    };
+
+    let mut patch = file.header.join("\n");
+    patch.push('\n');
+
+    // How far the lines of the side being changed moved because of the
+    // hunks picked so far
+    let mut offset: isize = 0;
+    let mut any_picked = false;
+
+    for (hunk, picked) in file.hunks.iter().zip(picked) {
+        if !(0..hunk.lines.len()).any(|index| hunk.is_change(index) && picked[index])
+        {
+            continue;
+        }
+        any_picked = true;
+
+        let mut lines = vec![];
+        let mut previous_kept = true;
+
+        for (index, line) in hunk.lines.iter().enumerate() {
+            let kind = line.chars().next().unwrap_or(' ');
+
+            if kind == '\\' {
+                // "\ No newline at end of file" belongs to the line before it
+                if previous_kept {
+                    lines.push(line.clone());
+                }
+                continue;
+            }
+
+            previous_kept = true;
+            if !hunk.is_change(index) || picked[index] {
+                lines.push(line.clone());
+            } else if kind == kept {
+                lines.push(format!(" {}", &line[1..]));
+            } else if kind == left_out {
+                previous_kept = false;
+            }
+        }
+
+        let count = |kinds: [char; 2]| {
+            lines
+                .iter()
+                .filter(|line| {
+                    line.starts_with(kinds[0]) || line.starts_with(kinds[1])
+                })
+                .count()
+        };
+        let old_count = count([' ', '-']);
+        let new_count = count([' ', '+']);
+
+        // The index is the old side when staging and the new side when
+        // unstaging, whose line numbers stay as they are
+        let (old_start, new_start) = match direction {
+            PatchDirection::Stage => {
+                (hunk.old_start, hunk.old_start.saturating_add_signed(offset))
+            }
+            PatchDirection::Unstage => (
+                hunk.new_start.saturating_add_signed(-offset),
+                hunk.new_start,
+            ),
+        };
+        offset += new_count as isize - old_count as isize;
+
+        patch.push_str(&format!(
+            "@@ -{},{} +{},{} @@{}\n",
+            old_start, old_count, new_start, new_count, hunk.section
+        ));
+        for line in lines {
+            patch.push_str(&line);
+            patch.push('\n');
+        }
+    }
+
+    any_picked.then_some(patch)
+}
+
+/// Stages or unstages what was picked of each file: whole files by path, and
+/// the picked hunks and lines by applying a patch of them to the index.
+///
+/// Returns:
+///
+/// Whether anything was picked, and thus the index changed.
+pub fn apply_picks_git(
+    files: &[FileDiff],
+    picks: &[Option<FilePick>],
+    direction: PatchDirection,
+    working_dir: &Path,
+) -> ScudResult<bool> {
+    let mut whole_files = vec![];
+    let mut patch = String::new();
+
+    for (file, pick) in files.iter().zip(picks) {
+        match pick {
+            Some(FilePick::Whole) => whole_files.push(literal_pathspec(&file.path)),
+            Some(FilePick::Lines(lines)) => {
+                if let Some(file_patch) = build_patch(file, lines, direction) {
+                    patch.push_str(&file_patch);
+                }
+            }
+            None => {}
+        }
+    }
+
+    if !whole_files.is_empty() {
+        let args: &[&str] = match direction {
+            PatchDirection::Stage => &["add", "-A", "--"],
+            PatchDirection::Unstage => &["reset", "--quiet", "--"],
+        };
+        ExternalCommand::git(args)
+            .args(&whole_files)
+            .current_dir(working_dir)
+            .run()?;
+    }
+
+    let any_patch = !patch.is_empty();
+    if any_patch {
+        let mut apply = ExternalCommand::git(["apply", "--cached"]);
+        if direction == PatchDirection::Unstage {
+            apply = apply.arg("--reverse");
+        }
+
+        // The paths of the patch are relative to the root of the repository
+        apply
+            .stdin(patch)
+            .current_dir(get_repository_root(&VCS::Git, working_dir))
+            .run()?;
+    }
+
+    Ok(!whole_files.is_empty() || any_patch)
+}
@@ -0,0 +1,247 @@
+use colored::Colorize;
+use dialoguer::{theme::ColorfulTheme, MultiSelect, Select};
+
+use crate::{
+    commands::stage::helpers::{DiffHunk, FileDiff, PatchDirection},
+    error::ScudResult,
+    logging::theme::Themed,
+};
+
+/// What was picked of the changes of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FilePick {
+    /// The file as a whole, for files which can only be picked as such (e.g.
+    /// new or deleted files).
+    Whole,
+    /// The picked lines of each hunk, by the index of the line.
+    Lines(Vec<Vec<bool>>),
+}
+
+/// Asks which of the changed files to stage or unstage, showing the kind of
+/// each change the same way `scud state` does.
+///
+/// Arguments:
+///
+/// * `files`: The path of each file along with how it's listed (e.g. its
+/// change badge followed by its path).
+/// * `direction`: Whether the files are about to be staged or unstaged.
+///
+/// Returns:
+///
+/// The paths of the picked files.
+pub fn pick_files(
+    files: &[(String, String)],
+    direction: PatchDirection,
+) -> ScudResult<Vec<String>> {
+    let items = files.iter().map(|(_, item)| item).collect::<Vec<_>>();
+
+    let picked = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "{}{}{}",
+            "Which files do you want to ".bright_yellow().italic(),
+            direction.verb().yellow().italic(),
+            "? (space to pick, enter to confirm)"
+                .bright_yellow()
+                .italic()
+        ))
+        .items(&items)
+        .interact()?;
+
+    Ok(picked
+        .into_iter()
+        .map(|index| files[index].0.clone())
+        .collect())
+}
+
+/// Steps through the hunks of every file, asking whether to stage or unstage
+/// each of them, or only some of their lines.
+///
+/// Returns:
+///
+/// What was picked of each file, in the same order as the files (None for
+/// the files nothing was picked of).
+pub fn pick_hunks(
+    files: &[FileDiff],
+    direction: PatchDirection,
+) -> ScudResult<Vec<Option<FilePick>>> {
+    let verb = direction.verb();
+    let total = files
+        .iter()
+        .map(|file| if file.whole_file { 1 } else { file.hunks.len() })
+        .sum::<usize>();
+    let mut position = 0;
+    let mut picks = vec![];
+
+    for file in files {
+        if file.whole_file {
+            position += 1;
+            display_file_header(file, position, total);
+            for hunk in &file.hunks {
+                display_hunk(hunk);
+            }
+
+            let options = [
+                format!("{} this file", capitalize(verb)),
+                "Skip this file".to_string(),
+                "Done  (leave the rest as it is)".to_string(),
+            ];
+            match ask_action(&options, "this file")? {
+                0 => picks.push(Some(FilePick::Whole)),
+                1 => picks.push(None),
+                _ => break,
+            }
+            continue;
+        }
+
+        let mut lines = file
+            .hunks
+            .iter()
+            .map(|hunk| vec![false; hunk.lines.len()])
+            .collect::<Vec<_>>();
+        let mut done = false;
+
+        for (index, hunk) in file.hunks.iter().enumerate() {
+            position += 1;
+            display_file_header(file, position, total);
+            display_hunk(hunk);
+
+            let options = [
+                format!("{} this hunk", capitalize(verb)),
+                "Skip this hunk".to_string(),
+                format!("Pick the lines to {}", verb),
+                format!(
+                    "{} this and the remaining hunks of the file",
+                    capitalize(verb)
+                ),
+                "Skip the remaining hunks of the file".to_string(),
+                "Done  (leave the rest as it is)".to_string(),
+            ];
+            match ask_action(&options, "this hunk")? {
+                0 => lines[index].fill(true),
+                1 => {}
+                2 => lines[index] = pick_lines(hunk, direction)?,
+                3 => {
+                    lines[index..].iter_mut().for_each(|lines| lines.fill(true));
+                    position += file.hunks.len() - index - 1;
+                    break;
+                }
+                4 => {
+                    position += file.hunks.len() - index - 1;
+                    break;
+                }
+                _ => {
+                    done = true;
+                    break;
+                }
+            }
+        }
+
+        let any_picked = lines.iter().flatten().any(|picked| *picked);
+        picks.push(any_picked.then_some(FilePick::Lines(lines)));
+
+        if done {
+            break;
+        }
+    }
+
+    // The files after the user was done are left as they are
+    picks.resize(files.len(), None);
+
+    Ok(picks)
+}
+
+/// Asks which of the added and removed lines of the hunk to stage or
+/// unstage.
+///
+/// Returns:
+///
+/// Whether each line of the hunk was picked, by the index of the line.
+fn pick_lines(hunk: &DiffHunk, direction: PatchDirection) -> ScudResult<Vec<bool>> {
+    let changes = (0..hunk.lines.len())
+        .filter(|index| hunk.is_change(*index))
+        .collect::<Vec<usize>>();
+    let items = changes
+        .iter()
+        .map(|index| format_line(&hunk.lines[*index]))
+        .collect::<Vec<String>>();
+
+    let picked = MultiSelect::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "{}{}{}",
+            "Which lines do you want to ".bright_yellow().italic(),
+            direction.verb().yellow().italic(),
+            "? (space to pick, enter to confirm)"
+                .bright_yellow()
+                .italic()
+        ))
+        .items(&items)
+        .interact()?;
+
+    let mut lines = vec![false; hunk.lines.len()];
+    for index in picked {
+        lines[changes[index]] = true;
+    }
+
+    Ok(lines)
+}
+
+fn ask_action(options: &[String], subject: &str) -> ScudResult<usize> {
+    Ok(Select::with_theme(&ColorfulTheme::default())
+        .with_prompt(format!(
+            "{}{}{}",
+            "What do you want to do with ".bright_yellow().italic(),
+            subject.yellow().italic(),
+            "?".bright_yellow().italic()
+        ))
+        .default(0)
+        .items(options)
+        .interact()?)
+}
+
+fn display_file_header(file: &FileDiff, position: usize, total: usize) {
+    println!(
+        "\n{} {}\n",
+        format!(" {} ", file.path).black().on_cyan(),
+        format!("({}/{})", position, total).muted().italic()
+    );
+
+    if file.hunks.is_empty() {
+        // Binary files and mode changes have no lines to show
+        for line in file.header.iter().skip(1) {
+            println!("  {}", line.muted().italic());
+        }
+        println!();
+    }
+}
+
+fn display_hunk(hunk: &DiffHunk) {
+    println!(
+        "  {}",
+        format!(
+            "@@ -{} +{} @@{}",
+            hunk.old_start, hunk.new_start, hunk.section
+        )
+        .bright_cyan()
+    );
+    for line in &hunk.lines {
+        println!("  {}", format_line(line));
+    }
+    println!();
+}
+
+/// Colors a line of a hunk after whether it's added, removed or context.
+fn format_line(line: &str) -> String {
+    match line.chars().next() {
+        Some('+') => line.bright_green().to_string(),
+        Some('-') => line.bright_red().to_string(),
+        _ => line.muted().to_string(),
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
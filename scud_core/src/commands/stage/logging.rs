@@ -1,23 +1,19 @@
 use::colored::Colorize;
-use std::process::Command;
+use crate::{error::ScudResult, runner::ExternalCommand};
 
-pub fn log_staged_status(dry_run: bool) {
+pub fn log_staged_status(dry_run: bool) -> ScudResult {
   println!(
     "{}\n",
     "Repository status:".yellow()
   );
 
-  let status_raw = Command::new("git")
-    .arg("status")
-    .arg("--short")
-    .output()
-    .expect("Failed to get git status").stdout;
-
-  let mut status = String::from_utf8_lossy(&status_raw).to_string();
+  let mut status = ExternalCommand::git(["status", "--short"]).run()?.stdout;
 
   if dry_run {
     status = status.replace("??", "A");
   }
 
   print!("{}", status.green());
+
+  Ok(())
 }
\ No newline at end of file
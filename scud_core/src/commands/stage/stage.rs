@@ -1,43 +1,114 @@
+use std::{
+    io::{self, IsTerminal},
+    path::Path,
+};
+
 use colored::Colorize;
 
 use crate::{
     cli::cli::{Stage, VCS},
-    commands::stage::executors::{
-        execute_stage_breezy, execute_stage_dry_run, execute_stage_git,
-        execute_stage_info, execute_stage_mercurial,
+    commands::stage::{
+        executors::{
+            execute_stage_breezy, execute_stage_dry_run, execute_stage_fossil,
+            execute_stage_git, execute_stage_info, execute_stage_jujutsu,
+            execute_stage_mercurial, execute_stage_subversion,
+        },
+        helpers::ChangeSelection,
     },
     diagnostics::{log_diagnostic, DiagnosticKind},
-    helpers::detect_vcs,
-    logging::helpers::bright_yellow_backtick,
+    error::{ScudError, ScudResult},
+    logging::helpers::backtick,
+    repository::Repository,
 };
 
-pub fn stage_command(stage_options: Stage) {
+pub fn stage_command(stage_options: Stage, working_dir: &Path) -> ScudResult {
     if stage_options.dry_run {
         execute_stage_dry_run();
     } else if stage_options.info {
         execute_stage_info();
     } else {
-        execute_stage();
+        let selection = ChangeSelection {
+            paths: stage_options.paths,
+            interactive: stage_options.interactive,
+            patch: stage_options.patch,
+        };
+        execute_stage(&selection, working_dir)?;
     }
+
+    Ok(())
 }
 
-pub fn execute_stage() {
-    let vcs = detect_vcs();
+pub fn execute_stage(selection: &ChangeSelection, working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
+
+    check_selection(selection, vcs, "stage")?;
 
     match vcs {
-        VCS::Git => execute_stage_git(),
-        VCS::Mercurial => execute_stage_mercurial(),
-        VCS::Breezy => execute_stage_breezy(),
+        VCS::Git => execute_stage_git(selection, working_dir)?,
+        VCS::Mercurial => execute_stage_mercurial(working_dir)?,
+        VCS::Breezy => execute_stage_breezy(working_dir)?,
+        VCS::Subversion => execute_stage_subversion(working_dir)?,
+        VCS::Jujutsu => execute_stage_jujutsu(working_dir)?,
+        VCS::Fossil => execute_stage_fossil(working_dir)?,
     }
 
     log_diagnostic(DiagnosticKind::Tip {
         body: &format!(
             "{} {}{}{} {}",
             "Use".yellow(),
-            bright_yellow_backtick(),
+            backtick(),
             "scud commit".green().italic(),
-            bright_yellow_backtick(),
+            backtick(),
             "to commit any staged changes".yellow()
         ),
     });
+
+    Ok(())
+}
+
+/// Checks that only some of the changes can be picked, which needs a git
+/// repository (the other VCSs have no staging area to pick changes into), and
+/// a terminal to ask in for --interactive and --patch.
+///
+/// Arguments:
+///
+/// * `selection`: Which of the changes to work on.
+/// * `vcs`: The VCS of the repository.
+/// * `command`: The command the selection was given to (e.g. stage).
+pub fn check_selection(
+    selection: &ChangeSelection,
+    vcs: VCS,
+    command: &str,
+) -> ScudResult {
+    if selection.is_partial() && vcs != VCS::Git {
+        return Err(ScudError::failed(
+            format!("Cannot {} only some of the changes", command),
+            format!(
+                "Paths, --interactive and --patch are only supported in git \
+                 repositories. The other version control systems commit every \
+                 change to a tracked file, so scud {} works on the whole \
+                 repository.",
+                command
+            ),
+        ));
+    }
+
+    // The pickers draw on stderr and read the keys from the terminal
+    let attended = io::stdin().is_terminal() && io::stderr().is_terminal();
+    if (selection.interactive || selection.patch) && !attended {
+        return Err(ScudError::failed(
+            format!("Cannot pick the changes to {} without a terminal", command),
+            "--interactive and --patch ask which of the changes to pick, which \
+             needs a terminal to ask in.",
+        )
+        .with_hint(
+            format!(
+                "{}",
+                "Pick the changes by their paths instead, e.g. with".yellow()
+            ),
+            format!("scud {} src/main.rs", command),
+        ));
+    }
+
+    Ok(())
 }
@@ -1,13 +1,21 @@
-use std::process::Command;
+use std::path::Path;
 
 use colored::Colorize;
 
 use crate::{
+    cli::cli::VCS,
+    commands::hooks::helpers::get_repository_root,
     diagnostics::{log_diagnostic, DiagnosticKind},
+    error::ScudResult,
+    runner::ExternalCommand,
     state::helpers::{
         display_all_staged_changes, display_all_unstaged_changes,
-        display_all_untracked_changes, display_commit_state, display_current_branch,
-        display_state_header, get_commit_diff,
+        display_all_untracked_changes, display_changes, display_commit_state,
+        display_current_branch, display_state_header, get_changes_breezy,
+        get_changes_fossil, get_changes_jujutsu, get_changes_mercurial,
+        get_changes_subversion, get_commit_diff, get_current_branch_breezy,
+        get_current_branch_fossil, get_current_branch_jujutsu,
+        get_current_branch_mercurial, get_current_branch_subversion,
     },
 };
 
@@ -22,50 +30,146 @@ pub fn execute_state_info() {
         command_name: "state",
         git_command: &format!(
             "{} {}",
-            "git status",
-            "(along with other commands for more rich output)".bright_yellow()
+            "git status -sb",
+            "(along with git branch --show-current and git diff --name-only for \
+             each kind of change)"
+                .bright_yellow()
         ),
         mercurial_command: &format!(
             "{} {}",
             "hg status",
-            "(along with other commands for more rich output)".bright_yellow()
+            "(along with hg branch and hg log -r . -T {activebookmark} for the \
+             current branch and bookmark)"
+                .bright_yellow()
         ),
         breezy_command: &format!(
             "{} {}",
-            "bzr status",
-            "(along with other commands for more rich output)".bright_yellow()
+            "brz status --short",
+            "(along with brz nick for the name of the branch)".bright_yellow()
         ),
+        other_commands: &[
+            (
+                "Subversion",
+                &format!(
+                    "{} {}",
+                    "svn status",
+                    "(along with svn info --show-item relative-url for the branch)"
+                        .bright_yellow()
+                ),
+            ),
+            (
+                "Jujutsu",
+                &format!(
+                    "{} {}",
+                    "jj diff --summary",
+                    "(the working-copy changes of jj status, along with jj log for \
+                     the closest bookmark)"
+                        .bright_yellow()
+                ),
+            ),
+            (
+                "Fossil",
+                &format!(
+                    "{} {}",
+                    "fossil changes",
+                    "(along with fossil extras for the untracked files and fossil \
+                     branch list for the current branch)"
+                        .bright_yellow()
+                ),
+            ),
+        ],
     });
 }
 
-pub fn execute_state_git() {
-    display_state_header();
+pub fn execute_state_git(working_dir: &Path) -> ScudResult {
+    // Reading the repository in-process spares spawning git for each kind of
+    // change, falling back to the git CLI when gitoxide can't read it
+    #[cfg(feature = "gix")]
+    if let Some(state) = crate::native::get_state_git(working_dir) {
+        display_changes("Git", &state.branch, &state.changes);
+        return display_commit_state(state.ahead_behind);
+    }
+
+    display_state_header("Git");
 
-    display_current_branch();
+    display_current_branch(working_dir);
 
-    display_all_staged_changes();
+    display_all_staged_changes(working_dir);
 
-    display_all_unstaged_changes();
+    display_all_unstaged_changes(working_dir);
 
-    display_all_untracked_changes();
+    display_all_untracked_changes(working_dir);
 
     // get status diff (e.g. git status -sb)
     // parse number of commits ahead and behind
     // if behind, print warning
 
-    match Command::new("git").args(["status", "-sb"]).output() {
-        Ok(output) => {
-            let status_diff = String::from_utf8_lossy(&output.stdout).to_string();
-            let ahead_behind = get_commit_diff(status_diff);
-            display_commit_state(ahead_behind);
-        }
-        Err(error) => log_diagnostic(DiagnosticKind::Error {
-            subject: "getting status diff (git)",
-            body: &format!("{}", error),
-        }),
-    }
+    let status_diff = ExternalCommand::git(["status", "-sb"])
+        .current_dir(working_dir)
+        .run()?
+        .stdout;
+    let ahead_behind = get_commit_diff(status_diff);
+    display_commit_state(ahead_behind)
+}
+
+/// Displays the changes of the current Mercurial repository, treating every
+/// change `hg commit` would commit as staged.
+pub fn execute_state_mercurial(working_dir: &Path) -> ScudResult {
+    let changes =
+        get_changes_mercurial(&get_repository_root(&VCS::Mercurial, working_dir))?;
+    display_changes(
+        "Mercurial",
+        &get_current_branch_mercurial(working_dir),
+        &changes,
+    );
+
+    Ok(())
+}
+
+/// Displays the changes of the current Breezy working tree, treating every
+/// change `brz commit` would commit as staged.
+pub fn execute_state_breezy(working_dir: &Path) -> ScudResult {
+    let changes =
+        get_changes_breezy(&get_repository_root(&VCS::Breezy, working_dir))?;
+    display_changes("Breezy", &get_current_branch_breezy(working_dir), &changes);
+
+    Ok(())
+}
+
+/// Displays the changes of the current Subversion working copy, treating
+/// every change `svn commit` would commit as staged.
+pub fn execute_state_subversion(working_dir: &Path) -> ScudResult {
+    let changes =
+        get_changes_subversion(&get_repository_root(&VCS::Subversion, working_dir))?;
+    display_changes(
+        "Subversion",
+        &get_current_branch_subversion(working_dir),
+        &changes,
+    );
+
+    Ok(())
 }
 
-pub fn execute_state_mercurial() {}
+/// Displays the changes of the working-copy commit of the current Jujutsu
+/// repository, which are all staged since jj snapshots every change into it.
+pub fn execute_state_jujutsu(working_dir: &Path) -> ScudResult {
+    let changes =
+        get_changes_jujutsu(&get_repository_root(&VCS::Jujutsu, working_dir))?;
+    display_changes(
+        "Jujutsu",
+        &get_current_branch_jujutsu(working_dir),
+        &changes,
+    );
 
-pub fn execute_state_breezy() {}
+    Ok(())
+}
+
+/// Displays the changes of the current Fossil checkout, treating every
+/// change `fossil commit` would commit as staged.
+pub fn execute_state_fossil(working_dir: &Path) -> ScudResult {
+    let changes =
+        get_changes_fossil(&get_repository_root(&VCS::Fossil, working_dir))?;
+    display_changes("Fossil", &get_current_branch_fossil(working_dir), &changes);
+
+    Ok(())
+}
@@ -1,49 +1,55 @@
-use std::process::Command;
+use std::path::Path;
 
-use colored::Colorize;
+use colored::{ColoredString, Colorize};
 
-use crate::diagnostics::{log_diagnostic, DiagnosticKind};
+use crate::{
+    error::{ScudError, ScudResult},
+    logging::theme::Themed,
+    runner::ExternalCommand,
+};
 
 pub fn get_commit_diff(status_diff: String) -> String {
-    let mut status = status_diff.split("\n");
-
     // (i.e. "## master...origin/master [ahead 1]")
-    let branch_metadata = status.next().unwrap();
-
-    // split on the space if the number of tokens is 2
-    //
-
-    let branch_tokens = branch_metadata.split(" ").collect::<Vec<&str>>();
+    let branch_metadata = status_diff.lines().next().unwrap_or_default();
 
-    if branch_tokens.len() > 2 {
-        let branch_metadata_tokens =
-            branch_metadata.split('[').collect::<Vec<&str>>();
-
-        let ahead_behind_raw = branch_metadata_tokens.last().unwrap();
-        return ahead_behind_raw.split(']').next().unwrap().to_string();
-    } else {
-        return String::new();
+    // Only branches diverging from their upstream carry a bracketed summary
+    match branch_metadata.split_once('[') {
+        Some((_, ahead_behind_raw)) => ahead_behind_raw
+            .split(']')
+            .next()
+            .unwrap_or_default()
+            .to_string(),
+        None => String::new(),
     }
 }
 
-pub fn display_commit_state(ahead_behind: String) {
+/// Parses a commit count of the ahead/behind summary (e.g. "ahead 3").
+fn parse_commit_count(ahead_behind: &str, token: &str) -> ScudResult<i32> {
+    token
+        .split_whitespace()
+        .nth(1)
+        .and_then(|count| count.parse::<i32>().ok())
+        .ok_or_else(|| {
+            ScudError::parse("ahead/behind summary of git status", ahead_behind)
+        })
+}
+
+pub fn display_commit_state(ahead_behind: String) -> ScudResult {
     // parse the ahead and behind numbers
     // if behind > 0, print warning
     // if ahead > 0, print info
 
     if ahead_behind.is_empty() {
-        return;
+        return Ok(());
     }
 
-    if ahead_behind.contains(",") {
-        // parse the ahead and behind numbers
-        let ahead_behind = ahead_behind
-            .split(",")
-            .map(|s| s.trim())
-            .collect::<Vec<&str>>();
+    if ahead_behind.contains(',') {
+        // parse the ahead and behind numbers (i.e. "ahead 1, behind 2")
+        let (ahead_raw, behind_raw) =
+            ahead_behind.split_once(',').unwrap_or_default();
 
-        let ahead = ahead_behind[0].parse::<i32>().unwrap();
-        let behind = ahead_behind[1].parse::<i32>().unwrap();
+        let ahead = parse_commit_count(&ahead_behind, ahead_raw)?;
+        let behind = parse_commit_count(&ahead_behind, behind_raw)?;
 
         println!(
             "{}{}{}{}{}{}{}{}\n",
@@ -57,19 +63,12 @@ pub fn display_commit_state(ahead_behind: String) {
             format!("{} commits", ahead).bright_green().italic()
         );
     } else {
-        let ahead_or_behind = ahead_behind
-            .split(" ")
-            .map(|s| s.trim())
-            .collect::<Vec<&str>>()[0];
+        let ahead_or_behind =
+            ahead_behind.split_whitespace().next().unwrap_or_default();
 
         match ahead_or_behind {
             "ahead" => {
-                let ahead = ahead_behind
-                    .split(" ")
-                    .map(|s| s.trim())
-                    .collect::<Vec<&str>>()[1]
-                    .parse::<i32>()
-                    .unwrap();
+                let ahead = parse_commit_count(&ahead_behind, &ahead_behind)?;
 
                 println!(
                     "{}{}{}{}{}\n",
@@ -81,12 +80,7 @@ pub fn display_commit_state(ahead_behind: String) {
                 );
             }
             "behind" => {
-                let behind = ahead_behind
-                    .split(" ")
-                    .map(|s| s.trim())
-                    .collect::<Vec<&str>>()[1]
-                    .parse::<i32>()
-                    .unwrap();
+                let behind = parse_commit_count(&ahead_behind, &ahead_behind)?;
 
                 println!(
                     "{}{}{}{}{}\n",
@@ -97,45 +91,50 @@ pub fn display_commit_state(ahead_behind: String) {
                     format!("{} commit(s)", behind).bright_red().italic(),
                 );
             }
-            _ => unreachable!(),
+            // e.g. "gone", when the upstream branch was deleted
+            _ => {}
         }
     }
+
+    Ok(())
 }
 
-pub fn display_all_staged_changes() {
+pub fn display_all_staged_changes(working_dir: &Path) {
     println!("{}\n", " STAGED CHANGES: ".black().on_cyan());
     let filters = vec!["M", "D", "R"];
 
     let mut any_changes = false;
 
     // check for any staged changes
-    match Command::new("git")
-        .args(["diff", "--cached", "--name-only"])
-        .output()
+    match ExternalCommand::git(["diff", "--cached", "--name-only"])
+        .current_dir(working_dir)
+        .run()
     {
         Ok(output) => {
-            let staged_changes = String::from_utf8_lossy(&output.stdout).to_string();
+            let staged_changes = output.stdout;
             if !staged_changes.is_empty() {
                 any_changes = true;
             }
         }
-        Err(error) => log_diagnostic(DiagnosticKind::Error {
-            subject: "getting staged changes (git)",
-            body: &format!("{}", error),
-        }),
+        Err(error) => error.log(),
     }
 
     if any_changes {
         // display different types of staged changes
         // (modified, deleted, renamed) to the user
         for filter in filters {
-            match Command::new("git")
-                .args(["diff", "--name-only", "--staged", "--diff-filter", filter])
-                .output()
+            match ExternalCommand::git([
+                "diff",
+                "--name-only",
+                "--staged",
+                "--diff-filter",
+                filter,
+            ])
+            .current_dir(working_dir)
+            .run()
             {
                 Ok(output) => {
-                    let staged_changes =
-                        String::from_utf8_lossy(&output.stdout).to_string();
+                    let staged_changes = output.stdout;
 
                     match filter {
                         "M" => {
@@ -145,14 +144,14 @@ pub fn display_all_staged_changes() {
                                     "No ".magenta().italic(),
                                     "modified files ".bright_yellow().italic(),
                                     "staged for commit ".magenta().italic(),
-                                    "...".black().italic()
+                                    "...".muted().italic()
                                 );
                             } else {
                                 println!(
                                     "  {}{}{}\n",
                                     "Modified files ".bright_yellow().italic(),
                                     "staged for commit ".magenta().italic(),
-                                    "...".black().italic(),
+                                    "...".muted().italic(),
                                 );
                                 for staged_change in staged_changes.lines() {
                                     println!(
@@ -174,14 +173,14 @@ pub fn display_all_staged_changes() {
                                     "No ".magenta().italic(),
                                     "deleted files ".red().italic(),
                                     "staged for commit ".magenta().italic(),
-                                    "...".black().italic()
+                                    "...".muted().italic()
                                 );
                             } else {
                                 println!(
                                     "  {}{}{}\n",
                                     "Deleted files ".red().italic(),
                                     "staged for commit ".magenta().italic(),
-                                    "...".black().italic(),
+                                    "...".muted().italic(),
                                 );
                                 for staged_change in staged_changes.lines() {
                                     println!(
@@ -202,14 +201,14 @@ pub fn display_all_staged_changes() {
                                     "No ".magenta().italic(),
                                     "renamed files ".bright_blue().italic(),
                                     "staged for commit ".magenta().italic(),
-                                    "...".black().italic()
+                                    "...".muted().italic()
                                 );
                             } else {
                                 println!(
                                     "  {}{}{}\n",
                                     "Renamed files ".bright_blue().italic(),
                                     "staged for commit ".magenta().italic(),
-                                    "...".black().italic(),
+                                    "...".muted().italic(),
                                 );
                                 for staged_change in staged_changes.lines() {
                                     println!(
@@ -226,10 +225,7 @@ pub fn display_all_staged_changes() {
                         _ => unreachable!(),
                     }
                 }
-                Err(error) => log_diagnostic(DiagnosticKind::Error {
-                    subject: "getting staged changes (git)",
-                    body: &format!("{}", error),
-                }),
+                Err(error) => error.log(),
             }
         }
     } else {
@@ -238,43 +234,46 @@ pub fn display_all_staged_changes() {
             "No ".magenta().italic(),
             "staged changes ".cyan().italic(),
             "to commit ".magenta().italic(),
-            "...".black().italic()
+            "...".muted().italic()
         );
     }
 }
 
-pub fn display_all_unstaged_changes() {
+pub fn display_all_unstaged_changes(working_dir: &Path) {
     println!("{}\n", " UNSTAGED CHANGES: ".black().on_cyan());
     let filters = vec!["M", "D", "R"];
 
     let mut any_changes = false;
 
     // check for any unstaged changes
-    match Command::new("git").args(["diff", "--name-only"]).output() {
+    match ExternalCommand::git(["diff", "--name-only"])
+        .current_dir(working_dir)
+        .run()
+    {
         Ok(output) => {
-            let unstaged_changes =
-                String::from_utf8_lossy(&output.stdout).to_string();
+            let unstaged_changes = output.stdout;
             if !unstaged_changes.is_empty() {
                 any_changes = true;
             }
         }
-        Err(error) => log_diagnostic(DiagnosticKind::Error {
-            subject: "getting staged changes (git)",
-            body: &format!("{}", error),
-        }),
+        Err(error) => error.log(),
     }
 
     if any_changes {
         // display different types of staged changes
         // (modified, deleted, renamed) to the user
         for filter in filters {
-            match Command::new("git")
-                .args(["diff", "--name-only", "--diff-filter", filter])
-                .output()
+            match ExternalCommand::git([
+                "diff",
+                "--name-only",
+                "--diff-filter",
+                filter,
+            ])
+            .current_dir(working_dir)
+            .run()
             {
                 Ok(output) => {
-                    let staged_changes =
-                        String::from_utf8_lossy(&output.stdout).to_string();
+                    let staged_changes = output.stdout;
 
                     match filter {
                         "M" => {
@@ -284,14 +283,14 @@ pub fn display_all_unstaged_changes() {
                                     "No ".magenta().italic(),
                                     "modified files ".bright_yellow().italic(),
                                     "not yet staged for commit ".magenta().italic(),
-                                    "...".black().italic()
+                                    "...".muted().italic()
                                 );
                             } else {
                                 println!(
                                     "  {}{}{}\n",
                                     "Modified files ".bright_yellow().italic(),
                                     "not yet staged for commit ".magenta().italic(),
-                                    "...".black().italic(),
+                                    "...".muted().italic(),
                                 );
                                 for staged_change in staged_changes.lines() {
                                     println!(
@@ -313,14 +312,14 @@ pub fn display_all_unstaged_changes() {
                                     "No ".magenta().italic(),
                                     "deleted files ".red().italic(),
                                     "not yet staged for commit ".magenta().italic(),
-                                    "...".black().italic()
+                                    "...".muted().italic()
                                 );
                             } else {
                                 println!(
                                     "  {}{}{}\n",
                                     "Deleted files ".red().italic(),
                                     "not yet staged for commit ".magenta().italic(),
-                                    "...".black().italic(),
+                                    "...".muted().italic(),
                                 );
                                 for staged_change in staged_changes.lines() {
                                     println!(
@@ -341,14 +340,14 @@ pub fn display_all_unstaged_changes() {
                                     "No ".magenta().italic(),
                                     "renamed files ".bright_blue().italic(),
                                     "not yet staged for commit ".magenta().italic(),
-                                    "...".black().italic()
+                                    "...".muted().italic()
                                 );
                             } else {
                                 println!(
                                     "  {}{}{}\n",
                                     "Renamed files ".bright_blue().italic(),
                                     "not yet staged for commit ".magenta().italic(),
-                                    "...".black().italic(),
+                                    "...".muted().italic(),
                                 );
                                 for staged_change in staged_changes.lines() {
                                     println!(
@@ -365,10 +364,7 @@ pub fn display_all_unstaged_changes() {
                         _ => unreachable!(),
                     }
                 }
-                Err(error) => log_diagnostic(DiagnosticKind::Error {
-                    subject: "getting staged changes (git)",
-                    body: &format!("{}", error),
-                }),
+                Err(error) => error.log(),
             }
         }
     } else {
@@ -377,36 +373,39 @@ pub fn display_all_unstaged_changes() {
             "No ".magenta().italic(),
             "unstaged changes ".cyan().italic(),
             "to commit ".magenta().italic(),
-            "...".black().italic()
+            "...".muted().italic()
         );
     }
 }
 
-pub fn display_state_header() {
+/// Prints the heading of `scud state` naming the underlying VCS (e.g. Git).
+pub fn display_state_header(vcs_name: &str) {
     println!(
         "\n{} {}",
-        " Git ".black().italic().on_bright_yellow(),
+        format!(" {} ", vcs_name)
+            .black()
+            .italic()
+            .on_bright_yellow(),
         "repository status".yellow().italic(),
     );
 }
 
-pub fn display_all_untracked_changes() {
+pub fn display_all_untracked_changes(working_dir: &Path) {
     println!("{}\n", " UNTRACKED CHANGES: ".black().on_cyan());
 
     // check for any untracked changes
-    match Command::new("git")
-        .args(["ls-files", "--others", "--exclude-standard"])
-        .output()
+    match ExternalCommand::git(["ls-files", "--others", "--exclude-standard"])
+        .current_dir(working_dir)
+        .run()
     {
         Ok(output) => {
-            let untracked_changes =
-                String::from_utf8_lossy(&output.stdout).to_string();
+            let untracked_changes = output.stdout;
             if !untracked_changes.is_empty() {
                 println!(
                     "  {}{}{}\n",
                     "New files ".yellow().italic(),
                     "not included in the previous commit ".magenta().italic(),
-                    "...".black().italic(),
+                    "...".muted().italic(),
                 );
                 for untracked_file in untracked_changes.lines() {
                     println!(
@@ -422,35 +421,656 @@ pub fn display_all_untracked_changes() {
                     "No ".magenta().italic(),
                     "untracked changes ".cyan().italic(),
                     "to commit ".magenta().italic(),
-                    "...".black().italic()
+                    "...".muted().italic()
                 );
             }
         }
-        Err(error) => log_diagnostic(DiagnosticKind::Error {
-            subject: "getting staged changes (git)",
-            body: &format!("{}", error),
-        }),
+        Err(error) => error.log(),
     }
 }
 
-pub fn display_current_branch() {
-    match Command::new("git")
-        .args(["branch", "--show-current"])
-        .output()
+pub fn display_current_branch(working_dir: &Path) {
+    match ExternalCommand::git(["branch", "--show-current"])
+        .current_dir(working_dir)
+        .run()
     {
-        Ok(output) => {
-            let current_branch = String::from_utf8_lossy(&output.stdout).to_string();
+        Ok(output) => display_branch(output.trimmed_stdout()),
+        Err(error) => error.log(),
+    }
+}
+
+/// Prints the branch the working directory is on.
+pub fn display_branch(branch: &str) {
+    println!(
+        "\n{}{}{}{}\n",
+        "On branch: ".muted().italic(),
+        " ".on_blue(),
+        branch.black().italic().on_blue(),
+        " ".on_blue()
+    );
+}
+
+/////////////////////////////////////////////////////
+// Changes of VCSs without git's staging area,     //
+// displayed the same way as git's                 //
+/////////////////////////////////////////////////////
+
+/// How a file changed since the last commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Modified,
+    Deleted,
+    Renamed,
+}
+
+impl ChangeKind {
+    const ALL: [ChangeKind; 4] = [
+        ChangeKind::Added,
+        ChangeKind::Modified,
+        ChangeKind::Deleted,
+        ChangeKind::Renamed,
+    ];
+
+    /// The badge shown in front of the changed path (e.g. " M ").
+    pub fn label(&self) -> ColoredString {
+        match self {
+            ChangeKind::Added => " A ".black().on_bright_green(),
+            ChangeKind::Modified => " M ".black().on_bright_yellow(),
+            ChangeKind::Deleted => " D ".black().on_bright_red(),
+            ChangeKind::Renamed => " R ".black().on_bright_blue(),
+        }
+    }
+
+    /// Colors the text after the kind of change.
+    pub fn describe(&self, text: &str) -> ColoredString {
+        match self {
+            ChangeKind::Added => text.bright_green(),
+            ChangeKind::Modified => text.bright_yellow(),
+            ChangeKind::Deleted => text.bright_red(),
+            ChangeKind::Renamed => text.bright_blue(),
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            ChangeKind::Added => "Added",
+            ChangeKind::Modified => "Modified",
+            ChangeKind::Deleted => "Deleted",
+            ChangeKind::Renamed => "Renamed",
+        }
+    }
+}
+
+/// A changed file, relative to the root of the repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileChange {
+    pub kind: ChangeKind,
+    pub path: String,
+}
+
+/// The changes in the working directory, grouped the way `scud state`
+/// displays them.
+///
+/// Properties:
+///
+/// * `staged`: Changes which will be part of the next commit.
+/// * `unstaged`: Changes to tracked files which won't be part of the next
+/// commit until they're staged (e.g. files deleted without the VCS knowing).
+/// * `untracked`: Paths of new files the VCS doesn't track yet.
+#[derive(Debug, Clone, Default)]
+pub struct RepositoryChanges {
+    pub staged: Vec<FileChange>,
+    pub unstaged: Vec<FileChange>,
+    pub untracked: Vec<String>,
+}
+
+impl RepositoryChanges {
+    /// The paths of the staged changes of the given kind.
+    pub fn staged_paths(&self, kind: ChangeKind) -> Vec<&str> {
+        self.staged
+            .iter()
+            .filter(|change| change.kind == kind)
+            .map(|change| change.path.as_str())
+            .collect()
+    }
+}
+
+/// Prints the changes of a repository in the same layout as `scud state`
+/// uses for git.
+///
+/// Arguments:
+///
+/// * `vcs_name`: The name of the VCS shown in the heading (e.g. Mercurial).
+/// * `branch`: The branch the working directory is on.
+/// * `changes`: The changes in the working directory.
+pub fn display_changes(vcs_name: &str, branch: &str, changes: &RepositoryChanges) {
+    display_state_header(vcs_name);
+    display_branch(branch);
+
+    println!("{}\n", " STAGED CHANGES: ".black().on_cyan());
+    display_file_changes(&changes.staged, "staged for commit ", "staged changes ");
+
+    println!("{}\n", " UNSTAGED CHANGES: ".black().on_cyan());
+    display_file_changes(
+        &changes.unstaged,
+        "not yet staged for commit ",
+        "unstaged changes ",
+    );
+
+    println!("{}\n", " UNTRACKED CHANGES: ".black().on_cyan());
+    if changes.untracked.is_empty() {
+        println!(
+            "  {}{}{}{}\n",
+            "No ".magenta().italic(),
+            "untracked changes ".cyan().italic(),
+            "to commit ".magenta().italic(),
+            "...".muted().italic()
+        );
+    } else {
+        println!(
+            "  {}{}{}\n",
+            "New files ".yellow().italic(),
+            "not included in the previous commit ".magenta().italic(),
+            "...".muted().italic(),
+        );
+        for untracked_file in &changes.untracked {
             println!(
-                "\n{}{}{}{}\n",
-                "On branch: ".black().italic(),
-                " ".on_blue(),
-                current_branch.trim().black().italic().on_blue(),
-                " ".on_blue()
+                "    {}  {}",
+                " ?? ".black().on_yellow(),
+                untracked_file.yellow().italic()
             );
         }
-        Err(error) => log_diagnostic(DiagnosticKind::Error {
-            subject: "getting current branch (git)",
-            body: &format!("{}", error),
-        }),
+        println!();
     }
 }
+
+fn display_file_changes(changes: &[FileChange], status: &str, none: &str) {
+    if changes.is_empty() {
+        println!(
+            "  {}{}{}{}\n",
+            "No ".magenta().italic(),
+            none.cyan().italic(),
+            "to commit ".magenta().italic(),
+            "...".muted().italic()
+        );
+        return;
+    }
+
+    for kind in ChangeKind::ALL {
+        let paths = changes
+            .iter()
+            .filter(|change| change.kind == kind)
+            .collect::<Vec<_>>();
+
+        if paths.is_empty() {
+            continue;
+        }
+
+        println!(
+            "  {}{}{}\n",
+            kind.describe(&format!("{} files ", kind.name())).italic(),
+            status.magenta().italic(),
+            "...".muted().italic(),
+        );
+        for change in paths {
+            println!(
+                "    {}  {}",
+                kind.label(),
+                kind.describe(&change.path).italic()
+            );
+        }
+        println!();
+    }
+}
+
+/// Gets the changes of the current git repository from `git status`, for
+/// when they're needed rather than displayed (e.g. by `scud workspace`).
+///
+/// Arguments:
+///
+/// * `working_dir`: The directory within the repository to run git in. The
+/// paths are relative to the root of the repository regardless.
+/// * `pathspecs`: The paths (relative to `working_dir`) to limit the changes
+/// to, or none for every change.
+///
+/// Renames are listed as the deletion of the old path and the addition of
+/// the new one, so every path can be staged or unstaged on its own.
+pub fn get_changes_git(
+    working_dir: &Path,
+    pathspecs: &[String],
+) -> ScudResult<RepositoryChanges> {
+    let status = ExternalCommand::git([
+        "status",
+        "--porcelain",
+        "-z",
+        "--untracked-files=all",
+        "--no-renames",
+        "--",
+    ])
+    .args(pathspecs)
+    .current_dir(working_dir)
+    .run()?
+    .stdout;
+
+    let mut changes = RepositoryChanges::default();
+    let mut entries = status.split('\0');
+
+    // The first column is the change to the index, the second one the change
+    // to the working tree, followed by the path (e.g. "MM src/main.rs").
+    // Copies are followed by an entry with the original path.
+    while let Some(entry) = entries.next() {
+        if entry.len() < 4 || !entry.is_char_boundary(3) {
+            continue;
+        }
+        let (code, path) = entry.split_at(3);
+        let mut code = code.chars();
+        let index = code.next().unwrap_or(' ');
+        let work_tree = code.next().unwrap_or(' ');
+
+        if index == 'R' || index == 'C' {
+            entries.next();
+        }
+        if index == '?' {
+            changes.untracked.push(path.to_string());
+            continue;
+        }
+
+        if let Some(kind) = get_change_kind_git(index) {
+            changes.staged.push(FileChange {
+                kind,
+                path: path.to_string(),
+            });
+        }
+        if let Some(kind) = get_change_kind_git(work_tree) {
+            changes.unstaged.push(FileChange {
+                kind,
+                path: path.to_string(),
+            });
+        }
+    }
+
+    Ok(changes)
+}
+
+/// The kind of change of a status code of `git status --porcelain` (e.g. M).
+fn get_change_kind_git(code: char) -> Option<ChangeKind> {
+    match code {
+        'A' | 'C' => Some(ChangeKind::Added),
+        'M' | 'T' => Some(ChangeKind::Modified),
+        'D' => Some(ChangeKind::Deleted),
+        'R' => Some(ChangeKind::Renamed),
+        _ => None,
+    }
+}
+
+/// Gets the changes of the current Mercurial repository from `hg status`.
+///
+/// Mercurial has no staging area, as `hg commit` commits every change to a
+/// tracked file. So modified, added and removed files count as staged, while
+/// files deleted without `hg remove` (`!`) are unstaged until they're removed.
+///
+/// Arguments:
+///
+/// * `repository_root`: The root of the repository, which the paths are
+/// relative to.
+pub fn get_changes_mercurial(
+    repository_root: &Path,
+) -> ScudResult<RepositoryChanges> {
+    let status = ExternalCommand::hg(["status"])
+        .current_dir(repository_root)
+        .run()?
+        .stdout;
+
+    let mut changes = RepositoryChanges::default();
+
+    // (e.g. "M src/main.rs")
+    for line in status.lines() {
+        let (code, path) = match line.split_once(' ') {
+            Some(status_and_path) => status_and_path,
+            None => continue,
+        };
+        let path = path.to_string();
+
+        match code {
+            "A" => changes.staged.push(FileChange {
+                kind: ChangeKind::Added,
+                path,
+            }),
+            "M" => changes.staged.push(FileChange {
+                kind: ChangeKind::Modified,
+                path,
+            }),
+            "R" => changes.staged.push(FileChange {
+                kind: ChangeKind::Deleted,
+                path,
+            }),
+            "!" => changes.unstaged.push(FileChange {
+                kind: ChangeKind::Deleted,
+                path,
+            }),
+            "?" => changes.untracked.push(path),
+            _ => {}
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Gets the branch the working directory of the current Mercurial repository
+/// is on, along with its active bookmark if there is one (e.g. feature
+/// (default)).
+pub fn get_current_branch_mercurial(working_dir: &Path) -> String {
+    let branch = ExternalCommand::hg(["branch"])
+        .current_dir(working_dir)
+        .stdout()
+        .unwrap_or_else(|| "default".to_string());
+    let bookmark = ExternalCommand::hg(["log", "-r", ".", "-T", "{activebookmark}"])
+        .current_dir(working_dir)
+        .stdout()
+        .unwrap_or_default();
+
+    if bookmark.is_empty() {
+        branch
+    } else {
+        format!("{} ({})", bookmark, branch)
+    }
+}
+
+/// Gets the changes of the current Breezy working tree from
+/// `brz status --short`.
+///
+/// Like Mercurial, Breezy commits every change to a versioned file, so only
+/// files deleted without `brz remove` are unstaged.
+///
+/// Arguments:
+///
+/// * `repository_root`: The root of the working tree, which the paths are
+/// relative to.
+pub fn get_changes_breezy(repository_root: &Path) -> ScudResult<RepositoryChanges> {
+    let status = ExternalCommand::brz(["status", "--short"])
+        .current_dir(repository_root)
+        .run()?
+        .stdout;
+
+    let mut changes = RepositoryChanges::default();
+
+    // The first column is the change to the versioning (e.g. + for added), the
+    // second one the change to the content (e.g. M for modified), followed by
+    // the path (e.g. "+N  src/main.rs")
+    for line in status.lines() {
+        if line.len() < 5 || !line.is_char_boundary(4) {
+            continue;
+        }
+        let (versioning, content) = (&line[0..1], &line[1..2]);
+        let path = line[4..].to_string();
+
+        let change = |kind| FileChange {
+            kind,
+            path: path.clone(),
+        };
+
+        match (versioning, content) {
+            ("+", _) => changes.staged.push(change(ChangeKind::Added)),
+            ("-", _) => changes.staged.push(change(ChangeKind::Deleted)),
+            ("R", _) => changes.staged.push(change(ChangeKind::Renamed)),
+            ("?", _) => changes.untracked.push(path.clone()),
+            (" ", "M") | (" ", "K") => {
+                changes.staged.push(change(ChangeKind::Modified))
+            }
+            // Files deleted without Breezy knowing, which `brz remove` stops
+            // tracking
+            (" ", "D") => changes.unstaged.push(change(ChangeKind::Deleted)),
+            _ => {}
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Gets the nickname of the current Breezy branch, which defaults to the
+/// name of its directory.
+pub fn get_current_branch_breezy(working_dir: &Path) -> String {
+    ExternalCommand::brz(["nick"])
+        .current_dir(working_dir)
+        .stdout()
+        .unwrap_or_default()
+}
+
+/// Gets the changes of the current Subversion working copy from
+/// `svn status`.
+///
+/// Subversion commits every change to a versioned file, so only files deleted
+/// without `svn delete` (`!`) and conflicted files are unstaged.
+///
+/// Arguments:
+///
+/// * `repository_root`: The root of the working copy, which the paths are
+/// relative to.
+pub fn get_changes_subversion(
+    repository_root: &Path,
+) -> ScudResult<RepositoryChanges> {
+    let status = ExternalCommand::svn(["status"])
+        .current_dir(repository_root)
+        .run()?
+        .stdout;
+
+    let mut changes = RepositoryChanges::default();
+
+    // The first seven columns are flags, the first one being the change to the
+    // item and the second one the change to its properties, followed by a space
+    // and the path (e.g. "M       src/main.rs")
+    for line in status.lines() {
+        if line.len() < 9 || !line.is_char_boundary(8) {
+            continue;
+        }
+        let (item, properties) = (&line[0..1], &line[1..2]);
+        let path = line[8..].to_string();
+
+        let change = |kind| FileChange {
+            kind,
+            path: path.clone(),
+        };
+
+        match (item, properties) {
+            ("A", _) => changes.staged.push(change(ChangeKind::Added)),
+            ("D", _) => changes.staged.push(change(ChangeKind::Deleted)),
+            // Replaced items were deleted and added again
+            ("M", _) | ("R", _) | (" ", "M") => {
+                changes.staged.push(change(ChangeKind::Modified))
+            }
+            ("!", _) => changes.unstaged.push(change(ChangeKind::Deleted)),
+            // Conflicted items can't be committed until they're resolved
+            ("C", _) | (_, "C") => {
+                changes.unstaged.push(change(ChangeKind::Modified))
+            }
+            ("?", _) => changes.untracked.push(path.clone()),
+            _ => {}
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Gets the path of the current Subversion working copy within its
+/// repository, which is where its branch is by convention (e.g. trunk or
+/// branches/feature).
+pub fn get_current_branch_subversion(working_dir: &Path) -> String {
+    let relative_url = ExternalCommand::svn(["info", "--show-item", "relative-url"])
+        .current_dir(working_dir)
+        .stdout()
+        .unwrap_or_default();
+
+    match relative_url.trim_start_matches('^').trim_matches('/') {
+        "" => relative_url,
+        branch => branch.to_string(),
+    }
+}
+
+/// Escapes a path for Subversion, which would read the part after an `@` as
+/// a peg revision (e.g. `logo@2x.png`).
+pub fn escape_path_subversion(path: &str) -> String {
+    if path.contains('@') {
+        format!("{}@", path)
+    } else {
+        path.to_string()
+    }
+}
+
+/// Gets the changes of the working-copy commit of the current Jujutsu
+/// repository from `jj diff --summary`, the same list `jj status` shows.
+///
+/// jj snapshots every change into the working-copy commit as it runs, so
+/// every change is staged and nothing is ever unstaged or untracked.
+///
+/// Arguments:
+///
+/// * `repository_root`: The root of the repository (jj prints the paths
+///   relative to the directory it's run from).
+///
+/// Returns:
+///
+/// A RepositoryChanges struct, or an error when jj fails
+pub fn get_changes_jujutsu(repository_root: &Path) -> ScudResult<RepositoryChanges> {
+    let summary = ExternalCommand::jj(["diff", "--summary"])
+        .current_dir(repository_root)
+        .run()?
+        .stdout;
+
+    let mut changes = RepositoryChanges::default();
+
+    // Each line is the kind of change followed by the path, renames and
+    // copies being written as e.g. "R src/{old.rs => new.rs}"
+    for line in summary.lines() {
+        let Some((kind, path)) = line.split_once(' ') else {
+            continue;
+        };
+        let kind = match kind {
+            "A" | "C" => ChangeKind::Added,
+            "M" => ChangeKind::Modified,
+            "D" => ChangeKind::Deleted,
+            "R" => ChangeKind::Renamed,
+            _ => continue,
+        };
+
+        changes.staged.push(FileChange {
+            kind,
+            path: path.to_string(),
+        });
+    }
+
+    Ok(changes)
+}
+
+/// Gets the bookmarks of the closest bookmarked ancestor of the working-copy
+/// commit, which jj users treat as the current branch, or the change ID of
+/// the working-copy commit when none of its ancestors has a bookmark.
+pub fn get_current_branch_jujutsu(working_dir: &Path) -> String {
+    let bookmarks = ExternalCommand::jj([
+        "log",
+        "--no-graph",
+        "-r",
+        "heads(::@ & bookmarks())",
+        "-T",
+        "local_bookmarks.map(|bookmark| bookmark.name()).join(\" \") ++ \"\\n\"",
+    ])
+    .current_dir(working_dir)
+    .stdout()
+    .and_then(|bookmarks| bookmarks.lines().next().map(str::to_string))
+    .filter(|bookmarks| !bookmarks.is_empty());
+
+    bookmarks.unwrap_or_else(|| {
+        ExternalCommand::jj([
+            "log",
+            "--no-graph",
+            "-r",
+            "@",
+            "-T",
+            "change_id.short()",
+        ])
+        .current_dir(working_dir)
+        .stdout()
+        .unwrap_or_default()
+    })
+}
+
+/// Gets the changes of the current Fossil checkout from `fossil changes`,
+/// along with `fossil extras` for the files Fossil doesn't manage.
+///
+/// Fossil commits every change to a managed file, so only files deleted
+/// without Fossil knowing (i.e. missing) and conflicted files are unstaged.
+///
+/// Arguments:
+///
+/// * `repository_root`: The root of the checkout (Fossil prints the paths
+///   relative to the directory it's run from).
+///
+/// Returns:
+///
+/// A RepositoryChanges struct, or an error when fossil fails
+pub fn get_changes_fossil(repository_root: &Path) -> ScudResult<RepositoryChanges> {
+    let status = ExternalCommand::fossil(["changes"])
+        .current_dir(repository_root)
+        .run()?
+        .stdout;
+
+    let mut changes = RepositoryChanges::default();
+
+    // Each line is the kind of change, padded with spaces, followed by the
+    // path (e.g. "EDITED     src/main.rs")
+    for line in status.lines() {
+        let Some((kind, path)) = line.split_once(' ') else {
+            continue;
+        };
+        let change = |kind| FileChange {
+            kind,
+            path: path.trim_start().to_string(),
+        };
+
+        match kind {
+            "ADDED" | "ADDED_BY_MERGE" | "ADDED_BY_INTEGRATE" => {
+                changes.staged.push(change(ChangeKind::Added))
+            }
+            "EDITED"
+            | "UPDATED_BY_MERGE"
+            | "UPDATED_BY_INTEGRATE"
+            | "EXECUTABLE"
+            | "UNEXEC"
+            | "SYMLINK"
+            | "UNLINK" => changes.staged.push(change(ChangeKind::Modified)),
+            "DELETED" => changes.staged.push(change(ChangeKind::Deleted)),
+            "RENAMED" => changes.staged.push(change(ChangeKind::Renamed)),
+            "MISSING" => changes.unstaged.push(change(ChangeKind::Deleted)),
+            // Conflicted files can't be committed until they're resolved
+            "CONFLICT" => changes.unstaged.push(change(ChangeKind::Modified)),
+            _ => {}
+        }
+    }
+
+    // fossil extras leaves out the files matching the ignore-glob setting
+    changes.untracked = ExternalCommand::fossil(["extras"])
+        .current_dir(repository_root)
+        .run()?
+        .stdout
+        .lines()
+        .map(str::to_string)
+        .collect();
+
+    Ok(changes)
+}
+
+/// Gets the branch of the current Fossil checkout, which `fossil branch list`
+/// marks with a star (e.g. " * trunk").
+pub fn get_current_branch_fossil(working_dir: &Path) -> String {
+    ExternalCommand::fossil(["branch", "list"])
+        .current_dir(working_dir)
+        .stdout()
+        .and_then(|branches| {
+            branches.lines().find_map(|line| {
+                line.trim_start()
+                    .strip_prefix("* ")
+                    .map(|branch| branch.trim().to_string())
+            })
+        })
+        .unwrap_or_else(|| "trunk".to_string())
+}
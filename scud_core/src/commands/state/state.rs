@@ -1,50 +1,60 @@
+use std::path::Path;
+
 use crate::{
     cli::cli::{State, VCS},
     commands::state::executors::{
-        execute_state_breezy, execute_state_git, execute_state_info,
-        execute_state_mercurial,
+        execute_state_breezy, execute_state_fossil, execute_state_git,
+        execute_state_info, execute_state_jujutsu, execute_state_mercurial,
+        execute_state_subversion,
     },
     diagnostics::{log_diagnostic, DiagnosticKind},
-    helpers::detect_vcs,
-    logging::helpers::bright_yellow_backtick,
+    error::ScudResult,
+    logging::helpers::backtick,
+    repository::Repository,
 };
 
 use colored::Colorize;
 
-pub fn state_command(state_options: State) {
+pub fn state_command(state_options: State, working_dir: &Path) -> ScudResult {
     if state_options.info {
         execute_state_info();
+        Ok(())
     } else {
-        execute_state();
+        execute_state(working_dir)
     }
 }
 
-fn execute_state() {
-    let vcs = detect_vcs();
+fn execute_state(working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
 
     match vcs {
-        VCS::Git => execute_state_git(),
-        VCS::Mercurial => execute_state_mercurial(),
-        VCS::Breezy => execute_state_breezy(),
+        VCS::Git => execute_state_git(working_dir)?,
+        VCS::Mercurial => execute_state_mercurial(working_dir)?,
+        VCS::Breezy => execute_state_breezy(working_dir)?,
+        VCS::Subversion => execute_state_subversion(working_dir)?,
+        VCS::Jujutsu => execute_state_jujutsu(working_dir)?,
+        VCS::Fossil => execute_state_fossil(working_dir)?,
     }
 
     log_diagnostic(DiagnosticKind::Tip {
         body: &format!(
             "{} {}{}{} {}{}{}{}{} {}{}{} {}{}",
             "Use".yellow(),
-            bright_yellow_backtick(),
+            backtick(),
             "scud stage".green().italic(),
-            bright_yellow_backtick(),
+            backtick(),
             "to stage all ".yellow(),
             "unstaged".bright_yellow(),
             " and ".yellow(),
             "untracked changes".bright_yellow(),
             " for commit or use".yellow(),
-            bright_yellow_backtick(),
+            backtick(),
             "scud commit".green().italic(),
-            bright_yellow_backtick(),
+            backtick(),
             "to commit any ".yellow(),
             "staged changes".bright_yellow()
         ),
     });
+
+    Ok(())
 }
@@ -0,0 +1,3 @@
+pub mod executors;
+pub mod journal;
+pub mod undo;
@@ -0,0 +1,317 @@
+use std::path::Path;
+
+use colored::Colorize;
+
+use crate::{
+    commands::undo::journal::{
+        last_operation_git, pop_operation_git, JournalEntry, Operation,
+    },
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::{ScudError, ScudResult},
+    logging::helpers::backtick,
+    runner::ExternalCommand,
+    state::executors::execute_state_git,
+};
+
+/// Shows which operation would be undone without modifying the repository.
+pub fn execute_undo_dry_run(working_dir: &Path) {
+    match last_operation_git(working_dir) {
+        Some(entry) => log_undo_plan(&entry),
+        None => nothing_to_undo().log(),
+    }
+
+    log_diagnostic(DiagnosticKind::DryRun { command: "undo" });
+}
+
+pub fn execute_undo_info() {
+    log_diagnostic(DiagnosticKind::ScudCommandInfo {
+        command: "undo",
+        description: "This command is used to revert the last mutating \
+                      operation scud performed in the current repository (stage, \
+                      unstage, commit or push). Before each of these operations \
+                      scud records the current HEAD, index tree and branch in a \
+                      journal located under .git/scud/, which is used to restore \
+                      the previous state.",
+    });
+    log_diagnostic(DiagnosticKind::VCSInfo {
+        command_name: "undo",
+        git_command: &format!(
+            "{} {}",
+            "git reset --soft <previous HEAD> | git read-tree <previous index> | git \
+             switch <previous branch>",
+            "(depending on the operation being undone)".bright_yellow()
+        ),
+        mercurial_command: &format!("{}", "(not yet supported)".bright_yellow()),
+        breezy_command: &format!("{}", "(not yet supported)".bright_yellow()),
+        other_commands: &[],
+    });
+}
+
+//////////////////////////////////////////////////////////
+// Functions handle the execution of each the supported //
+// underlying VCS implementations of the undo command   //
+//////////////////////////////////////////////////////////
+
+/// Reverts the last operation recorded in the journal of the current git
+/// repository. Pushes are only reverted when `force` is true since doing so
+/// rewrites the history of the remote repository.
+pub fn execute_undo_git(force: bool, working_dir: &Path) -> ScudResult {
+    let entry = last_operation_git(working_dir).ok_or_else(nothing_to_undo)?;
+
+    if entry.operation == Operation::Push && !force {
+        return Err(ScudError::failed(
+            "Refusing to undo a push",
+            "The last operation scud performed was a push. Undoing it means \
+             rewriting the history of the remote repository, which can discard \
+             work your collaborators have already fetched or built on top of.",
+        )
+        .with_hint(
+            format!(
+                "{}",
+                "If you are sure nobody else depends on the pushed commits, rerun \
+                 the command with the force flag"
+                    .yellow()
+            ),
+            "scud undo --force",
+        ));
+    }
+
+    log_undo_plan(&entry);
+
+    switch_back_to_branch(&entry, working_dir)?;
+
+    match entry.operation {
+        Operation::Stage | Operation::Unstage => restore_index(&entry, working_dir)?,
+        Operation::Commit => uncommit(&entry, working_dir)?,
+        Operation::Push => unpush(&entry, working_dir)?,
+    }
+
+    pop_operation_git(working_dir);
+
+    execute_state_git(working_dir)
+}
+
+pub fn execute_undo_mercurial() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud undo (mercurial)",
+    });
+}
+
+pub fn execute_undo_breezy() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud undo (breezy)",
+    });
+}
+
+pub fn execute_undo_subversion() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud undo (subversion)",
+    });
+}
+
+pub fn execute_undo_jujutsu() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud undo (jujutsu)",
+    });
+}
+
+pub fn execute_undo_fossil() {
+    log_diagnostic(DiagnosticKind::WorkInProgress {
+        feature: "scud undo (fossil)",
+    });
+}
+
+/////////////////////////////////////////////////
+// Helpers for restoring each recorded piece   //
+// of repository state                         //
+/////////////////////////////////////////////////
+
+/// Runs the given git command, failing with its stderr when it doesn't succeed.
+fn run_git(args: &[&str], working_dir: &Path) -> ScudResult {
+    ExternalCommand::git(args)
+        .current_dir(working_dir)
+        .run()
+        .map(|_| ())
+}
+
+/// Switches back to the branch the operation was performed on if the user
+/// has moved to another branch since.
+fn switch_back_to_branch(entry: &JournalEntry, working_dir: &Path) -> ScudResult {
+    let branch = match &entry.branch {
+        Some(branch) => branch,
+        None => return Ok(()),
+    };
+
+    let current_branch = ExternalCommand::git(["branch", "--show-current"])
+        .current_dir(working_dir)
+        .stdout()
+        .unwrap_or_default();
+
+    if current_branch == *branch {
+        return Ok(());
+    }
+
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{}{}{}",
+            "Switching back to branch ".yellow(),
+            branch.cyan().italic(),
+            " where the operation was performed".yellow()
+        ),
+    });
+
+    run_git(&["switch", branch], working_dir)
+}
+
+/// Restores the index to the tree recorded before a stage or unstage.
+fn restore_index(entry: &JournalEntry, working_dir: &Path) -> ScudResult {
+    match &entry.index_tree {
+        Some(index_tree) => run_git(&["read-tree", index_tree], working_dir),
+        None => Err(ScudError::failed(
+            "Unable to restore the index",
+            "No index tree was recorded for this operation (the index most likely \
+             contained unresolved merge conflicts).",
+        )),
+    }
+}
+
+/// Un-commits the last commit, keeping its changes staged.
+fn uncommit(entry: &JournalEntry, working_dir: &Path) -> ScudResult {
+    match &entry.head {
+        Some(head) => run_git(&["reset", "--soft", head], working_dir),
+        // The commit was the first one on an unborn branch
+        None => run_git(&["update-ref", "-d", "HEAD"], working_dir),
+    }
+}
+
+/// Resets the upstream branch back to where it pointed before the push.
+fn unpush(entry: &JournalEntry, working_dir: &Path) -> ScudResult {
+    let (upstream, upstream_head, head) =
+        match (&entry.upstream, &entry.upstream_head, &entry.head) {
+            (Some(upstream), Some(upstream_head), Some(head)) => {
+                (upstream, upstream_head, head)
+            }
+            _ => {
+                return Err(ScudError::failed(
+                    "Unable to undo push",
+                    "The state of the remote branch before the push was not \
+                     recorded (the branch most likely had no upstream yet).",
+                ));
+            }
+        };
+
+    let (remote, remote_branch) =
+        get_upstream_remote_git(entry, upstream, working_dir)?;
+
+    run_git(
+        &[
+            "push",
+            &format!("--force-with-lease={}:{}", remote_branch, head),
+            &remote,
+            &format!("{}:refs/heads/{}", upstream_head, remote_branch),
+        ],
+        working_dir,
+    )
+}
+
+/// Looks up the remote and the branch on it which the recorded upstream
+/// (e.g. `team/fork/main`) refers to. Remote names may contain slashes, so
+/// they're read from the configuration of the branch rather than split off
+/// the upstream.
+fn get_upstream_remote_git(
+    entry: &JournalEntry,
+    upstream: &str,
+    working_dir: &Path,
+) -> ScudResult<(String, String)> {
+    let branch_config = |key: &str| {
+        entry.branch.as_ref().and_then(|branch| {
+            ExternalCommand::git([
+                "config",
+                "--get",
+                &format!("branch.{}.{}", branch, key),
+            ])
+            .current_dir(working_dir)
+            .stdout()
+        })
+    };
+
+    let remote = branch_config("remote");
+    let remote_branch = branch_config("merge").map(|merge| {
+        merge
+            .strip_prefix("refs/heads/")
+            .map(str::to_string)
+            .unwrap_or(merge)
+    });
+
+    match (remote, remote_branch) {
+        (Some(remote), Some(remote_branch))
+            if format!("{}/{}", remote, remote_branch) == upstream =>
+        {
+            Ok((remote, remote_branch))
+        }
+        _ => Err(ScudError::failed(
+            "Unable to undo push",
+            format!(
+                "The branch no longer tracks {}, the upstream it was pushed to.",
+                upstream
+            ),
+        )),
+    }
+}
+
+/////////////////////////////////////
+// Logging helpers for undo output //
+/////////////////////////////////////
+
+fn log_undo_plan(entry: &JournalEntry) {
+    let short = |value: &Option<String>| match value {
+        Some(value) => value.chars().take(7).collect::<String>(),
+        None => "(none)".to_string(),
+    };
+
+    let plan = match entry.operation {
+        Operation::Stage | Operation::Unstage => {
+            format!("restore the index to tree {}", short(&entry.index_tree))
+        }
+        Operation::Commit => {
+            format!("un-commit with a soft reset to {}", short(&entry.head))
+        }
+        Operation::Push => format!(
+            "reset {} back to {}",
+            entry.upstream.clone().unwrap_or_default(),
+            short(&entry.upstream_head)
+        ),
+    };
+
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{}{}{}{}{}{}{}{}",
+            "Undoing ".yellow(),
+            backtick(),
+            format!("scud {}", entry.operation.as_str())
+                .green()
+                .italic(),
+            backtick(),
+            format!(" ({}, ", entry.age()).yellow(),
+            format!(
+                "on branch {}",
+                entry
+                    .branch
+                    .clone()
+                    .unwrap_or_else(|| "(detached)".to_string())
+            )
+            .cyan()
+            .italic(),
+            ") => ".yellow(),
+            plan.bright_yellow().italic()
+        ),
+    });
+}
+
+fn nothing_to_undo() -> ScudError {
+    ScudError::failed(
+        "Nothing to undo",
+        "scud has not recorded any stage, unstage, commit or push operations in \
+         the current repository.",
+    )
+}
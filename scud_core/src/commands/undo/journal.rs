@@ -0,0 +1,410 @@
+use std::{
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    runner::ExternalCommand,
+};
+
+//////////////////////////////////////////////////////
+// Journal of the mutating operations scud performs //
+// on behalf of the user (stored in .git/scud/)     //
+//////////////////////////////////////////////////////
+
+/// The mutating operations scud records in its journal
+/// so they can later be reverted with `scud undo`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    Stage,
+    Unstage,
+    Commit,
+    Push,
+}
+
+impl Operation {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Operation::Stage => "stage",
+            Operation::Unstage => "unstage",
+            Operation::Commit => "commit",
+            Operation::Push => "push",
+        }
+    }
+
+    pub fn parse(operation: &str) -> Option<Operation> {
+        match operation {
+            "stage" => Some(Operation::Stage),
+            "unstage" => Some(Operation::Unstage),
+            "commit" => Some(Operation::Commit),
+            "push" => Some(Operation::Push),
+            _ => None,
+        }
+    }
+}
+
+/// Snapshot of the repository taken right before a mutating operation.
+///
+/// Properties:
+///
+/// * `operation`: The operation which was performed after the snapshot was
+///   taken.
+/// * `head`: The commit `HEAD` pointed to (`None` on an unborn branch).
+/// * `index_tree`: The tree object written from the index (`git write-tree`).
+/// * `branch`: The checked-out branch (`None` when `HEAD` is detached).
+/// * `upstream`: The upstream of the branch (e.g. `origin/main`), if any.
+/// * `upstream_head`: The commit the upstream pointed to.
+/// * `timestamp`: Seconds since the unix epoch when the snapshot was taken.
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    pub operation: Operation,
+    pub head: Option<String>,
+    pub index_tree: Option<String>,
+    pub branch: Option<String>,
+    pub upstream: Option<String>,
+    pub upstream_head: Option<String>,
+    pub timestamp: u64,
+}
+
+impl JournalEntry {
+    /// Serializes the entry into a single tab-separated journal line
+    /// (missing values are written as `-`, and tabs, newlines and
+    /// backslashes within values are escaped).
+    pub fn to_line(&self) -> String {
+        let field = |value: &Option<String>| match value {
+            Some(value) => escape_field(value),
+            None => "-".to_string(),
+        };
+
+        format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            self.operation.as_str(),
+            field(&self.head),
+            field(&self.index_tree),
+            field(&self.branch),
+            field(&self.upstream),
+            field(&self.upstream_head),
+            self.timestamp
+        )
+    }
+
+    /// Parses a journal line previously written with `to_line`, or returns
+    /// None when the line is truncated or corrupt.
+    pub fn from_line(line: &str) -> Option<JournalEntry> {
+        let fields = line.split('\t').collect::<Vec<&str>>();
+
+        if fields.len() != 7 {
+            return None;
+        }
+
+        let field = |value: &str| match value {
+            "-" | "" => None,
+            value => Some(unescape_field(value)),
+        };
+
+        Some(JournalEntry {
+            operation: Operation::parse(fields[0])?,
+            head: field(fields[1]),
+            index_tree: field(fields[2]),
+            branch: field(fields[3]),
+            upstream: field(fields[4]),
+            upstream_head: field(fields[5]),
+            timestamp: fields[6].parse().ok()?,
+        })
+    }
+
+    /// Human-readable age of the entry (e.g. `5 minutes ago`).
+    pub fn age(&self) -> String {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(self.timestamp);
+        let elapsed = now.saturating_sub(self.timestamp);
+
+        match elapsed {
+            0..=59 => format!("{} seconds ago", elapsed),
+            60..=3599 => format!("{} minutes ago", elapsed / 60),
+            3600..=86399 => format!("{} hours ago", elapsed / 3600),
+            _ => format!("{} days ago", elapsed / 86400),
+        }
+    }
+}
+
+/// Escapes the characters which would otherwise split a value across
+/// journal fields or lines, as well as a lone `-` which stands for a missing
+/// value.
+fn escape_field(value: &str) -> String {
+    if value == "-" {
+        return "\\-".to_string();
+    }
+
+    let mut escaped = String::with_capacity(value.len());
+
+    for character in value.chars() {
+        match character {
+            '\\' => escaped.push_str("\\\\"),
+            '\t' => escaped.push_str("\\t"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            character => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+/// Reverts `escape_field`. Unknown escapes are kept as they are.
+fn unescape_field(value: &str) -> String {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut characters = value.chars();
+
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            unescaped.push(character);
+            continue;
+        }
+
+        match characters.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('t') => unescaped.push('\t'),
+            Some('n') => unescaped.push('\n'),
+            Some('r') => unescaped.push('\r'),
+            Some('-') => unescaped.push('-'),
+            Some(other) => {
+                unescaped.push('\\');
+                unescaped.push(other);
+            }
+            None => unescaped.push('\\'),
+        }
+    }
+
+    unescaped
+}
+
+/// Runs the given git command and returns its trimmed stdout
+/// when the command succeeds with a non-empty output.
+fn git_output(working_dir: &Path, args: &[&str]) -> Option<String> {
+    ExternalCommand::git(args)
+        .current_dir(working_dir)
+        .stdout()
+        .filter(|stdout| !stdout.is_empty())
+}
+
+/// Location of the journal within the git directory of the current repository
+/// (i.e. `.git/scud/journal`).
+pub fn journal_path_git(working_dir: &Path) -> Option<PathBuf> {
+    git_output(working_dir, &["rev-parse", "--absolute-git-dir"])
+        .map(|git_dir| PathBuf::from(git_dir).join("scud").join("journal"))
+}
+
+/// Captures the state of the repository (HEAD, index tree, branch and
+/// upstream) before the given operation is performed.
+pub fn snapshot_git(operation: Operation, working_dir: &Path) -> JournalEntry {
+    JournalEntry {
+        operation,
+        head: git_output(working_dir, &["rev-parse", "--verify", "--quiet", "HEAD"]),
+        index_tree: git_output(working_dir, &["write-tree"]),
+        branch: git_output(working_dir, &["branch", "--show-current"]),
+        upstream: git_output(
+            working_dir,
+            &["rev-parse", "--abbrev-ref", "--symbolic-full-name", "@{u}"],
+        ),
+        upstream_head: git_output(
+            working_dir,
+            &["rev-parse", "--verify", "--quiet", "@{u}"],
+        ),
+        timestamp: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default(),
+    }
+}
+
+/// Appends the given snapshot to the journal of the current repository.
+pub fn record_operation_git(entry: JournalEntry, working_dir: &Path) {
+    let journal_path = match journal_path_git(working_dir) {
+        Some(journal_path) => journal_path,
+        None => return,
+    };
+
+    if let Some(journal_dir) = journal_path.parent() {
+        if let Err(error) = fs::create_dir_all(journal_dir) {
+            log_diagnostic(DiagnosticKind::Error {
+                subject: "failed to create the scud journal directory",
+                body: &format!("{}", error),
+            });
+            return;
+        }
+    }
+
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&journal_path)
+        .and_then(|mut journal| writeln!(journal, "{}", entry.to_line()));
+
+    if let Err(error) = result {
+        log_diagnostic(DiagnosticKind::Error {
+            subject: "failed to record operation in the scud journal",
+            body: &format!("{}", error),
+        });
+    }
+}
+
+/// Reads every entry of the journal of the current repository
+/// (oldest first).
+pub fn read_journal_git(working_dir: &Path) -> Vec<JournalEntry> {
+    journal_path_git(working_dir)
+        .and_then(|journal_path| fs::read_to_string(journal_path).ok())
+        .map(|journal| {
+            journal
+                .lines()
+                .filter_map(JournalEntry::from_line)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Returns the most recently recorded operation, if any.
+pub fn last_operation_git(working_dir: &Path) -> Option<JournalEntry> {
+    read_journal_git(working_dir).pop()
+}
+
+/// Removes the most recently recorded operation from the journal.
+pub fn pop_operation_git(working_dir: &Path) {
+    let journal_path = match journal_path_git(working_dir) {
+        Some(journal_path) => journal_path,
+        None => return,
+    };
+
+    let mut entries = read_journal_git(working_dir);
+    entries.pop();
+
+    let journal = entries
+        .iter()
+        .map(|entry| format!("{}\n", entry.to_line()))
+        .collect::<String>();
+
+    if let Err(error) = fs::write(journal_path, journal) {
+        log_diagnostic(DiagnosticKind::Error {
+            subject: "failed to update the scud journal",
+            body: &format!("{}", error),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry() -> JournalEntry {
+        JournalEntry {
+            operation: Operation::Push,
+            head: Some("1111111111111111111111111111111111111111".to_string()),
+            index_tree: Some("2222222222222222222222222222222222222222".to_string()),
+            branch: Some("main".to_string()),
+            upstream: Some("team/fork/main".to_string()),
+            upstream_head: Some(
+                "3333333333333333333333333333333333333333".to_string(),
+            ),
+            timestamp: 1_700_000_000,
+        }
+    }
+
+    fn assert_same(parsed: &JournalEntry, entry: &JournalEntry) {
+        assert_eq!(parsed.operation, entry.operation);
+        assert_eq!(parsed.head, entry.head);
+        assert_eq!(parsed.index_tree, entry.index_tree);
+        assert_eq!(parsed.branch, entry.branch);
+        assert_eq!(parsed.upstream, entry.upstream);
+        assert_eq!(parsed.upstream_head, entry.upstream_head);
+        assert_eq!(parsed.timestamp, entry.timestamp);
+    }
+
+    #[test]
+    fn entries_round_trip() {
+        let entry = entry();
+
+        let parsed = JournalEntry::from_line(&entry.to_line()).unwrap();
+
+        assert_same(&parsed, &entry);
+    }
+
+    #[test]
+    fn missing_values_round_trip() {
+        let entry = JournalEntry {
+            operation: Operation::Commit,
+            head: None,
+            index_tree: None,
+            branch: None,
+            upstream: None,
+            upstream_head: None,
+            timestamp: 0,
+        };
+
+        let line = entry.to_line();
+
+        assert_eq!(line, "commit\t-\t-\t-\t-\t-\t0");
+        assert_same(&JournalEntry::from_line(&line).unwrap(), &entry);
+    }
+
+    #[test]
+    fn tabs_newlines_and_backslashes_are_escaped() {
+        let entry = JournalEntry {
+            branch: Some("a\tb\nc\r\\d\\te".to_string()),
+            upstream: Some("-".to_string()),
+            ..entry()
+        };
+
+        let line = entry.to_line();
+
+        assert_eq!(line.split('\t').count(), 7);
+        assert!(!line.contains('\n') && !line.contains('\r'));
+        assert_same(&JournalEntry::from_line(&line).unwrap(), &entry);
+    }
+
+    #[test]
+    fn unknown_escapes_are_kept() {
+        assert_eq!(unescape_field("a\\qb\\"), "a\\qb\\");
+    }
+
+    #[test]
+    fn truncated_lines_are_skipped() {
+        let line = entry().to_line();
+
+        for length in 0..line.len() {
+            let truncated = &line[..length];
+            // Cutting the timestamp short still leaves a valid (earlier) one
+            if truncated.split('\t').count() == 7 {
+                continue;
+            }
+            assert!(
+                JournalEntry::from_line(truncated).is_none(),
+                "parsed {:?}",
+                truncated
+            );
+        }
+    }
+
+    #[test]
+    fn corrupt_lines_are_skipped() {
+        let line = entry().to_line();
+
+        for corrupt in [
+            line.replacen("push", "rebase", 1),
+            line.replace("1700000000", "yesterday"),
+            line.replace("1700000000", "-1"),
+            format!("{}\textra", line),
+            line.replace('\t', " "),
+        ] {
+            assert!(
+                JournalEntry::from_line(&corrupt).is_none(),
+                "parsed {:?}",
+                corrupt
+            );
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use crate::{
+    cli::cli::{Undo, VCS},
+    commands::undo::executors::{
+        execute_undo_breezy, execute_undo_dry_run, execute_undo_fossil,
+        execute_undo_git, execute_undo_info, execute_undo_jujutsu,
+        execute_undo_mercurial, execute_undo_subversion,
+    },
+    error::ScudResult,
+    repository::Repository,
+};
+
+/// Executes the undo command with the given options
+///
+/// Arguments:
+///
+/// * `undo_options`: Struct that contains the options that were passed to the
+///   command via user input (e.g. dry-run, info, force).
+/// * `working_dir`: The directory to operate on, which is the current one
+///   unless given with --directory.
+pub fn undo_command(undo_options: Undo, working_dir: &Path) -> ScudResult {
+    if undo_options.dry_run {
+        execute_undo_dry_run(working_dir);
+    } else if undo_options.info {
+        execute_undo_info();
+    } else {
+        execute_undo(undo_options.force, working_dir)?;
+    }
+
+    Ok(())
+}
+
+/// Detects the version control system, and then calls the appropriate
+/// function to revert the last recorded operation
+fn execute_undo(force: bool, working_dir: &Path) -> ScudResult {
+    let vcs = Repository::discover(working_dir)?.vcs;
+
+    match vcs {
+        VCS::Git => execute_undo_git(force, working_dir)?,
+        VCS::Mercurial => execute_undo_mercurial(),
+        VCS::Breezy => execute_undo_breezy(),
+        VCS::Subversion => execute_undo_subversion(),
+        VCS::Jujutsu => execute_undo_jujutsu(),
+        VCS::Fossil => execute_undo_fossil(),
+    }
+
+    Ok(())
+}
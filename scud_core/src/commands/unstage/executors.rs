@@ -1,32 +1,364 @@
-use std::process::Command;
+use std::{fs, path::Path};
+
+use colored::Colorize;
 
 use crate::{
+    cli::cli::VCS,
+    commands::{
+        hooks::helpers::get_repository_root,
+        stage::{
+            helpers::{
+                apply_picks_git, get_diff_git, literal_pathspec, ChangeSelection,
+                PatchDirection,
+            },
+            interactive::{pick_files, pick_hunks},
+        },
+        state::helpers::{
+            escape_path_subversion, get_changes_breezy, get_changes_fossil,
+            get_changes_git, get_changes_mercurial, get_changes_subversion,
+            ChangeKind, RepositoryChanges,
+        },
+        undo::journal::{record_operation_git, snapshot_git, Operation},
+    },
     diagnostics::{log_diagnostic, DiagnosticKind},
-    state::executors::execute_state_git,
+    error::{ScudError, ScudResult},
+    logging::helpers::backtick,
+    runner::ExternalCommand,
+    state::executors::{
+        execute_state_breezy, execute_state_fossil, execute_state_git,
+        execute_state_jujutsu, execute_state_mercurial, execute_state_subversion,
+    },
 };
 
-pub fn execute_unstage_git() {
-    match Command::new("git").arg("reset").output() {
-        Ok(_) /* git add -A doesn't emit any output */ => {
-            execute_state_git();
+/// Unstages the staged changes of the current git repository: every one of
+/// them, the ones within the given paths, the files picked from a list
+/// (--interactive), or the hunks and lines picked from the changes
+/// (--patch).
+pub fn execute_unstage_git(
+    selection: &ChangeSelection,
+    working_dir: &Path,
+) -> ScudResult {
+    // Record the index before unstaging so `scud undo` can restore it
+    let snapshot = snapshot_git(Operation::Unstage, working_dir);
+
+    let unstaged = if selection.interactive || selection.patch {
+        unstage_picked_changes_git(selection, working_dir)?
+    } else {
+        ExternalCommand::git(["reset", "--"])
+            .args(&selection.paths)
+            .current_dir(working_dir)
+            .run()?;
+        true
+    };
+
+    if unstaged {
+        record_operation_git(snapshot, working_dir);
+    }
+    execute_state_git(working_dir)
+}
+
+/// Asks which of the staged changes to unstage, first by file and then by
+/// hunk when both --interactive and --patch are given.
+///
+/// Returns:
+///
+/// Whether anything was unstaged.
+fn unstage_picked_changes_git(
+    selection: &ChangeSelection,
+    working_dir: &Path,
+) -> ScudResult<bool> {
+    let mut pathspecs = selection.paths.clone();
+
+    if selection.interactive {
+        let files = get_changes_git(working_dir, &selection.paths)?
+            .staged
+            .iter()
+            .map(|change| {
+                (
+                    change.path.clone(),
+                    format!(
+                        "{}  {}",
+                        change.kind.label(),
+                        change.kind.describe(&change.path)
+                    ),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        if files.is_empty() {
+            log_nothing_to_unstage();
+            return Ok(false);
+        }
+
+        let picked = pick_files(&files, PatchDirection::Unstage)?;
+        if picked.is_empty() {
+            return Ok(false);
+        }
+        pathspecs = picked.iter().map(|path| literal_pathspec(path)).collect();
+
+        if !selection.patch {
+            ExternalCommand::git(["reset", "--quiet", "--"])
+                .args(&pathspecs)
+                .current_dir(working_dir)
+                .run()?;
+            return Ok(true);
         }
-        Err(error) => log_diagnostic(
-            DiagnosticKind::Error {
-                subject: "failed to stage (git)",
-                body: &format!("{}", error)
-            }
-        )
     }
+
+    let files = get_diff_git(PatchDirection::Unstage, &pathspecs, working_dir)?;
+    if files.is_empty() {
+        log_nothing_to_unstage();
+        return Ok(false);
+    }
+
+    let picks = pick_hunks(&files, PatchDirection::Unstage)?;
+    apply_picks_git(&files, &picks, PatchDirection::Unstage, working_dir)
 }
 
-pub fn execute_unstage_mercurial() {
-    log_diagnostic(DiagnosticKind::WorkInProgress {
-        feature: "scud unstage (mercurial)",
+fn log_nothing_to_unstage() {
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!("{}", "There are no staged changes to pick from".yellow()),
     });
 }
 
-pub fn execute_unstage_breezy() {
-    log_diagnostic(DiagnosticKind::WorkInProgress {
-        feature: "scud unstage (breezy)",
+/// Undoes `scud stage` in Mercurial: added files are forgotten again and
+/// removed files go back to being deleted without Mercurial knowing. Modified
+/// files can't be unstaged, as `hg commit` commits every change to a tracked
+/// file.
+pub fn execute_unstage_mercurial(working_dir: &Path) -> ScudResult {
+    let repository_root = get_repository_root(&VCS::Mercurial, working_dir);
+    let changes = get_changes_mercurial(&repository_root)?;
+
+    let added = changes.staged_paths(ChangeKind::Added);
+    if !added.is_empty() {
+        ExternalCommand::hg(["forget"])
+            .args(added.iter().map(|path| format!("path:{}", path)))
+            .current_dir(&repository_root)
+            .run()?;
+    }
+
+    // hg revert brings back the removed files, which are then deleted again
+    // so the deletion shows up as an unstaged change (unless they were only
+    // forgotten, and are still there)
+    let removed = changes.staged_paths(ChangeKind::Deleted);
+    if !removed.is_empty() {
+        revert_deletions(&repository_root, &removed, || {
+            ExternalCommand::hg(["revert", "--no-backup"])
+                .args(removed.iter().map(|path| format!("path:{}", path)))
+                .current_dir(&repository_root)
+                .run()
+                .map(|_| ())
+        })?;
+    }
+
+    log_modified_files_stay_staged(&changes, "Mercurial");
+
+    execute_state_mercurial(working_dir)
+}
+
+/// Undoes `scud stage` in Breezy: added files are unversioned again and
+/// removed files go back to being deleted without Breezy knowing. Modified
+/// files can't be unstaged, as `brz commit` commits every change to a
+/// versioned file.
+pub fn execute_unstage_breezy(working_dir: &Path) -> ScudResult {
+    let repository_root = get_repository_root(&VCS::Breezy, working_dir);
+    let changes = get_changes_breezy(&repository_root)?;
+
+    let added = changes.staged_paths(ChangeKind::Added);
+    if !added.is_empty() {
+        ExternalCommand::brz(["remove", "--keep", "--"])
+            .args(&added)
+            .current_dir(&repository_root)
+            .run()?;
+    }
+
+    // brz revert brings back the removed files, which are then deleted again
+    // so the deletion shows up as an unstaged change (unless they were
+    // removed with --keep, and are still there)
+    let removed = changes.staged_paths(ChangeKind::Deleted);
+    if !removed.is_empty() {
+        revert_deletions(&repository_root, &removed, || {
+            ExternalCommand::brz(["revert", "--no-backup", "--"])
+                .args(&removed)
+                .current_dir(&repository_root)
+                .run()
+                .map(|_| ())
+        })?;
+    }
+
+    log_modified_files_stay_staged(&changes, "Breezy");
+
+    execute_state_breezy(working_dir)
+}
+
+/// Undoes `scud stage` in Subversion: added files are unscheduled again and
+/// deleted files go back to being missing. Modified files can't be unstaged,
+/// as `svn commit` commits every change to a versioned file.
+pub fn execute_unstage_subversion(working_dir: &Path) -> ScudResult {
+    let repository_root = get_repository_root(&VCS::Subversion, working_dir);
+    let changes = get_changes_subversion(&repository_root)?;
+
+    // Reverting a directory reverts everything below it, so only the
+    // outermost paths are reverted (and deleted again)
+    let added = outermost_paths(changes.staged_paths(ChangeKind::Added));
+    if !added.is_empty() {
+        ExternalCommand::svn(["revert", "--depth", "infinity", "--"])
+            .args(added.iter().map(|path| escape_path_subversion(path)))
+            .current_dir(&repository_root)
+            .run()?;
+    }
+
+    // svn revert brings back the deleted files, which are then deleted again
+    // so the deletion shows up as an unstaged change (unless they were deleted
+    // with --keep-local, and are still there)
+    let deleted = outermost_paths(changes.staged_paths(ChangeKind::Deleted));
+    if !deleted.is_empty() {
+        revert_deletions(&repository_root, &deleted, || {
+            ExternalCommand::svn(["revert", "--depth", "infinity", "--"])
+                .args(deleted.iter().map(|path| escape_path_subversion(path)))
+                .current_dir(&repository_root)
+                .run()
+                .map(|_| ())
+        })?;
+    }
+
+    log_modified_files_stay_staged(&changes, "Subversion");
+
+    execute_state_subversion(working_dir)
+}
+
+/// Undoes `scud stage` in Fossil: added files are forgotten again and deleted
+/// files go back to being missing. Modified files can't be unstaged, as
+/// `fossil commit` commits every change to a managed file.
+pub fn execute_unstage_fossil(working_dir: &Path) -> ScudResult {
+    let repository_root = get_repository_root(&VCS::Fossil, working_dir);
+    let changes = get_changes_fossil(&repository_root)?;
+
+    let added = changes.staged_paths(ChangeKind::Added);
+    if !added.is_empty() {
+        ExternalCommand::fossil(["forget"])
+            .args(&added)
+            .current_dir(&repository_root)
+            .run()?;
+    }
+
+    // fossil rm keeps the file by default, so only the deleted files that are
+    // gone (e.g. staged by scud stage) are brought back by fossil revert and
+    // deleted again
+    let deleted = changes
+        .staged_paths(ChangeKind::Deleted)
+        .into_iter()
+        .filter(|path| !repository_root.join(path).exists())
+        .collect::<Vec<_>>();
+    if !deleted.is_empty() {
+        ExternalCommand::fossil(["revert"])
+            .args(&deleted)
+            .current_dir(&repository_root)
+            .run()?;
+        delete_files(&repository_root, &deleted)?;
+    }
+
+    log_modified_files_stay_staged(&changes, "Fossil");
+
+    execute_state_fossil(working_dir)
+}
+
+/// Shows the changes of the working-copy commit, as there's nothing to
+/// unstage in Jujutsu: every change is part of the working-copy commit.
+pub fn execute_unstage_jujutsu(working_dir: &Path) -> ScudResult {
+    log_diagnostic(DiagnosticKind::Note {
+        body: &format!(
+            "{} {}{}{} {}",
+            "Changes stay in the working-copy commit, since jj has no staging \
+             area. Use"
+                .yellow(),
+            backtick(),
+            "jj split".green().italic(),
+            backtick(),
+            "to commit only some of them".yellow()
+        ),
     });
+
+    execute_state_jujutsu(working_dir)
+}
+
+/// Drops the paths that are inside another one of the given paths.
+fn outermost_paths(paths: Vec<&str>) -> Vec<&str> {
+    paths
+        .iter()
+        .filter(|path| {
+            !paths
+                .iter()
+                .any(|other| path.starts_with(&format!("{}/", other)))
+        })
+        .copied()
+        .collect()
+}
+
+/// Runs the given revert of staged deletions without losing the files which
+/// were only untracked and kept on disk (e.g. with `hg forget`). Files which
+/// were gone before the revert are deleted again, while the others get back
+/// the contents the revert replaced with the committed version.
+fn revert_deletions(
+    repository_root: &Path,
+    paths: &[&str],
+    revert: impl FnOnce() -> ScudResult,
+) -> ScudResult {
+    let (kept, missing): (Vec<&str>, Vec<&str>) = paths
+        .iter()
+        .partition(|path| repository_root.join(path).exists());
+
+    // Directories are kept as a whole, only the contents of files matter
+    let kept_files = kept
+        .iter()
+        .filter_map(|path| {
+            fs::read(repository_root.join(path))
+                .ok()
+                .map(|contents| (*path, contents))
+        })
+        .collect::<Vec<_>>();
+
+    revert()?;
+
+    for (path, contents) in kept_files {
+        fs::write(repository_root.join(path), contents).map_err(|error| {
+            ScudError::io(format!("Failed to restore {}", path), error)
+        })?;
+    }
+
+    delete_files(repository_root, &missing)
+}
+
+fn delete_files(repository_root: &Path, paths: &[&str]) -> ScudResult {
+    for path in paths {
+        let path_in_repository = repository_root.join(path);
+        let deleted = if path_in_repository.is_dir() {
+            fs::remove_dir_all(path_in_repository)
+        } else {
+            fs::remove_file(path_in_repository)
+        };
+        deleted.map_err(|error| {
+            ScudError::io(format!("Failed to delete {}", path), error)
+        })?;
+    }
+
+    Ok(())
+}
+
+/// Tells the user that modified files can't be unstaged in VCSs without a
+/// staging area.
+fn log_modified_files_stay_staged(changes: &RepositoryChanges, vcs_name: &str) {
+    if !changes.staged_paths(ChangeKind::Modified).is_empty() {
+        log_diagnostic(DiagnosticKind::Note {
+            body: &format!(
+                "{}",
+                format!(
+                    "Modified files stay staged, since {} commits every change to \
+                     a tracked file",
+                    vcs_name
+                )
+                .yellow()
+            ),
+        });
+    }
 }
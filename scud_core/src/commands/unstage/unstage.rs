@@ -1,24 +1,41 @@
+use std::path::Path;
+
 use colored::Colorize;
 
 use crate::{
     cli::cli::{Unstage, VCS},
-    commands::unstage::executors::execute_unstage_git,
-    detect_vcs,
+    commands::{
+        stage::{helpers::ChangeSelection, stage::check_selection},
+        unstage::executors::{
+            execute_unstage_breezy, execute_unstage_fossil, execute_unstage_git,
+            execute_unstage_jujutsu, execute_unstage_mercurial,
+            execute_unstage_subversion,
+        },
+    },
     diagnostics::{log_diagnostic, DiagnosticKind},
-    logging::helpers::bright_yellow_backtick,
+    error::ScudResult,
+    logging::helpers::backtick,
+    repository::Repository,
 };
 
-pub fn unstage_command(unstage_options: Unstage) {
+pub fn unstage_command(unstage_options: Unstage, working_dir: &Path) -> ScudResult {
     if unstage_options.dry_run {
         execute_unstage_dry_run();
     } else if unstage_options.info {
         execute_unstage_info();
     } else {
-        execute_unstage();
+        let selection = ChangeSelection {
+            paths: unstage_options.paths,
+            interactive: unstage_options.interactive,
+            patch: unstage_options.patch,
+        };
+        execute_unstage(&selection, working_dir)?;
     }
+
+    Ok(())
 }
 
-fn execute_unstage() {
+fn execute_unstage(selection: &ChangeSelection, working_dir: &Path) -> ScudResult {
     log_diagnostic(DiagnosticKind::ScudCommandInfo {
         command: "unstage",
         description: "This command is intended to unstage all modified files in \
@@ -31,24 +48,31 @@ fn execute_unstage() {
                       the current repository.",
     });
 
-    let vcs = detect_vcs();
+    let vcs = Repository::discover(working_dir)?.vcs;
+
+    check_selection(selection, vcs, "unstage")?;
 
     match vcs {
-        VCS::Git => execute_unstage_git(),
-        VCS::Mercurial => println!("{}", "Mercurial repository detected".green()),
-        VCS::Breezy => println!("{}", "Breezy repository detected".green()),
+        VCS::Git => execute_unstage_git(selection, working_dir)?,
+        VCS::Mercurial => execute_unstage_mercurial(working_dir)?,
+        VCS::Breezy => execute_unstage_breezy(working_dir)?,
+        VCS::Subversion => execute_unstage_subversion(working_dir)?,
+        VCS::Jujutsu => execute_unstage_jujutsu(working_dir)?,
+        VCS::Fossil => execute_unstage_fossil(working_dir)?,
     }
 
     log_diagnostic(DiagnosticKind::Tip {
         body: &format!(
             "{} {}{}{} {}",
             "When you're ready, use".yellow(),
-            bright_yellow_backtick(),
+            backtick(),
             "scud stage".green().italic(),
-            bright_yellow_backtick(),
+            backtick(),
             "to stage all unstaged changes and untracked files for commit".yellow(),
         ),
     });
+
+    Ok(())
 }
 
 pub fn execute_unstage_dry_run() {
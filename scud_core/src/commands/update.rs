@@ -1,2 +1,4 @@
 pub mod executors;
+pub mod helpers;
+pub mod notifier;
 pub mod update;
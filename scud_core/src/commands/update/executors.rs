@@ -1,55 +1,189 @@
-use std::{process::Command, time::Duration};
-
 use colored::Colorize;
-use update_informer::{registry, Check};
-
-pub fn execute_update() {
-    // const EVERY_MIN: Duration = Duration::from_secs(60);
-    const EVERY_HOUR: Duration = Duration::from_secs(60 * 60);
-
-    let pkg_name = env!("CARGO_PKG_NAME");
-    let current_version = env!("CARGO_PKG_VERSION");
-    let informer = update_informer::new(registry::Crates, pkg_name, current_version)
-        .interval(EVERY_HOUR);
-
-    if let Ok(Some(_new_version)) = informer.check_version() {
-        match Command::new("cargo")
-            .arg("install")
-            .arg("scud")
-            .arg("--force")
-            .status()
-        {
-            Ok(status) => {
-                if !status.success() {
-                    panic!("Failed to stage files");
-                } else {
-                    // log_repo_status(false);
-                }
-            }
-            Err(error) => {
-                panic!("Failed to stage files: {}", error);
+
+use crate::{
+    commands::{
+        setup::helpers::is_in_path,
+        update::helpers::{
+            get_install_args, get_latest_version, is_older, is_valid_version,
+            NO_UPDATE_CHECK_ENV,
+        },
+    },
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::{ScudError, ScudResult},
+    logging::helpers::backtick,
+    logging::theme::Themed,
+    runner::ExternalCommand,
+};
+
+const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Updates scud to the latest version, or the pinned version when given.
+///
+/// Arguments:
+///
+/// * `version`: The version to install instead of the latest (e.g. 0.12.0).
+/// * `dry_run`: Whether to only show the install plan.
+pub fn execute_update(version: Option<&str>, dry_run: bool) -> ScudResult {
+    let target_version = match version {
+        Some(version) => {
+            let version = version.trim_start_matches('v');
+
+            if !is_valid_version(version) {
+                return Err(ScudError::failed(
+                    format!("Invalid version {}", version),
+                    "Versions are expected to be full semantic versions (e.g. \
+                     0.13.0).",
+                ));
             }
+
+            version.to_string()
         }
-        // TODO log version change
-    } else {
-        println!("{}", "No new release available".green());
+        None => get_latest_version().map_err(|error| {
+            ScudError::failed(
+                "Failed to check for the latest release of scud",
+                format!("crates.io couldn't be reached: {}", error),
+            )
+            .with_hint(
+                format!(
+                    "{}",
+                    "Install a specific version without checking with".yellow()
+                ),
+                "scud update --version <version>",
+            )
+        })?,
+    };
+
+    log_versions(&target_version, version.is_some());
+
+    if target_version == CURRENT_VERSION
+        || (version.is_none() && is_older(&target_version, CURRENT_VERSION))
+    {
+        println!(
+            "{}\n",
+            format!("scud is already up to date (v{})", CURRENT_VERSION).green()
+        );
+        return Ok(());
+    }
+
+    if is_older(&target_version, CURRENT_VERSION) {
+        log_diagnostic(DiagnosticKind::Warning {
+            subject: &format!("Downgrading scud to v{}", target_version),
+            body: "The pinned version is older than the installed version.",
+        });
+    }
+
+    let install_args = get_install_args(&target_version);
+    let install_command = format!("cargo {}", install_args.join(" "));
+
+    if dry_run {
+        println!("{}", "Install plan:".bright_yellow());
         println!(
-            "{}{}{}{}{}{}{}",
-            " INFO ".black().on_yellow(),
-            " A new release of ".yellow().bold(),
-            "scud".yellow().italic(),
-            " is available: ".yellow().bold(),
-            "0.3.0".red().bold().italic(),
-            " -> ".black(),
-            "0.6.0".to_string().green().bold().italic()
+            "    {} {}{}{}",
+            "1.".muted(),
+            backtick(),
+            install_command.green().italic(),
+            backtick()
+        );
+
+        if !is_in_path("cargo") {
+            println!(
+                "    {}",
+                "(cargo isn't installed, it's needed to update scud)"
+                    .bright_red()
+                    .italic()
+            );
+        }
+
+        log_diagnostic(DiagnosticKind::DryRun { command: "update" });
+        return Ok(());
+    }
+
+    if !is_in_path("cargo") {
+        return Err(ScudError::failed(
+            "cargo is not installed or not found in PATH",
+            "scud is distributed via crates.io and updates itself with cargo.",
         )
+        .with_hint(
+            format!("{}", "Install cargo along with Rust with".yellow()),
+            "curl --proto '=https' --tlsv1.2 -sSf https://sh.rustup.rs | sh",
+        ));
+    }
+
+    println!(
+        "{} {}{}{}\n",
+        "Running".bright_yellow(),
+        backtick(),
+        install_command.green().italic(),
+        backtick()
+    );
+
+    let install = ExternalCommand::new("cargo")
+        .args(&install_args)
+        .run_attached();
+
+    if let Err(error @ ScudError::CommandFailed { .. }) = install {
+        return Err(ScudError::failed(
+            format!("Failed to update scud to v{}", target_version),
+            format!("{}, see its output above.", error),
+        ));
     }
+    install?;
+
+    println!(
+        "\n{} {} {} {}\n",
+        "Updated scud:".green(),
+        CURRENT_VERSION.bright_red().italic(),
+        "->".muted(),
+        target_version.bright_green().italic()
+    );
+
+    Ok(())
 }
 
-pub fn execute_update_dry_run() {
-    println!("{}", "Dry run".green());
+fn log_versions(target_version: &str, pinned: bool) {
+    println!(
+        "{} {} {} {} {}\n",
+        " INFO ".black().on_yellow(),
+        "Current version:".bright_yellow(),
+        CURRENT_VERSION.cyan().italic(),
+        if pinned {
+            "Pinned version:".bright_yellow()
+        } else {
+            "Latest version:".bright_yellow()
+        },
+        target_version.cyan().italic()
+    );
 }
 
 pub fn execute_update_info() {
-    println!("{}", "Info".green());
+    log_diagnostic(DiagnosticKind::ScudCommandInfo {
+        command: "update",
+        description: "This command is used to update scud to the latest version \
+                      released on crates.io, or to the version given with \
+                      --version [alias: up]",
+    });
+
+    println!(
+        "{}\n\n    {}{}{}\n",
+        "Under the hood, scud runs:".bright_yellow(),
+        backtick(),
+        "cargo install scud --version <version> --locked --force"
+            .green()
+            .italic(),
+        backtick()
+    );
+
+    log_diagnostic(DiagnosticKind::Tip {
+        body: &format!(
+            "{} {} {} {}{}{}",
+            "Turn off the notifications about new releases by setting".yellow(),
+            NO_UPDATE_CHECK_ENV.cyan().italic(),
+            "or running".yellow(),
+            backtick(),
+            "git config --global scud.updateCheck false"
+                .green()
+                .italic(),
+            backtick()
+        ),
+    });
 }
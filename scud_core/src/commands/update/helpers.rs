@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use update_informer::{registry, Check};
+
+use crate::{
+    commands::healthcheck::helpers::parse_version, runner::ExternalCommand,
+};
+
+/// Name of the crate scud is published as (this library is `scud_core`).
+pub const PACKAGE_NAME: &str = "scud";
+
+/// Environment variable which, when set, turns off checking for new releases.
+pub const NO_UPDATE_CHECK_ENV: &str = "SCUD_NO_UPDATE_CHECK";
+
+/// How long to wait for crates.io to answer.
+const REGISTRY_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Whether the user opted out of checking for new releases via the
+/// `SCUD_NO_UPDATE_CHECK` environment variable.
+pub fn update_checks_disabled_by_env() -> bool {
+    std::env::var(NO_UPDATE_CHECK_ENV)
+        .map(|value| !matches!(value.trim(), "" | "0" | "false"))
+        .unwrap_or(false)
+}
+
+/// Whether the user opted out of checking for new releases via the
+/// `scud.updateCheck` git config (e.g. `git config --global scud.updateCheck
+/// false`). Reading it spawns git, so it's only read once a check is due or
+/// there's a new release to tell about.
+pub fn update_checks_disabled_by_config() -> bool {
+    ExternalCommand::git(["config", "--get", "--type=bool", "scud.updateCheck"])
+        .stdout()
+        .as_deref()
+        == Some("false")
+}
+
+/// Asks crates.io for the latest release of scud.
+///
+/// Returns:
+///
+/// The latest version, which is the current version when scud is up to date,
+/// or a description of why the registry couldn't be reached
+pub fn get_latest_version() -> Result<String, String> {
+    get_latest_version_with_timeout(REGISTRY_TIMEOUT)
+}
+
+/// Asks crates.io for the latest release of scud, giving up after the
+/// timeout.
+pub fn get_latest_version_with_timeout(timeout: Duration) -> Result<String, String> {
+    let current_version = env!("CARGO_PKG_VERSION");
+
+    // A zero interval skips update-informer's own cache so the answer is
+    // always fresh
+    update_informer::new(registry::Crates, PACKAGE_NAME, current_version)
+        .interval(Duration::ZERO)
+        .timeout(timeout)
+        .check_version()
+        .map(|new_version| match new_version {
+            Some(new_version) => {
+                new_version.to_string().trim_start_matches('v').to_string()
+            }
+            None => current_version.to_string(),
+        })
+        .map_err(|error| error.to_string())
+}
+
+/// Whether the version is a full `major.minor.patch` version (optionally with
+/// a pre-release, e.g. `0.14.0-beta.1`), as accepted by `cargo install
+/// --version`.
+pub fn is_valid_version(version: &str) -> bool {
+    let release = version.split('-').next().unwrap_or_default();
+
+    release.split('.').count() == 3
+        && release
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Compares two versions by their `major.minor.patch` release.
+pub fn is_older(version: &str, than: &str) -> bool {
+    match (parse_version(version), parse_version(than)) {
+        (Some(version), Some(than)) => version < than,
+        _ => false,
+    }
+}
+
+/// The command installing the given version of scud.
+pub fn get_install_args(version: &str) -> Vec<String> {
+    [
+        "install",
+        PACKAGE_NAME,
+        "--version",
+        version,
+        "--locked",
+        "--force",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect()
+}
@@ -0,0 +1,205 @@
+use std::{
+    env, fs,
+    io::IsTerminal,
+    path::PathBuf,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+use colored::Colorize;
+
+use crate::commands::update::helpers::{
+    get_latest_version_with_timeout, is_older, update_checks_disabled_by_config,
+    update_checks_disabled_by_env,
+};
+use crate::logging::theme::Themed;
+
+/// How often crates.io is asked for the latest release.
+const CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60 * 24);
+
+/// How long the background check may take before it's abandoned. Commands
+/// finishing sooner wait for the check at most this long after it started.
+const CHECK_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Environment variables set by CI providers.
+const CI_ENV_VARS: &[&str] = &[
+    "CI",
+    "CONTINUOUS_INTEGRATION",
+    "GITHUB_ACTIONS",
+    "GITLAB_CI",
+    "BUILDKITE",
+    "TF_BUILD",
+    "JENKINS_URL",
+];
+
+/// The outcome of the latest check for a new release, as cached on disk.
+///
+/// Properties:
+///
+/// * `checked_at`: When crates.io was last asked (seconds since the epoch).
+/// * `latest_version`: The latest version it reported.
+struct CachedCheck {
+    checked_at: u64,
+    latest_version: String,
+}
+
+/// A check for a new release of scud, started before running a command and
+/// finished once it's done.
+///
+/// Properties:
+///
+/// * `cached`: The result of the previous check, shown once the command is
+///   done.
+/// * `pending`: Signals the background check being done, when one was
+///   started.
+/// * `started_at`: When the background check was started.
+pub struct UpdateCheck {
+    cached: Option<CachedCheck>,
+    pending: Option<Receiver<()>>,
+    started_at: Instant,
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
+
+fn is_ci() -> bool {
+    CI_ENV_VARS.iter().any(|var| {
+        env::var(var)
+            .map(|value| !matches!(value.trim(), "" | "0" | "false"))
+            .unwrap_or(false)
+    })
+}
+
+/// Where the result of the latest check is cached (e.g.
+/// ~/.cache/scud/latest-version).
+fn get_cache_path() -> Option<PathBuf> {
+    let cache_dir = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache"))
+        })
+        .or_else(|| env::var_os("LOCALAPPDATA").map(PathBuf::from))?;
+
+    Some(cache_dir.join("scud").join("latest-version"))
+}
+
+fn read_cache() -> Option<CachedCheck> {
+    let content = fs::read_to_string(get_cache_path()?).ok()?;
+    let (checked_at, latest_version) = content.trim().split_once(' ')?;
+
+    Some(CachedCheck {
+        checked_at: checked_at.parse().ok()?,
+        latest_version: latest_version.to_string(),
+    })
+}
+
+/// Writes the cache via a temporary file, so concurrent invocations never read
+/// half written content.
+fn write_cache(latest_version: &str) {
+    let cache_path = match get_cache_path() {
+        Some(cache_path) => cache_path,
+        None => return,
+    };
+    let temp_path = cache_path.with_extension(format!("{}.tmp", std::process::id()));
+
+    let written = cache_path
+        .parent()
+        .map(|cache_dir| fs::create_dir_all(cache_dir).is_ok())
+        .unwrap_or(false)
+        && fs::write(&temp_path, format!("{} {}\n", now(), latest_version)).is_ok();
+
+    if !written || fs::rename(&temp_path, &cache_path).is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+}
+
+/// Starts checking for a new release of scud. The check is skipped under CI,
+/// when the output isn't a terminal, or when the user opted out (see
+/// `update_checks_disabled_by_env` and `update_checks_disabled_by_config`).
+/// Otherwise, crates.io is asked on a background thread at most once a day,
+/// and the answer is cached on disk to be shown by the next invocation.
+pub fn start_update_check() -> UpdateCheck {
+    let mut update_check = UpdateCheck {
+        cached: None,
+        pending: None,
+        started_at: Instant::now(),
+    };
+
+    if is_ci() || !std::io::stdout().is_terminal() || update_checks_disabled_by_env()
+    {
+        return update_check;
+    }
+
+    update_check.cached = read_cache();
+
+    let is_stale = update_check
+        .cached
+        .as_ref()
+        .map(|cached| {
+            now().saturating_sub(cached.checked_at) >= CHECK_INTERVAL.as_secs()
+        })
+        .unwrap_or(true);
+
+    if is_stale {
+        let (done, pending) = mpsc::channel();
+        let previous_version = update_check
+            .cached
+            .as_ref()
+            .map(|cached| cached.latest_version.clone())
+            .unwrap_or_else(|| env!("CARGO_PKG_VERSION").to_string());
+
+        thread::spawn(move || {
+            // Opting out via the config is cached like a check finding
+            // nothing new, so git isn't spawned again until the next check is
+            // due. A failed check is cached too, so being offline doesn't make
+            // every invocation wait for crates.io
+            let latest_version = match update_checks_disabled_by_config() {
+                true => previous_version,
+                false => get_latest_version_with_timeout(CHECK_TIMEOUT)
+                    .unwrap_or(previous_version),
+            };
+            write_cache(&latest_version);
+            let _ = done.send(());
+        });
+
+        update_check.pending = Some(pending);
+    }
+
+    update_check
+}
+
+impl UpdateCheck {
+    /// Gives the background check the rest of its time to finish caching its
+    /// answer, and notifies the user when the previous check found a newer
+    /// release.
+    pub fn finish(self) {
+        if let Some(pending) = self.pending {
+            let remaining = CHECK_TIMEOUT.saturating_sub(self.started_at.elapsed());
+            let _ = pending.recv_timeout(remaining);
+        }
+
+        let current_version = env!("CARGO_PKG_VERSION");
+
+        if let Some(cached) = self.cached {
+            if is_older(current_version, &cached.latest_version)
+                && !update_checks_disabled_by_config()
+            {
+                println!(
+                    "{}{}{}{}{}{}{}\n",
+                    " INFO ".on_yellow(),
+                    " A new release of ".bright_yellow(),
+                    "scud".yellow().italic(),
+                    " is available: ".bright_yellow(),
+                    current_version.bright_red().italic(),
+                    " -> ".muted(),
+                    cached.latest_version.bright_green().italic()
+                );
+            }
+        }
+    }
+}
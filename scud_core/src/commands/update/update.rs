@@ -1,16 +1,20 @@
 use crate::{
     cli::cli::Update,
-    commands::update::executors::{
-        execute_update, execute_update_dry_run, execute_update_info,
-    },
+    commands::update::executors::{execute_update, execute_update_info},
+    error::ScudResult,
 };
 
-pub fn update_command(update_options: Update) {
-    if update_options.dry_run {
-        execute_update_dry_run();
-    } else if update_options.info {
+/// Executes the update command with the given options
+///
+/// Arguments:
+///
+/// * `update_options`: Struct that contains the options that were passed to
+///   the command via user input (e.g. version, dry_run).
+pub fn update_command(update_options: Update) -> ScudResult {
+    if update_options.info {
         execute_update_info();
+        Ok(())
     } else {
-        execute_update();
+        execute_update(update_options.version.as_deref(), update_options.dry_run)
     }
 }
@@ -0,0 +1,3 @@
+pub mod executors;
+pub mod helpers;
+pub mod workspace;
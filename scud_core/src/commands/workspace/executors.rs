@@ -0,0 +1,179 @@
+use std::path::Path;
+
+use crate::{
+    cli::cli::VCS,
+    commands::push::executors::get_push_args_git,
+    error::{ScudError, ScudResult},
+    repository::Repository,
+    runner::{CommandOutput, ExternalCommand},
+};
+
+/// Pulls the commits of the remote repository into the repository at the
+/// given path, with the output captured rather than printed.
+///
+/// Returns:
+///
+/// What happened to the repository (e.g. "up to date").
+pub fn pull_repository(path: &Path) -> ScudResult<String> {
+    let repository = Repository::discover(path)?;
+    let root = repository.root.as_path();
+
+    match repository.vcs {
+        VCS::Git => {
+            let output = non_interactive_git(["pull"], root).run()?;
+            Ok(describe_outcome(&output, "Already up to date", "pulled"))
+        }
+        VCS::Mercurial => {
+            let output = ExternalCommand::hg(["pull", "--update"])
+                .current_dir(root)
+                .run()?;
+            Ok(describe_outcome(&output, "no changes found", "pulled"))
+        }
+        VCS::Breezy => {
+            let output = ExternalCommand::brz(["pull"]).current_dir(root).run()?;
+            Ok(describe_outcome(&output, "No revisions", "pulled"))
+        }
+        VCS::Subversion => {
+            ExternalCommand::svn(["update", "--non-interactive"])
+                .current_dir(root)
+                .run()?;
+            Ok("updated".to_string())
+        }
+        VCS::Jujutsu => {
+            ExternalCommand::jj(["git", "fetch"])
+                .current_dir(root)
+                .run()?;
+            Ok("fetched".to_string())
+        }
+        VCS::Fossil => {
+            // Without autosync, fossil update only updates to what's local
+            ExternalCommand::fossil(["pull"]).current_dir(root).run()?;
+            ExternalCommand::fossil(["update"])
+                .current_dir(root)
+                .run()?;
+            Ok("pulled".to_string())
+        }
+    }
+}
+
+/// Pushes the commits of the repository at the given path to its remote
+/// repository, with the output captured rather than printed.
+///
+/// Returns:
+///
+/// What happened to the repository (e.g. "pushed").
+pub fn push_repository(path: &Path) -> ScudResult<String> {
+    let repository = Repository::discover(path)?;
+    let root = repository.root.as_path();
+
+    match repository.vcs {
+        VCS::Git => {
+            let output = non_interactive_git(get_push_args_git(root), root).run()?;
+            Ok(describe_outcome(&output, "Everything up-to-date", "pushed"))
+        }
+        VCS::Mercurial => {
+            // hg push exits with 1 when there's nothing to push
+            let output = ExternalCommand::hg(["push"]).current_dir(root).output()?;
+            match output.status {
+                Some(1) => Ok("up to date".to_string()),
+                _ => output.check().map(|_| "pushed".to_string()),
+            }
+        }
+        VCS::Breezy => {
+            let output = ExternalCommand::brz(["push"]).current_dir(root).run()?;
+            Ok(describe_outcome(&output, "No new revisions", "pushed"))
+        }
+        // svn commit already sends every commit to the repository
+        VCS::Subversion => Ok("nothing to push".to_string()),
+        VCS::Jujutsu => {
+            let output = ExternalCommand::jj(["git", "push"])
+                .current_dir(root)
+                .run()?;
+            Ok(describe_outcome(&output, "Nothing changed", "pushed"))
+        }
+        VCS::Fossil => {
+            ExternalCommand::fossil(["push"]).current_dir(root).run()?;
+            Ok("pushed".to_string())
+        }
+    }
+}
+
+/// Switches the working directory of the repository at the given path to
+/// the given branch (or bookmark), with the output captured rather than
+/// printed.
+///
+/// Returns:
+///
+/// What happened to the repository (e.g. "switched").
+pub fn switch_repository(path: &Path, branch: &str) -> ScudResult<String> {
+    let repository = Repository::discover(path)?;
+    let root = repository.root.as_path();
+
+    match repository.vcs {
+        VCS::Git => {
+            ExternalCommand::git(["switch", branch])
+                .current_dir(root)
+                .run()?;
+        }
+        VCS::Mercurial => {
+            ExternalCommand::hg(["update", branch])
+                .current_dir(root)
+                .run()?;
+        }
+        // Only checkouts can switch, standalone branches live in a directory
+        // of their own
+        VCS::Breezy => {
+            ExternalCommand::brz(["switch", branch])
+                .current_dir(root)
+                .run()?;
+        }
+        VCS::Subversion => {
+            return Err(ScudError::failed(
+                "Cannot switch branches of a Subversion working copy",
+                "Subversion branches are directories of the repository, which \
+                 are switched to by their URL with svn switch.",
+            ));
+        }
+        // A new working-copy commit on top of the bookmark, rather than
+        // editing the commit it points at
+        VCS::Jujutsu => {
+            ExternalCommand::jj(["new", branch])
+                .current_dir(root)
+                .run()?;
+        }
+        VCS::Fossil => {
+            ExternalCommand::fossil(["update", branch])
+                .current_dir(root)
+                .run()?;
+        }
+    }
+
+    Ok("switched".to_string())
+}
+
+/// A git command which fails rather than prompting for credentials, since
+/// the prompts of repositories processed at the same time would interleave.
+fn non_interactive_git<I, S>(args: I, root: &Path) -> ExternalCommand
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<std::ffi::OsStr>,
+{
+    ExternalCommand::git(args)
+        .env("GIT_TERMINAL_PROMPT", "0")
+        .current_dir(root)
+}
+
+/// Tells whether the command had nothing to do from its output (e.g. git
+/// pull printing "Already up to date.").
+fn describe_outcome(
+    output: &CommandOutput,
+    nothing_to_do: &str,
+    done: &str,
+) -> String {
+    if output.stdout.contains(nothing_to_do) || output.stderr.contains(nothing_to_do)
+    {
+        "up to date".to_string()
+    } else {
+        done.to_string()
+    }
+}
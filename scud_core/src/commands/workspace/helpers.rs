@@ -0,0 +1,491 @@
+use std::{
+    fs,
+    num::NonZeroUsize,
+    panic,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use colored::{ColoredString, Colorize};
+
+use crate::{
+    cli::cli::VCS,
+    commands::state::helpers::{
+        get_changes_breezy, get_changes_fossil, get_changes_git,
+        get_changes_jujutsu, get_changes_mercurial, get_changes_subversion,
+        get_commit_diff, get_current_branch_breezy, get_current_branch_fossil,
+        get_current_branch_jujutsu, get_current_branch_mercurial,
+        get_current_branch_subversion, RepositoryChanges,
+    },
+    error::{ScudError, ScudResult},
+    logging::theme::Themed,
+    repository::{detect_vcs, Repository},
+    runner::ExternalCommand,
+};
+
+/// Name of the manifest listing the repositories of a workspace, which is
+/// read from the directory scud runs in when `--manifest` isn't given.
+pub const DEFAULT_MANIFEST: &str = "scud-workspace";
+
+/// How many directories below the workspace directory repositories are
+/// looked for when there's no manifest (e.g. team/service/api).
+const DISCOVERY_DEPTH: usize = 3;
+
+/// A repository of the workspace, which may turn out not to exist when
+/// it's listed in a manifest.
+///
+/// Properties:
+///
+/// * `name`: How the repository is referred to in the output, which is its
+/// path relative to the manifest or to the directory it was found in.
+/// * `path`: The path of the repository.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WorkspaceRepository {
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// The state of a repository of the workspace, as listed by
+/// `scud workspace state`.
+///
+/// Properties:
+///
+/// * `vcs`: The version control system of the repository.
+/// * `branch`: The branch the working directory is on.
+/// * `changes`: The changes in the working directory.
+/// * `ahead_behind`: How far the branch is ahead of or behind its upstream
+/// (e.g. "ahead 1, behind 2"), which is empty when they're in sync or the
+/// VCS can't tell without contacting the remote.
+#[derive(Debug, Clone)]
+pub struct RepositorySummary {
+    pub vcs: VCS,
+    pub branch: String,
+    pub changes: RepositoryChanges,
+    pub ahead_behind: String,
+}
+
+/// A row of the table printed for the workspace. Columns which are None in
+/// every row are left out (e.g. the changes when only listing branches).
+///
+/// Properties:
+///
+/// * `repository`: The name of the repository.
+/// * `vcs`: The name of its version control system.
+/// * `branch`: The branch the working directory is on.
+/// * `changes`: A summary of the changes (e.g. "2 staged, 1 untracked").
+/// * `ahead_behind`: How far the branch is ahead of or behind its upstream.
+/// * `outcome`: What the command did to the repository (e.g. pulled).
+/// * `failed`: Whether the command failed for the repository.
+#[derive(Debug, Clone, Default)]
+pub struct WorkspaceRow {
+    pub repository: String,
+    pub vcs: String,
+    pub branch: Option<String>,
+    pub changes: Option<String>,
+    pub ahead_behind: Option<String>,
+    pub outcome: Option<String>,
+    pub failed: bool,
+}
+
+/// Finds the repositories of the workspace. A manifest given with
+/// `--manifest` takes precedence over a directory given with `--dir`, which
+/// takes precedence over the `scud-workspace` manifest of the working
+/// directory, falling back to the repositories found under it.
+///
+/// Arguments:
+///
+/// * `manifest`: The manifest given with `--manifest`, if any.
+/// * `dir`: The directory given with `--dir`, if any.
+/// * `working_dir`: The directory relative paths are relative to, which is
+/// the current one unless given with --directory.
+pub fn get_workspace_repositories(
+    manifest: Option<&Path>,
+    dir: Option<&Path>,
+    working_dir: &Path,
+) -> ScudResult<Vec<WorkspaceRepository>> {
+    if let Some(manifest) = manifest {
+        return read_manifest(&working_dir.join(manifest));
+    }
+    if let Some(dir) = dir {
+        return discover_repositories(&working_dir.join(dir));
+    }
+
+    let default_manifest = working_dir.join(DEFAULT_MANIFEST);
+    if default_manifest.is_file() {
+        read_manifest(&default_manifest)
+    } else {
+        discover_repositories(working_dir)
+    }
+}
+
+/// Reads the repositories listed in a manifest, one path per line (e.g.
+/// "../api"). Paths are relative to the directory of the manifest, and
+/// blank lines and lines starting with # are skipped.
+pub fn read_manifest(manifest: &Path) -> ScudResult<Vec<WorkspaceRepository>> {
+    let content = fs::read_to_string(manifest).map_err(|error| {
+        ScudError::io(
+            format!(
+                "Failed to read the workspace manifest {}",
+                manifest.display()
+            ),
+            error,
+        )
+    })?;
+    let base = manifest.parent().unwrap_or_else(|| Path::new("."));
+
+    Ok(content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| WorkspaceRepository {
+            name: line.to_string(),
+            path: base.join(line),
+        })
+        .collect())
+}
+
+/// Finds the repositories under the given directory, of any supported VCS.
+/// Repositories nested within a repository (e.g. submodules) aren't looked
+/// for, as they belong to it.
+pub fn discover_repositories(dir: &Path) -> ScudResult<Vec<WorkspaceRepository>> {
+    if !dir.is_dir() {
+        return Err(ScudError::failed(
+            "Cannot look for the repositories of the workspace",
+            format!("{} isn't a directory", dir.display()),
+        ));
+    }
+
+    Ok(find_repositories(dir, 0)
+        .into_iter()
+        .map(|path| WorkspaceRepository {
+            name: match path.strip_prefix(dir) {
+                Ok(name) if !name.as_os_str().is_empty() => {
+                    name.display().to_string()
+                }
+                _ => ".".to_string(),
+            },
+            path,
+        })
+        .collect())
+}
+
+fn find_repositories(directory: &Path, depth: usize) -> Vec<PathBuf> {
+    if detect_vcs(directory).is_some() {
+        return vec![directory.to_path_buf()];
+    }
+    if depth >= DISCOVERY_DEPTH {
+        return vec![];
+    }
+
+    // Hidden directories (e.g. .cache) aren't expected to hold repositories
+    let mut entries = fs::read_dir(directory)
+        .map(|entries| {
+            entries
+                .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| {
+                    path.is_dir()
+                        && path.file_name().map_or(false, |name| {
+                            !name.to_string_lossy().starts_with('.')
+                        })
+                })
+                .collect::<Vec<PathBuf>>()
+        })
+        .unwrap_or_default();
+    entries.sort();
+
+    entries
+        .iter()
+        .flat_map(|entry| find_repositories(entry, depth + 1))
+        .collect()
+}
+
+/// The number of repositories processed at the same time when `--jobs`
+/// isn't given.
+pub fn default_jobs() -> usize {
+    thread::available_parallelism().map_or(4, NonZeroUsize::get)
+}
+
+/// Runs the given operation on every repository, with up to `jobs` of them
+/// being processed at the same time. The output of the commands spawned by
+/// the operation should be captured, as it would otherwise interleave.
+///
+/// Returns:
+///
+/// The result of the operation for each repository, in the same order as
+/// the repositories.
+pub fn for_each_repository<T, F>(
+    repositories: &[WorkspaceRepository],
+    jobs: usize,
+    operation: F,
+) -> Vec<ScudResult<T>>
+where
+    T: Send,
+    F: Fn(&WorkspaceRepository) -> ScudResult<T> + Sync,
+{
+    let next = AtomicUsize::new(0);
+    let workers = jobs.clamp(1, repositories.len().max(1));
+
+    let mut results = thread::scope(|scope| {
+        let handles = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut results = vec![];
+                    // Each worker takes the next repository nobody has taken yet
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        match repositories.get(index) {
+                            Some(repository) => {
+                                results.push((index, operation(repository)))
+                            }
+                            None => break results,
+                        }
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| {
+                handle
+                    .join()
+                    .unwrap_or_else(|payload| panic::resume_unwind(payload))
+            })
+            .collect::<Vec<_>>()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Gets the state of the repository at the given path without printing it.
+pub fn get_repository_summary(path: &Path) -> ScudResult<RepositorySummary> {
+    let repository = Repository::discover(path)?;
+    let root = repository.root.as_path();
+
+    let (branch, changes, ahead_behind) = match repository.vcs {
+        VCS::Git => get_summary_git(root)?,
+        VCS::Mercurial => (
+            get_current_branch_mercurial(root),
+            get_changes_mercurial(root)?,
+            String::new(),
+        ),
+        VCS::Breezy => (
+            get_current_branch_breezy(root),
+            get_changes_breezy(root)?,
+            String::new(),
+        ),
+        VCS::Subversion => (
+            get_current_branch_subversion(root),
+            get_changes_subversion(root)?,
+            String::new(),
+        ),
+        VCS::Jujutsu => (
+            get_current_branch_jujutsu(root),
+            get_changes_jujutsu(root)?,
+            String::new(),
+        ),
+        VCS::Fossil => (
+            get_current_branch_fossil(root),
+            get_changes_fossil(root)?,
+            String::new(),
+        ),
+    };
+
+    Ok(RepositorySummary {
+        vcs: repository.vcs,
+        branch,
+        changes,
+        ahead_behind,
+    })
+}
+
+fn get_summary_git(root: &Path) -> ScudResult<(String, RepositoryChanges, String)> {
+    #[cfg(feature = "gix")]
+    if let Some(state) = crate::native::get_state_git(root) {
+        return Ok((state.branch, state.changes, state.ahead_behind));
+    }
+
+    let branch = ExternalCommand::git(["branch", "--show-current"])
+        .current_dir(root)
+        .run()?
+        .trimmed_stdout()
+        .to_string();
+    let status_diff = ExternalCommand::git(["status", "-sb"])
+        .current_dir(root)
+        .run()?
+        .stdout;
+
+    Ok((branch, get_changes_git(root, &[])?, get_commit_diff(status_diff)))
+}
+
+/// The name of a VCS as shown in the table (e.g. Mercurial).
+pub fn vcs_name(vcs: VCS) -> &'static str {
+    match vcs {
+        VCS::Git => "Git",
+        VCS::Mercurial => "Mercurial",
+        VCS::Breezy => "Breezy",
+        VCS::Subversion => "Subversion",
+        VCS::Jujutsu => "Jujutsu",
+        VCS::Fossil => "Fossil",
+    }
+}
+
+impl WorkspaceRow {
+    /// A row with the state of a repository.
+    pub fn from_summary(name: &str, summary: &RepositorySummary) -> Self {
+        WorkspaceRow {
+            repository: name.to_string(),
+            vcs: vcs_name(summary.vcs).to_string(),
+            branch: Some(display_branch_name(&summary.branch)),
+            changes: Some(describe_changes(&summary.changes)),
+            ahead_behind: Some(
+                match (summary.vcs, summary.ahead_behind.is_empty()) {
+                    (VCS::Git, true) => "in sync".to_string(),
+                    (_, true) => "-".to_string(),
+                    (_, false) => summary.ahead_behind.clone(),
+                },
+            ),
+            ..WorkspaceRow::default()
+        }
+    }
+
+    /// A row for a repository the command failed for.
+    pub fn failed(name: &str) -> Self {
+        WorkspaceRow {
+            repository: name.to_string(),
+            vcs: "-".to_string(),
+            outcome: Some("failed".to_string()),
+            failed: true,
+            ..WorkspaceRow::default()
+        }
+    }
+}
+
+/// The branch as shown in the table, which is empty for a detached HEAD.
+pub fn display_branch_name(branch: &str) -> String {
+    if branch.is_empty() {
+        "(detached)".to_string()
+    } else {
+        branch.to_string()
+    }
+}
+
+/// Summarizes the changes of a repository (e.g. "2 staged, 1 untracked").
+fn describe_changes(changes: &RepositoryChanges) -> String {
+    let counts = [
+        (changes.staged.len(), "staged"),
+        (changes.unstaged.len(), "unstaged"),
+        (changes.untracked.len(), "untracked"),
+    ];
+    let description = counts
+        .iter()
+        .filter(|(count, _)| *count > 0)
+        .map(|(count, kind)| format!("{} {}", count, kind))
+        .collect::<Vec<String>>();
+
+    if description.is_empty() {
+        "clean".to_string()
+    } else {
+        description.join(", ")
+    }
+}
+
+/// Prints the rows as a table with a column per property, leaving out the
+/// columns which are empty in every row.
+pub fn display_workspace_table(rows: &[WorkspaceRow]) {
+    type Column = (
+        &'static str,
+        fn(&WorkspaceRow) -> Option<&str>,
+        fn(&WorkspaceRow, String) -> ColoredString,
+    );
+
+    let columns: [Column; 6] = [
+        (
+            "REPOSITORY",
+            |row| Some(&row.repository),
+            |_, cell| cell.bold(),
+        ),
+        ("VCS", |row| Some(&row.vcs), |_, cell| cell.muted().italic()),
+        (
+            "BRANCH",
+            |row| row.branch.as_deref(),
+            |_, cell| cell.cyan().italic(),
+        ),
+        (
+            "CHANGES",
+            |row| row.changes.as_deref(),
+            |row, cell| match row.changes.as_deref() {
+                Some("clean") => cell.bright_green(),
+                _ => cell.yellow(),
+            },
+        ),
+        (
+            "AHEAD/BEHIND",
+            |row| row.ahead_behind.as_deref(),
+            |row, cell| match row.ahead_behind.as_deref() {
+                Some(ahead_behind) if ahead_behind.contains("behind") => {
+                    cell.bright_red()
+                }
+                Some(ahead_behind) if ahead_behind.contains("ahead") => {
+                    cell.yellow()
+                }
+                _ => cell.muted(),
+            },
+        ),
+        (
+            "RESULT",
+            |row| row.outcome.as_deref(),
+            |row, cell| {
+                if row.failed {
+                    cell.bright_red().bold()
+                } else {
+                    cell.bright_green()
+                }
+            },
+        ),
+    ];
+
+    let mut columns = columns
+        .iter()
+        .filter(|(_, value, _)| rows.iter().any(|row| value(row).is_some()))
+        .map(|column| {
+            let width = rows
+                .iter()
+                .map(|row| column.1(row).unwrap_or("-").chars().count())
+                .chain([column.0.len()])
+                .max()
+                .unwrap_or_default();
+            (column, width)
+        })
+        .collect::<Vec<_>>();
+
+    // Nothing follows the last column, so it isn't padded
+    if let Some((_, width)) = columns.last_mut() {
+        *width = 0;
+    }
+
+    let header = columns
+        .iter()
+        .map(|((title, ..), width)| {
+            format!("{:width$}", title, width = width)
+                .muted()
+                .to_string()
+        })
+        .collect::<Vec<String>>();
+    println!("  {}", header.join("  ").trim_end());
+
+    for row in rows {
+        let cells = columns
+            .iter()
+            .map(|((_, value, style), width)| {
+                let cell =
+                    format!("{:width$}", value(row).unwrap_or("-"), width = width);
+                style(row, cell).to_string()
+            })
+            .collect::<Vec<String>>();
+        println!("  {}", cells.join("  "));
+    }
+    println!();
+}
@@ -0,0 +1,246 @@
+use std::path::Path;
+
+use colored::Colorize;
+
+use crate::{
+    cli::cli::{Workspace, WorkspaceCommands},
+    commands::workspace::{
+        executors::{pull_repository, push_repository, switch_repository},
+        helpers::{
+            default_jobs, display_branch_name, display_workspace_table,
+            for_each_repository, get_repository_summary, get_workspace_repositories,
+            vcs_name, WorkspaceRepository, WorkspaceRow, DEFAULT_MANIFEST,
+        },
+    },
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::{ScudError, ScudResult},
+    logging::helpers::backtick,
+};
+
+/// Executes the workspace command along with its subcommands
+///
+/// Arguments:
+///
+/// * `workspace_options`: Struct that contains the subcommand and options that
+///   were passed to the command via user input (e.g. pull, --manifest).
+/// * `working_dir`: The directory to operate on, which is the current one
+///   unless given with --directory.
+pub fn process_workspace_commands(
+    workspace_options: Workspace,
+    working_dir: &Path,
+) -> ScudResult {
+    let repositories = get_workspace_repositories(
+        workspace_options.manifest.as_deref(),
+        workspace_options.dir.as_deref(),
+        working_dir,
+    )?;
+
+    if repositories.is_empty() {
+        return Err(ScudError::failed(
+            "No repositories found in the workspace",
+            format!(
+                "Neither does {} list any repositories nor are there any under {}.",
+                DEFAULT_MANIFEST,
+                working_dir.display()
+            ),
+        )
+        .with_hint(
+            format!(
+                "{}",
+                "List the repositories in a manifest, one path per line, or \
+                 point at the directory they're in with"
+                    .yellow()
+            ),
+            "scud workspace --dir <DIR> state",
+        ));
+    }
+
+    let jobs = workspace_options.jobs.unwrap_or_else(default_jobs);
+
+    match workspace_options.command {
+        WorkspaceCommands::State => execute_workspace_state(&repositories, jobs),
+        WorkspaceCommands::Pull => {
+            execute_workspace_update(&repositories, jobs, "pull", &pull_repository)
+        }
+        WorkspaceCommands::Push => {
+            execute_workspace_update(&repositories, jobs, "push", &push_repository)
+        }
+        WorkspaceCommands::Branch(branch_options) => match branch_options.name {
+            Some(branch) => execute_workspace_update(
+                &repositories,
+                jobs,
+                &format!("check out {} in", branch),
+                &|path| switch_repository(path, &branch),
+            ),
+            None => execute_workspace_branch(&repositories, jobs),
+        },
+    }
+}
+
+/// Lists the branch, changes and commits ahead of or behind the upstream of
+/// every repository.
+fn execute_workspace_state(
+    repositories: &[WorkspaceRepository],
+    jobs: usize,
+) -> ScudResult {
+    let results = for_each_repository(repositories, jobs, |repository| {
+        get_repository_summary(&repository.path)
+    });
+
+    display_workspace_header(repositories.len());
+
+    let rows = repositories
+        .iter()
+        .zip(&results)
+        .map(|(repository, result)| match result {
+            Ok(summary) => WorkspaceRow::from_summary(&repository.name, summary),
+            Err(_) => WorkspaceRow::failed(&repository.name),
+        })
+        .collect::<Vec<WorkspaceRow>>();
+    display_workspace_table(&rows);
+
+    report_failures(repositories, &results, "read the state of")
+}
+
+/// Lists the branch every repository is on.
+fn execute_workspace_branch(
+    repositories: &[WorkspaceRepository],
+    jobs: usize,
+) -> ScudResult {
+    let results = for_each_repository(repositories, jobs, |repository| {
+        get_repository_summary(&repository.path)
+    });
+
+    display_workspace_header(repositories.len());
+
+    let rows = repositories
+        .iter()
+        .zip(&results)
+        .map(|(repository, result)| match result {
+            Ok(summary) => WorkspaceRow {
+                repository: repository.name.clone(),
+                vcs: vcs_name(summary.vcs).to_string(),
+                branch: Some(display_branch_name(&summary.branch)),
+                ..WorkspaceRow::default()
+            },
+            Err(_) => WorkspaceRow::failed(&repository.name),
+        })
+        .collect::<Vec<WorkspaceRow>>();
+    display_workspace_table(&rows);
+
+    report_failures(repositories, &results, "read the branch of")
+}
+
+/// Runs an operation which changes every repository (e.g. pulling), and then
+/// lists the state the repositories were left in along with the outcome.
+///
+/// Arguments:
+///
+/// * `repositories`: The repositories of the workspace.
+/// * `jobs`: How many repositories to process at the same time.
+/// * `action`: What the operation does, as reported for failures (e.g. pull).
+/// * `operation`: The operation, returning what happened to the repository.
+fn execute_workspace_update(
+    repositories: &[WorkspaceRepository],
+    jobs: usize,
+    action: &str,
+    operation: &(dyn Fn(&Path) -> ScudResult<String> + Sync),
+) -> ScudResult {
+    let results = for_each_repository(repositories, jobs, |repository| {
+        let outcome = operation(&repository.path)?;
+        let summary = get_repository_summary(&repository.path)?;
+        Ok((outcome, summary))
+    });
+
+    display_workspace_header(repositories.len());
+
+    let rows = repositories
+        .iter()
+        .zip(&results)
+        .map(|(repository, result)| match result {
+            Ok((outcome, summary)) => WorkspaceRow {
+                outcome: Some(outcome.clone()),
+                ..WorkspaceRow::from_summary(&repository.name, summary)
+            },
+            Err(_) => WorkspaceRow::failed(&repository.name),
+        })
+        .collect::<Vec<WorkspaceRow>>();
+    display_workspace_table(&rows);
+
+    report_failures(repositories, &results, action)
+}
+
+fn display_workspace_header(repository_count: usize) {
+    println!(
+        "\n{} {}\n",
+        " Workspace ".black().italic().on_bright_yellow(),
+        format!(
+            "{} {}",
+            repository_count,
+            if repository_count == 1 {
+                "repository"
+            } else {
+                "repositories"
+            }
+        )
+        .yellow()
+        .italic(),
+    );
+}
+
+/// Reports why the command failed for each repository it failed for, after
+/// the others were processed.
+///
+/// Returns:
+///
+/// An error when the command failed for any repository, so scud exits with
+/// a failure.
+fn report_failures<T>(
+    repositories: &[WorkspaceRepository],
+    results: &[ScudResult<T>],
+    action: &str,
+) -> ScudResult {
+    let mut failures = 0;
+
+    for (repository, result) in repositories.iter().zip(results) {
+        if let Err(error) = result {
+            failures += 1;
+            log_diagnostic(DiagnosticKind::Error {
+                subject: &format!(
+                    "Failed to {} {}",
+                    action,
+                    repository.name.bright_cyan().italic()
+                ),
+                body: &match error {
+                    ScudError::VcsNotFound => format!(
+                        "{} isn't in a repository of a supported VCS",
+                        repository.path.display()
+                    ),
+                    error => error.to_string(),
+                },
+            });
+        }
+    }
+
+    if failures == 0 {
+        return Ok(());
+    }
+
+    log_diagnostic(DiagnosticKind::Tip {
+        body: &format!(
+            "{} {}{}{} {}",
+            format!(
+                "{} of {} repositories failed,",
+                failures,
+                repositories.len()
+            )
+            .yellow(),
+            backtick(),
+            "scud -C <repository> state".green().italic(),
+            backtick(),
+            "shows the state of one of them in full".yellow()
+        ),
+    });
+
+    Err(ScudError::Reported { exit_code: 1 })
+}
@@ -0,0 +1,562 @@
+use std::{
+    fmt,
+    io::{self, IsTerminal, Write},
+    process::ExitStatus,
+};
+
+use colored::Colorize;
+
+use crate::{
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    logging::helpers::backtick,
+};
+
+/// Exit code of a process interrupted by the user (128 + SIGINT).
+pub const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Exit code used by shells for commands which can't be found.
+const COMMAND_NOT_FOUND_EXIT_CODE: i32 = 127;
+
+/// Exit code for input which couldn't be parsed (EX_DATAERR in sysexits.h).
+const PARSE_ERROR_EXIT_CODE: i32 = 65;
+
+/// Exit code for failed file operations (EX_IOERR in sysexits.h).
+const IO_ERROR_EXIT_CODE: i32 = 74;
+
+/// Exit code for invocations outside of a repository, as used by git.
+const VCS_NOT_FOUND_EXIT_CODE: i32 = 128;
+
+/// The ways a scud command can fail. Commands return these instead of
+/// exiting, so `main` can report the failure once with a fitting exit code.
+#[derive(Debug)]
+pub enum ScudError {
+    /// Not inside a repository of a supported version control system.
+    VcsNotFound,
+    /// An external program isn't installed or not found in PATH.
+    CommandNotFound { program: String },
+    /// An external command exited unsuccessfully.
+    ///
+    /// Properties:
+    ///
+    /// * `cmd`: The command line which was run (e.g. git commit -m ...).
+    /// * `status`: The exit code of the command, if it wasn't killed by a
+    ///   signal.
+    /// * `stderr`: What the command reported, its stderr or its stdout if
+    ///   nothing was written to stderr.
+    CommandFailed {
+        cmd: String,
+        status: Option<i32>,
+        stderr: String,
+    },
+    /// The user interrupted a prompt (e.g. with Ctrl-C).
+    Interrupted,
+    /// Output of the underlying VCS couldn't be understood.
+    ///
+    /// Properties:
+    ///
+    /// * `subject`: What was being parsed (e.g. the branch status).
+    /// * `input`: The text which couldn't be parsed.
+    ParseError { subject: String, input: String },
+    /// Reading or writing a file failed.
+    Io { context: String, source: io::Error },
+    /// A command specific failure, along with a hint on how to recover.
+    ///
+    /// Properties:
+    ///
+    /// * `subject`: What failed.
+    /// * `body`: Why it failed.
+    /// * `hint`: The description and command of a way forward (optional).
+    Failed {
+        subject: String,
+        body: String,
+        hint: Option<(String, String)>,
+    },
+    /// The failure was already reported in detail (e.g. failed healthchecks),
+    /// only the exit code is left to be set.
+    Reported { exit_code: i32 },
+}
+
+/// Result of running a scud command.
+pub type ScudResult<T = ()> = Result<T, ScudError>;
+
+impl ScudError {
+    pub fn failed(subject: impl Into<String>, body: impl Into<String>) -> Self {
+        ScudError::Failed {
+            subject: subject.into(),
+            body: body.into(),
+            hint: None,
+        }
+    }
+
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        if source.kind() == io::ErrorKind::Interrupted {
+            return ScudError::Interrupted;
+        }
+
+        ScudError::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    pub fn parse(subject: impl Into<String>, input: impl Into<String>) -> Self {
+        ScudError::ParseError {
+            subject: subject.into(),
+            input: input.into(),
+        }
+    }
+
+    /// The error for a command which couldn't be spawned, telling a missing
+    /// program apart from other failures.
+    ///
+    /// Arguments:
+    ///
+    /// * `program`: The program which was run (e.g. git).
+    /// * `error`: The error returned when spawning it.
+    pub fn spawn(program: &str, error: io::Error) -> Self {
+        if error.kind() == io::ErrorKind::NotFound {
+            ScudError::CommandNotFound {
+                program: program.to_string(),
+            }
+        } else {
+            ScudError::io(format!("Failed to run {}", program), error)
+        }
+    }
+
+    /// The error for a command which ran with inherited stdio (so its output
+    /// was already shown) but exited unsuccessfully.
+    pub fn from_status(cmd: impl Into<String>, status: ExitStatus) -> Self {
+        ScudError::CommandFailed {
+            cmd: cmd.into(),
+            status: status.code(),
+            stderr: String::new(),
+        }
+    }
+
+    /// Attaches a hint to a command specific failure. Other errors already
+    /// carry all they need and are left as is.
+    pub fn with_hint(
+        self,
+        body: impl Into<String>,
+        command: impl Into<String>,
+    ) -> Self {
+        match self {
+            ScudError::Failed {
+                subject,
+                body: failure,
+                ..
+            } => ScudError::Failed {
+                subject,
+                body: failure,
+                hint: Some((body.into(), command.into())),
+            },
+            error => error,
+        }
+    }
+
+    /// The exit code scud exits with:
+    ///
+    /// * 1 for failures of scud itself (or the exit code of the failed command).
+    /// * 65 and 74 for parse and file errors (as in sysexits.h).
+    /// * 127 for missing programs, like shells do.
+    /// * 128 outside of a repository, like git does.
+    /// * 130 when interrupted, like any process killed by Ctrl-C.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            ScudError::VcsNotFound => VCS_NOT_FOUND_EXIT_CODE,
+            ScudError::CommandNotFound { .. } => COMMAND_NOT_FOUND_EXIT_CODE,
+            ScudError::CommandFailed { status, .. } => match status {
+                Some(code) if *code != 0 => *code,
+                _ => 1,
+            },
+            ScudError::Interrupted => INTERRUPTED_EXIT_CODE,
+            ScudError::ParseError { .. } => PARSE_ERROR_EXIT_CODE,
+            ScudError::Io { .. } => IO_ERROR_EXIT_CODE,
+            ScudError::Failed { .. } => 1,
+            ScudError::Reported { exit_code } => *exit_code,
+        }
+    }
+
+    /// Reports the error to the user as a single diagnostic. Interruptions are
+    /// quiet, they only restore the cursor hidden by an interrupted prompt.
+    pub fn log(&self) {
+        match self {
+            ScudError::VcsNotFound => {
+                log_diagnostic(DiagnosticKind::Error {
+                    subject: &format!(
+                        "{} {}",
+                        "Could not detect a valid version control system",
+                        "(Supported VCSs are: Git, Mercurial, Breezy, Subversion, \
+                         Jujutsu and Fossil)"
+                            .bright_cyan()
+                            .italic()
+                    ),
+                    body: "Scud's declarative, high-level operations on top of the \
+                           underlying VCS are intended to be used with a supported \
+                           version control system.",
+                });
+                log_diagnostic(DiagnosticKind::Tip {
+                    body: &format!(
+                        "{} {}{}{} {}",
+                        "To get started using scud's declarative, high-level \
+                         operations on top of the underlying VCS, try using"
+                            .yellow(),
+                        backtick(),
+                        "scud init".green().italic(),
+                        backtick(),
+                        "to initialize a new project with your desired VCS".yellow()
+                    ),
+                });
+            }
+            ScudError::CommandNotFound { program } => {
+                log_diagnostic(DiagnosticKind::Error {
+                    subject: &format!(
+                        "{} is not installed or not found in PATH",
+                        program
+                    ),
+                    body: "scud runs the underlying tools under the hood, so they \
+                           need to be installed.",
+                });
+                log_diagnostic(DiagnosticKind::Hint {
+                    body: &format!(
+                        "{}",
+                        "Check which tools are missing and how to install them with"
+                            .yellow()
+                    ),
+                    command: "scud setup",
+                });
+            }
+            ScudError::CommandFailed {
+                cmd,
+                status,
+                stderr,
+            } => {
+                let outcome = match status {
+                    Some(code) => format!("exited with code {}", code),
+                    None => "was terminated by a signal".to_string(),
+                };
+                // e.g. git commit, without the (possibly long) arguments
+                let command =
+                    cmd.split_whitespace().take(2).collect::<Vec<_>>().join(" ");
+                let program = cmd.split_whitespace().next().unwrap_or(cmd);
+
+                let body = if stderr.is_empty() {
+                    format!("{} {}.", cmd.cyan(), outcome)
+                } else {
+                    format!(
+                        "{} {}, {} said:\n\n{}",
+                        cmd.cyan(),
+                        outcome,
+                        program,
+                        quote(stderr)
+                    )
+                };
+
+                log_diagnostic(DiagnosticKind::Error {
+                    subject: &format!("{} {}", command, outcome),
+                    body: &body,
+                });
+
+                if let Some((body, command)) = hint_for_failure(cmd, stderr) {
+                    log_diagnostic(DiagnosticKind::Hint {
+                        body: &format!("{}", body.yellow()),
+                        command,
+                    });
+                }
+            }
+            ScudError::Interrupted => {
+                if io::stderr().is_terminal() {
+                    let _ = io::stderr().write_all(b"\x1b[?25h");
+                }
+            }
+            ScudError::ParseError { subject, .. } => {
+                log_diagnostic(DiagnosticKind::Error {
+                    subject: &format!("Failed to parse {}", subject),
+                    body: &self.to_string(),
+                });
+            }
+            ScudError::Io { context, source } => {
+                log_diagnostic(DiagnosticKind::Error {
+                    subject: context,
+                    body: &source.to_string(),
+                });
+            }
+            ScudError::Failed {
+                subject,
+                body,
+                hint,
+            } => {
+                log_diagnostic(DiagnosticKind::Error { subject, body });
+
+                if let Some((body, command)) = hint {
+                    log_diagnostic(DiagnosticKind::Hint { body, command });
+                }
+            }
+            ScudError::Reported { .. } => {}
+        }
+    }
+}
+
+/// Indents the message of a failed command, so it stands out as quoted.
+fn quote(message: &str) -> String {
+    message
+        .lines()
+        .map(|line| {
+            format!("    {} {}", "|".bright_black(), line)
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// A way forward for failures of git which are common enough to be
+/// recognized, by the subcommand which failed and what it reported.
+///
+/// Arguments:
+///
+/// * `cmd`: The command line which failed (e.g. git commit -m ...).
+/// * `message`: What the command reported.
+///
+/// Returns:
+///
+/// The description and command of the hint, if the failure is recognized.
+fn hint_for_failure(
+    cmd: &str,
+    message: &str,
+) -> Option<(&'static str, &'static str)> {
+    let subcommand = git_subcommand(cmd)?;
+
+    let hints: [(&[&str], &str, &str, &str); 6] = [
+        (
+            &["commit"],
+            "nothing to commit",
+            "Stage your changes first with",
+            "scud stage",
+        ),
+        (
+            &["commit"],
+            "no changes added to commit",
+            "Stage your changes first with",
+            "scud stage",
+        ),
+        (
+            // Any command creating commits
+            &[],
+            "Please tell me who you are",
+            "Configure your commit identity with",
+            "scud setup identity",
+        ),
+        (
+            &["push"],
+            "has no upstream branch",
+            "Publish the branch and track it with",
+            "git push --set-upstream origin HEAD",
+        ),
+        (
+            &["push"],
+            "Updates were rejected",
+            "Integrate the remote changes first with",
+            "git pull --rebase",
+        ),
+        (
+            &[
+                "merge",
+                "pull",
+                "rebase",
+                "cherry-pick",
+                "revert",
+                "am",
+                "stash",
+                "switch",
+                "checkout",
+            ],
+            "CONFLICT (",
+            "Resolve the conflicts interactively with",
+            "scud conflicts",
+        ),
+    ];
+
+    let hint = hints
+        .into_iter()
+        .find(|(subcommands, pattern, ..)| {
+            (subcommands.is_empty() || subcommands.contains(&subcommand))
+                && message.contains(pattern)
+        })
+        .map(|(_, _, body, command)| (body, command));
+
+    // Git doesn't say when one of its hooks rejected the changes, it only
+    // passes on what the hook printed
+    let ran_hooks = ["commit", "merge", "am"].contains(&subcommand)
+        && !cmd.split_whitespace().any(|arg| arg == "--no-verify");
+
+    hint.or_else(|| {
+        ran_hooks.then(|| {
+            (
+                "If one of the hooks git ran rejected the changes, inspect the \
+                 installed hooks with",
+                "scud hooks list",
+            )
+        })
+    })
+}
+
+/// The subcommand of a git command line (e.g. commit for
+/// git -c core.editor=true commit -m ...), or None for other programs.
+fn git_subcommand(cmd: &str) -> Option<&str> {
+    let mut args = cmd.split_whitespace();
+
+    if args.next() != Some("git") {
+        return None;
+    }
+
+    while let Some(arg) = args.next() {
+        match arg {
+            // Options of git itself which take a value
+            "-c" | "-C" => {
+                args.next();
+            }
+            arg if arg.starts_with('-') => {}
+            subcommand => return Some(subcommand),
+        }
+    }
+
+    None
+}
+
+impl fmt::Display for ScudError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ScudError::VcsNotFound => {
+                write!(f, "Could not detect a valid version control system")
+            }
+            ScudError::CommandNotFound { program } => {
+                write!(f, "{} is not installed or not found in PATH", program)
+            }
+            ScudError::CommandFailed {
+                cmd,
+                status,
+                stderr,
+            } => {
+                match status {
+                    Some(code) => write!(f, "{} exited with code {}", cmd, code)?,
+                    None => write!(f, "{} was terminated by a signal", cmd)?,
+                }
+
+                if stderr.is_empty() {
+                    Ok(())
+                } else {
+                    write!(f, ":\n\n{}", stderr)
+                }
+            }
+            ScudError::Interrupted => write!(f, "Interrupted"),
+            ScudError::ParseError { subject, input } => {
+                write!(f, "Unexpected {}: {:?}", subject, input)
+            }
+            ScudError::Io { context, source } => {
+                write!(f, "{}: {}", context, source)
+            }
+            ScudError::Failed { subject, body, .. } => {
+                write!(f, "{}: {}", subject, body)
+            }
+            ScudError::Reported { exit_code } => {
+                write!(f, "Failed with exit code {}", exit_code)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ScudError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ScudError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Prompts (dialoguer) fail with an interrupted error when the user hits
+/// Ctrl-C, everything else is a failure to read from or write to the terminal.
+impl From<io::Error> for ScudError {
+    fn from(error: io::Error) -> Self {
+        ScudError::io("Failed to interact with the terminal", error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hint_command(cmd: &str, message: &str) -> Option<&'static str> {
+        hint_for_failure(cmd, message).map(|(_, command)| command)
+    }
+
+    #[test]
+    fn subcommands_are_found_after_git_options() {
+        assert_eq!(git_subcommand("git commit -m x"), Some("commit"));
+        assert_eq!(
+            git_subcommand("git -c core.editor=true -C dir --no-pager rebase"),
+            Some("rebase")
+        );
+        assert_eq!(git_subcommand("git --version"), None);
+        assert_eq!(git_subcommand("hg commit"), None);
+    }
+
+    #[test]
+    fn failures_are_recognized_for_their_subcommand() {
+        assert_eq!(
+            hint_command("git commit -m x", "nothing to commit, working tree clean"),
+            Some("scud stage")
+        );
+        assert_eq!(
+            hint_command("git push", "hint: Updates were rejected because"),
+            Some("git pull --rebase")
+        );
+        assert_eq!(
+            hint_command(
+                "git merge main",
+                "CONFLICT (content): Merge conflict in a"
+            ),
+            Some("scud conflicts")
+        );
+        assert_eq!(
+            hint_command("git merge main", "*** Please tell me who you are."),
+            Some("scud setup identity")
+        );
+    }
+
+    #[test]
+    fn messages_of_other_subcommands_are_not_recognized() {
+        // e.g. a file whose name mentions a conflict
+        assert_eq!(
+            hint_command("git add CONFLICT (notes).txt", "fatal: pathspec"),
+            None
+        );
+        assert_eq!(hint_command("git push", "nothing to commit"), None);
+        assert_eq!(hint_command("hg commit", "nothing to commit"), None);
+    }
+
+    #[test]
+    fn hooks_are_only_suggested_when_git_ran_them() {
+        assert_eq!(
+            hint_command("git commit -m x", "lint failed"),
+            Some("scud hooks list")
+        );
+        assert_eq!(
+            hint_command("git commit --no-verify -m x", "lint failed"),
+            None
+        );
+        // Mentions of hooks elsewhere don't matter
+        assert_eq!(
+            hint_command("git push", "remote: webhook delivery failed"),
+            None
+        );
+        assert_eq!(
+            hint_command(
+                "git add .git/hooks/pre-commit",
+                "fatal: outside repository"
+            ),
+            None
+        );
+    }
+}
@@ -1,11 +1,17 @@
 pub mod cli;
 pub mod commands;
-pub mod helpers;
+pub mod error;
 pub mod logging;
+#[cfg(feature = "gix")]
+pub mod native;
 pub mod process_commands;
+pub mod repository;
+pub mod runner;
 
 pub use cli::*;
 pub use commands::*;
-pub use helpers::*;
+pub use error::*;
 pub use logging::*;
 pub use process_commands::*;
+pub use repository::*;
+pub use runner::*;
@@ -1,3 +1,4 @@
 pub mod diagnostics;
 pub mod general;
 pub mod helpers;
+pub mod theme;
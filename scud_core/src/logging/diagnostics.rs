@@ -1,11 +1,24 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use colored::{Color, Colorize};
 
+use crate::logging::theme::Themed;
 use crate::logging::helpers::{
-    black_colon, black_comma, black_italic_close_paren, black_italic_implies,
-    black_italic_open_paren, black_period, bright_yellow_backtick,
-    bright_yellow_dots, yellow_backtick,
+    backtick, close_paren, colon, comma, dots, implies, open_paren, period,
 };
 
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Leaves out the diagnostics which are only informational (banners, notes
+/// and tips) along with the "Done in" line (`--quiet`).
+pub fn set_quiet(quiet: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+}
+
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
 #[derive(Debug)]
 pub enum DiagnosticKind<'a> {
     ScudCommandInfo {
@@ -33,11 +46,17 @@ pub enum DiagnosticKind<'a> {
     Tip {
         body: &'a str,
     },
+    Warning {
+        subject: &'a str,
+        body: &'a str,
+    },
     VCSInfo {
         command_name: &'a str,
         git_command: &'a str,
         mercurial_command: &'a str,
         breezy_command: &'a str,
+        // (VCS name, command) for the VCSs only some commands support
+        other_commands: &'a [(&'a str, &'a str)],
     },
     GeneralCommandInfo {
         command_name: &'a str, // e.g. info system => `scud info system`
@@ -59,6 +78,17 @@ pub struct ExternalCommandInfo<'a> {
 // pub struct Command
 
 pub fn log_diagnostic(diagnostic_kind: DiagnosticKind) {
+    if is_quiet()
+        && matches!(
+            diagnostic_kind,
+            DiagnosticKind::ScudCommandInfo { .. }
+                | DiagnosticKind::Note { .. }
+                | DiagnosticKind::Tip { .. }
+        )
+    {
+        return;
+    }
+
     match diagnostic_kind {
         DiagnosticKind::ScudCommandInfo {
             command,
@@ -89,26 +119,26 @@ pub fn log_diagnostic(diagnostic_kind: DiagnosticKind) {
                 "  No ".bright_yellow().italic(),
                 command.cyan().italic(),
                 " was executed".bright_yellow().italic(),
-                black_period(),
+                period(),
                 " To ".bright_yellow().italic(),
                 "properly ".bright_yellow().italic(),
                 command.bright_yellow().italic(),
-                black_comma(),
+                comma(),
                 " rerun".yellow().italic(),
                 " the ".bright_yellow().italic(),
-                yellow_backtick(),
+                backtick(),
                 "scud ".green(),
                 command.green(),
-                yellow_backtick(),
+                backtick(),
                 " command ".bright_yellow().italic(),
                 "without".yellow().italic(),
                 " the ".bright_yellow().italic(),
-                "\"".black().italic(),
+                "\"".muted().italic(),
                 "--".bright_yellow().italic(),
                 "dry-run".yellow().italic(),
-                "\"".black().italic(),
+                "\"".muted().italic(),
                 " flag".bright_yellow().italic(),
-                black_period(),
+                period(),
             );
         }
         DiagnosticKind::GeneralCommandInfo {
@@ -124,29 +154,29 @@ pub fn log_diagnostic(diagnostic_kind: DiagnosticKind) {
                 "{}{}{}{}{}{}{}{}{}{}\n",
                 " INFO ".black().on_yellow(),
                 " Under the hood".bright_yellow().italic(),
-                black_comma(),
+                comma(),
                 " the following commands are called when ".yellow().italic(),
-                bright_yellow_backtick(),
+                backtick(),
                 "scud ".green().italic(),
                 command_name.green().italic(),
-                bright_yellow_backtick(),
+                backtick(),
                 " is invoked".yellow().italic(),
-                black_colon(),
+                colon(),
             );
             for general_command in commands {
                 println!(
                     "{}{}{}{} {}{}{} {} {}{}{}\n",
                     "  *- ".bright_red().italic(),
-                    bright_yellow_backtick(),
+                    backtick(),
                     general_command.command_name.cyan().italic(),
-                    bright_yellow_backtick(),
-                    black_italic_open_paren(),
-                    general_command.command_link.black().italic(),
-                    black_italic_close_paren(),
-                    bright_yellow_dots(),
+                    backtick(),
+                    open_paren(),
+                    general_command.command_link.muted().italic(),
+                    close_paren(),
+                    dots(),
                     "to ".yellow().italic(),
                     general_command.command_description.yellow().italic(),
-                    black_period(),
+                    period(),
                 );
             }
             println!(
@@ -155,15 +185,15 @@ pub fn log_diagnostic(diagnostic_kind: DiagnosticKind) {
                 " If any of these commands are ".bright_yellow().italic(),
                 "not installed ".red().italic(),
                 "on your system".bright_yellow().italic(),
-                black_comma(),
+                comma(),
                 " scud ".cyan().italic(),
                 "will encounter a ".bright_yellow().italic(),
                 "runtime error".red().italic(),
                 " when ".bright_yellow().italic(),
-                yellow_backtick(),
+                backtick(),
                 "scud ".green().italic(),
                 command_name.green().italic(),
-                yellow_backtick(),
+                backtick(),
                 " is invoked and will provide you with some "
                     .bright_yellow()
                     .italic(),
@@ -173,7 +203,7 @@ pub fn log_diagnostic(diagnostic_kind: DiagnosticKind) {
                     .bright_yellow()
                     .italic(),
                 "scud".cyan().italic(),
-                black_period(),
+                period(),
             );
         }
         DiagnosticKind::Error { subject, body } => {
@@ -191,12 +221,12 @@ pub fn log_diagnostic(diagnostic_kind: DiagnosticKind) {
             println!(
                 "{}{} {} {}{}{}{}\n",
                 "Hint".green().italic(),
-                black_colon(),
+                colon(),
                 body.to_string(),
-                bright_yellow_backtick(),
+                backtick(),
                 command.to_string().green().italic(),
-                bright_yellow_backtick(),
-                black_period()
+                backtick(),
+                period()
             );
         }
         DiagnosticKind::Info => {
@@ -209,9 +239,17 @@ pub fn log_diagnostic(diagnostic_kind: DiagnosticKind) {
             println!(
                 "{}{} {}{}\n",
                 "Tip".green().italic(),
-                black_colon(),
+                colon(),
                 body.to_string(),
-                black_period()
+                period()
+            );
+        }
+        DiagnosticKind::Warning { subject, body } => {
+            println!(
+                "\n{} {}\n\n{}\n",
+                " WARNING ".black().on_bright_yellow(),
+                subject.to_string().bright_yellow(),
+                body.to_string().italic(),
             );
         }
         DiagnosticKind::VCSInfo {
@@ -219,6 +257,7 @@ pub fn log_diagnostic(diagnostic_kind: DiagnosticKind) {
             git_command,
             mercurial_command,
             breezy_command,
+            other_commands,
         } => {
             println!(
                 "{}{}{}{}{}{}{}{}{}{}{}\n\n{} {} {}\n\n{} {} {}\n\n{} {} {}\n",
@@ -228,21 +267,42 @@ pub fn log_diagnostic(diagnostic_kind: DiagnosticKind) {
                 " issued for ".yellow(),
                 "supported version control systems".bright_yellow().italic(),
                 " when using ".yellow(),
-                bright_yellow_backtick(),
+                backtick(),
                 "scud ".green().italic(),
                 command_name.to_string().green().italic(),
-                bright_yellow_backtick(),
-                black_period(),
+                backtick(),
+                period(),
                 " Git ".bright_black().italic().on_bright_yellow(),
-                black_italic_implies(),
+                implies(),
                 git_command.to_string().bright_cyan().italic(),
                 " Mercurial ".bright_black().italic().on_bright_yellow(),
-                black_italic_implies(),
+                implies(),
                 mercurial_command.to_string().bright_cyan().italic(),
                 " Breezy ".bright_black().italic().on_bright_yellow(),
-                black_italic_implies(),
+                implies(),
                 breezy_command.to_string().bright_cyan().italic(),
             );
+            for (vcs_name, command) in other_commands.iter() {
+                println!(
+                    "{} {} {}\n",
+                    format!(" {} ", vcs_name)
+                        .bright_black()
+                        .italic()
+                        .on_bright_yellow(),
+                    implies(),
+                    command.to_string().bright_cyan().italic(),
+                );
+            }
+            log_diagnostic(DiagnosticKind::Tip {
+                body: &format!(
+                    "{} {}{}{} {}",
+                    "To see the exact commands as they run, rerun".yellow(),
+                    backtick(),
+                    format!("scud {} -v", command_name).green().italic(),
+                    backtick(),
+                    "without the --info flag".yellow(),
+                ),
+            });
         }
         DiagnosticKind::WorkInProgress { feature } => {
             println!(
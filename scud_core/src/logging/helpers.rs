@@ -1,47 +1,46 @@
 use colored::{ColoredString, Colorize};
 
+use crate::logging::theme::theme;
+
 ////////////////////////////////////////
 // Helpers that effectively provide   //
 // syntax highlighting primitives for //
-// the logging system.                //
+// the logging system, coloured by    //
+// the role they play in the theme.   //
 ////////////////////////////////////////
 
-pub fn bright_yellow_backtick() -> ColoredString {
-    "`".bright_yellow()
-}
-
-pub fn yellow_backtick() -> ColoredString {
-    "`".yellow()
+pub fn backtick() -> ColoredString {
+    "`".color(theme().code)
 }
 
-pub fn black_italic_quote() -> ColoredString {
-    "\"".black().italic()
+pub fn quote() -> ColoredString {
+    "\"".color(theme().punctuation).italic()
 }
 
-pub fn black_period() -> ColoredString {
-    ".".black()
+pub fn period() -> ColoredString {
+    ".".color(theme().punctuation)
 }
 
-pub fn black_comma() -> ColoredString {
-    ",".black()
+pub fn comma() -> ColoredString {
+    ",".color(theme().punctuation)
 }
 
-pub fn black_colon() -> ColoredString {
-    ":".black()
+pub fn colon() -> ColoredString {
+    ":".color(theme().punctuation)
 }
 
-pub fn black_italic_open_paren() -> ColoredString {
-    "(".black().italic()
+pub fn open_paren() -> ColoredString {
+    "(".color(theme().punctuation).italic()
 }
 
-pub fn black_italic_close_paren() -> ColoredString {
-    ")".black().italic()
+pub fn close_paren() -> ColoredString {
+    ")".color(theme().punctuation).italic()
 }
 
-pub fn black_italic_implies() -> ColoredString {
-    "=>".black().italic()
+pub fn implies() -> ColoredString {
+    "=>".color(theme().punctuation).italic()
 }
 
-pub fn bright_yellow_dots() -> ColoredString {
-    "...".bright_yellow().italic()
+pub fn dots() -> ColoredString {
+    "...".color(theme().ellipsis).italic()
 }
@@ -0,0 +1,93 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+};
+
+use colored::{Color, ColoredString, Colorize};
+
+use crate::cli::cli::ColorChoice;
+
+/////////////////////////////////////////////
+// Colours of the roles text plays in      //
+// scud's output, so they're picked in one //
+// place instead of at every call site.    //
+/////////////////////////////////////////////
+
+/// The colours of scud's output.
+///
+/// Properties:
+///
+/// * `punctuation`: Periods, commas, colons and parentheses around the text.
+/// * `muted`: Secondary text (e.g. commit hashes or paths next to a name).
+/// * `code`: The backticks around commands (e.g. `scud stage`).
+/// * `ellipsis`: The dots trailing a heading (e.g. New files ...).
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    pub punctuation: Color,
+    pub muted: Color,
+    pub code: Color,
+    pub ellipsis: Color,
+}
+
+/// Bright black (grey) stays readable on both dark and light terminals, unlike
+/// black which disappears on dark ones.
+pub const DEFAULT_THEME: Theme = Theme {
+    punctuation: Color::BrightBlack,
+    muted: Color::BrightBlack,
+    code: Color::BrightYellow,
+    ellipsis: Color::BrightYellow,
+};
+
+pub fn theme() -> &'static Theme {
+    &DEFAULT_THEME
+}
+
+/// Styles text by the role it plays rather than by a hard-coded colour.
+pub trait Themed {
+    /// Secondary text, which should be visible but not stand out.
+    fn muted(self) -> ColoredString;
+}
+
+impl<T: Colorize> Themed for T {
+    fn muted(self) -> ColoredString {
+        self.color(theme().muted)
+    }
+}
+
+/////////////////////////////////////////////
+// Colour policy (--color, NO_COLOR, ...)  //
+/////////////////////////////////////////////
+
+/// Decides whether scud's output is coloured and applies it to everything
+/// scud prints, including the interactive prompts.
+///
+/// With `auto`, colours are turned off when `NO_COLOR` is set (see
+/// https://no-color.org), `TERM` is `dumb`, or stdout isn't a terminal, and
+/// forced on by `CLICOLOR_FORCE`.
+///
+/// Arguments:
+///
+/// * `choice`: The colour policy passed on the command line.
+pub fn apply_color_choice(choice: ColorChoice) {
+    let enabled = match choice {
+        ColorChoice::Always => true,
+        ColorChoice::Never => false,
+        ColorChoice::Auto => colors_enabled_by_env(),
+    };
+
+    colored::control::set_override(enabled);
+    console::set_colors_enabled(enabled);
+    console::set_colors_enabled_stderr(enabled);
+}
+
+fn colors_enabled_by_env() -> bool {
+    let value = |name: &str| env::var(name).unwrap_or_default();
+
+    if !matches!(value("CLICOLOR_FORCE").as_str(), "" | "0") {
+        return true;
+    }
+
+    value("NO_COLOR").is_empty()
+        && value("TERM") != "dumb"
+        && io::stdout().is_terminal()
+}
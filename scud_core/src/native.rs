@@ -0,0 +1,203 @@
+//! Read-only git operations (i.e. status, branches, ahead/behind and latest
+//! commits) backed by gitoxide, which reads the repository in-process rather
+//! than spawning git once per query. Only built with the gix feature.
+//!
+//! Every function returns None when gitoxide can't read the repository (e.g.
+//! a repository format it doesn't support yet), in which case the callers
+//! fall back to the git CLI.
+
+use std::path::Path;
+
+use gix::{
+    bstr::BStr,
+    diff::index::ChangeRef,
+    progress::Discard,
+    refs::{FullNameRef, TargetRef},
+    remote::Direction,
+    status::{index_worktree::iter::Summary, Item as StatusItem, UntrackedFiles},
+    Repository,
+};
+
+use crate::{
+    branch::executors::GitBranches,
+    state::helpers::{ChangeKind, FileChange, RepositoryChanges},
+};
+
+/// What `scud state` displays for a git repository.
+///
+/// Properties:
+///
+/// * `branch`: The branch HEAD points at, which is empty when it's detached.
+/// * `changes`: The staged, unstaged and untracked changes.
+/// * `ahead_behind`: How far the branch diverged from its upstream, in the
+/// same format as git status -sb (e.g. "ahead 1, behind 2").
+#[derive(Debug, Clone, Default)]
+pub struct GitState {
+    pub branch: String,
+    pub changes: RepositoryChanges,
+    pub ahead_behind: String,
+}
+
+/// Opens the repository containing the given directory, honouring the same
+/// environment variables as git (e.g. GIT_DIR).
+fn open_repository(working_dir: &Path) -> Option<Repository> {
+    gix::discover_with_environment_overrides(working_dir).ok()
+}
+
+/// Gets the branch, changes and ahead/behind summary of the git repository
+/// the given directory belongs to.
+pub fn get_state_git(working_dir: &Path) -> Option<GitState> {
+    let repository = open_repository(working_dir)?;
+    let head_name = repository.head_name().ok()?;
+
+    let ahead_behind = head_name
+        .as_ref()
+        .and_then(|name| get_ahead_behind(&repository, name.as_ref()))
+        .unwrap_or_default();
+
+    Some(GitState {
+        branch: head_name
+            .map(|name| name.shorten().to_string())
+            .unwrap_or_default(),
+        changes: get_changes(&repository)?,
+        ahead_behind,
+    })
+}
+
+/// Gets the changes between HEAD and the index (staged), the index and the
+/// working tree (unstaged) and the untracked files, like `git diff --cached`,
+/// `git diff` and `git ls-files --others --exclude-standard` respectively.
+fn get_changes(repository: &Repository) -> Option<RepositoryChanges> {
+    let status = repository
+        .status(Discard)
+        .ok()?
+        .untracked_files(UntrackedFiles::Files)
+        // git diff doesn't pair deleted files with untracked ones either
+        .index_worktree_rewrites(None)
+        .into_iter(None)
+        .ok()?;
+
+    let mut changes = RepositoryChanges::default();
+    for item in status {
+        match item.ok()? {
+            StatusItem::TreeIndex(change) => {
+                let kind = match change {
+                    ChangeRef::Addition { .. } => ChangeKind::Added,
+                    ChangeRef::Deletion { .. } => ChangeKind::Deleted,
+                    ChangeRef::Modification { .. } => ChangeKind::Modified,
+                    ChangeRef::Rewrite { .. } => ChangeKind::Renamed,
+                };
+                changes.staged.push(file_change(kind, change.location()));
+            }
+            StatusItem::IndexWorktree(item) => {
+                let kind = match item.summary() {
+                    Some(Summary::Added) => {
+                        changes.untracked.push(item.rela_path().to_string());
+                        continue;
+                    }
+                    Some(Summary::IntentToAdd) => ChangeKind::Added,
+                    Some(Summary::Removed) => ChangeKind::Deleted,
+                    Some(Summary::Renamed | Summary::Copied) => ChangeKind::Renamed,
+                    Some(
+                        Summary::Modified | Summary::TypeChange | Summary::Conflict,
+                    ) => ChangeKind::Modified,
+                    // e.g. a file whose stat changed but whose content didn't
+                    None => continue,
+                };
+                changes.unstaged.push(file_change(kind, item.rela_path()));
+            }
+        }
+    }
+
+    // The changes arrive in whichever order they're found in
+    changes.staged.sort_by(|a, b| a.path.cmp(&b.path));
+    changes.unstaged.sort_by(|a, b| a.path.cmp(&b.path));
+    changes.untracked.sort();
+
+    Some(changes)
+}
+
+fn file_change(kind: ChangeKind, path: &BStr) -> FileChange {
+    FileChange {
+        kind,
+        path: path.to_string(),
+    }
+}
+
+/// Counts the commits the given branch is ahead and behind of its upstream,
+/// or None when it doesn't track one (or the upstream is gone).
+fn get_ahead_behind(
+    repository: &Repository,
+    branch: &FullNameRef,
+) -> Option<String> {
+    let upstream = repository
+        .branch_remote_tracking_ref_name(branch, Direction::Fetch)?
+        .ok()?;
+    let upstream = repository
+        .find_reference(upstream.as_ref())
+        .ok()?
+        .peel_to_id()
+        .ok()?
+        .detach();
+    let head = repository.head_id().ok()?.detach();
+
+    // The commits reachable from one of them but not the other
+    let count_commits = |from, hidden| -> Option<usize> {
+        repository
+            .rev_walk([from])
+            .with_hidden([hidden])
+            .all()
+            .ok()?
+            .map(|commit| commit.ok())
+            .collect::<Option<Vec<_>>>()
+            .map(|commits| commits.len())
+    };
+    let ahead = count_commits(head, upstream)?;
+    let behind = count_commits(upstream, head)?;
+
+    Some(match (ahead, behind) {
+        (0, 0) => String::new(),
+        (ahead, 0) => format!("ahead {}", ahead),
+        (0, behind) => format!("behind {}", behind),
+        (ahead, behind) => format!("ahead {}, behind {}", ahead, behind),
+    })
+}
+
+/// Gets the local and remote-tracking branches of the git repository the
+/// given directory belongs to along with their latest commit, like
+/// `git branch -a` followed by `git log -1 --pretty=%H <branch>` for each of
+/// them.
+pub fn get_branches_git(working_dir: &Path) -> Option<GitBranches> {
+    let repository = open_repository(working_dir)?;
+    let references = repository.references().ok()?;
+
+    let mut branches = GitBranches {
+        active: repository
+            .head_name()
+            .ok()?
+            .map(|name| name.shorten().to_string())
+            .unwrap_or_default(),
+        ..GitBranches::default()
+    };
+
+    for reference in references.local_branches().ok()? {
+        let mut reference = reference.ok()?;
+        let name = reference.name().shorten().to_string();
+        let latest_commit = reference.peel_to_id().ok()?.to_string();
+        branches.local.push((name, latest_commit));
+    }
+
+    for reference in references.remote_branches().ok()? {
+        let mut reference = reference.ok()?;
+        let mut name = reference.name().shorten().to_string();
+        // e.g. origin/HEAD, which points at the default branch of the remote
+        if let TargetRef::Symbolic(target) = reference.target() {
+            name = format!("{} -> {}", name, target.shorten());
+        }
+        let latest_commit = reference.peel_to_id().ok()?.to_string();
+        let name = name.strip_prefix("origin/").unwrap_or(&name).to_string();
+        branches.remote.push((name, latest_commit));
+    }
+
+    Some(branches)
+}
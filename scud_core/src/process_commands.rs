@@ -1,17 +1,41 @@
+use std::path::Path;
+
 use crate::{
     branch::branch::process_branch_commands,
     cli::cli::{Cli, Commands},
     commands::{
-        commit::commit::commit_command, diff::diff::diff_command,
-        healthcheck::healthcheck::healthcheck_command, init::init::init_command,
-        push::push::push_command, stage::stage::stage_command,
-        state::state::state_command, unstage::unstage::unstage_command,
+        commit::commit::commit_command, conflicts::conflicts::conflicts_command,
+        diff::diff::diff_command, healthcheck::healthcheck::healthcheck_command,
+        hooks::hooks::process_hooks_commands, init::init::init_command,
+        pull::pull::pull_command, push::push::push_command,
+        setup::setup::process_setup_commands,
+        squash_fixups::squash_fixups::squash_fixups_command,
+        stage::stage::stage_command, state::state::state_command,
+        undo::undo::undo_command, unstage::unstage::unstage_command,
         update::update::update_command,
+        workspace::workspace::process_workspace_commands,
     },
+    diagnostics::{log_diagnostic, DiagnosticKind},
+    error::{ScudError, ScudResult},
 };
 
 /// Handler for the various CLI commands
-pub fn process_args(args: Cli) {
+///
+/// Returns:
+///
+/// The failure of the command, which `main` reports before exiting with the
+/// matching exit code
+pub fn process_args(args: Cli) -> ScudResult {
+    // Commands are handed the directory to operate on (and run the underlying
+    // VCS in it), rather than scud changing its own working directory
+    let working_dir = args.directory.as_deref().unwrap_or_else(|| Path::new("."));
+    if !working_dir.is_dir() {
+        return Err(ScudError::failed(
+            "Cannot operate on the given repository",
+            format!("{} isn't a directory", working_dir.display()),
+        ));
+    }
+
     let args = args.command;
 
     match args {
@@ -20,10 +44,9 @@ pub fn process_args(args: Cli) {
         //////////////////////////////////////////////////////////////
         Commands::New(new_repo_options) => {
             println!("New: {}", new_repo_options.name);
+            Ok(())
         }
-        Commands::Init(init_options) => {
-            init_command(init_options);
-        }
+        Commands::Init(init_options) => init_command(init_options),
 
         //////////////////////////////////////////////////////////////
         // Declarative high-level operations on the top of the VCS. //
@@ -33,34 +56,42 @@ pub fn process_args(args: Cli) {
         // - Unstage                                                //
         // - Commit                                                 //
         // - Diff                                                   //
+        // - Undo                                                   //
         //////////////////////////////////////////////////////////////
-        Commands::State(state_options) => {
-            state_command(state_options);
-        }
+        Commands::State(state_options) => state_command(state_options, working_dir),
 
-        Commands::Stage(stage_options) => {
-            stage_command(stage_options);
-        }
+        Commands::Stage(stage_options) => stage_command(stage_options, working_dir),
 
         Commands::Unstage(unstage_options) => {
-            unstage_command(unstage_options);
+            unstage_command(unstage_options, working_dir)
         }
 
         Commands::Commit(commit_options) => {
-            commit_command(commit_options);
+            commit_command(commit_options, working_dir)
+        }
+
+        Commands::Push(push_options) => push_command(push_options, working_dir),
+
+        Commands::SquashFixups(squash_fixups_options) => {
+            squash_fixups_command(squash_fixups_options, working_dir)
         }
 
-        Commands::Push(push_options) => {
-            push_command(push_options);
+        Commands::Conflicts(conflicts_options) => {
+            conflicts_command(conflicts_options, working_dir)
         }
 
-        Commands::Pull(pull_options) => {
-            println!("Pull: {}", pull_options.info);
-            // pull_command(pull_options, start_time);
+        Commands::Hooks(hooks_options) => {
+            process_hooks_commands(hooks_options, working_dir)
         }
 
-        Commands::Diff(diff_options) => {
-            diff_command(diff_options);
+        Commands::Undo(undo_options) => undo_command(undo_options, working_dir),
+
+        Commands::Pull(pull_options) => pull_command(pull_options, working_dir),
+
+        Commands::Diff(diff_options) => diff_command(diff_options, working_dir),
+
+        Commands::Workspace(workspace_options) => {
+            process_workspace_commands(workspace_options, working_dir)
         }
 
         ///////////////////////////////////
@@ -68,35 +99,48 @@ pub fn process_args(args: Cli) {
         // branching strategies          //
         ///////////////////////////////////
         Commands::Branch(branch_commands) => {
-            process_branch_commands(branch_commands)
+            process_branch_commands(branch_commands, working_dir)
         }
 
         ////////////////////////////
         // Various setup commands //
         ////////////////////////////
-        // Commands::Setup(setup_options) => {
-        //     println!("Setup {}", setup_options.info);
-        // }
+        Commands::Setup(setup_options) => {
+            process_setup_commands(setup_options, working_dir)
+        }
 
         //////////////////////////////////////////////////
         // Commands to ensure system is setup for usage //
         // w/ the underlying components which make up   //
         // scud                                         //
         //////////////////////////////////////////////////
-        Commands::Healthcheck => {
-            healthcheck_command();
+        Commands::Healthcheck(healthcheck_options) => {
+            healthcheck_command(healthcheck_options, working_dir)
         }
 
         //
-        Commands::Update(update_options) => {
-            update_command(update_options);
-        }
+        Commands::Update(update_options) => update_command(update_options),
 
-        /////////////////////////////////////////////
-        // Handler for processing unknown commands //
-        /////////////////////////////////////////////
-        _ => {
-            !unreachable!();
+        /////////////////////////////////////////////////
+        // Commands which haven't been implemented yet //
+        /////////////////////////////////////////////////
+        Commands::Feature(_) => {
+            log_diagnostic(DiagnosticKind::WorkInProgress {
+                feature: "scud feature",
+            });
+            Ok(())
+        }
+        Commands::CommitAll(_) => {
+            log_diagnostic(DiagnosticKind::WorkInProgress {
+                feature: "scud commit-all",
+            });
+            Ok(())
+        }
+        Commands::Upstream(_) => {
+            log_diagnostic(DiagnosticKind::WorkInProgress {
+                feature: "scud upstream",
+            });
+            Ok(())
         }
     }
 }
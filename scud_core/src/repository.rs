@@ -0,0 +1,159 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    cli::cli::VCS,
+    error::{ScudError, ScudResult},
+};
+
+/// A repository of one of the supported version control systems, as found
+/// by [`Repository::discover`].
+///
+/// Properties:
+///
+/// * `vcs`: The version control system the repository belongs to.
+/// * `root`: The root of the working directory of the repository.
+/// * `git_dir`: Where git keeps the repository, for git repositories (and
+/// Jujutsu repositories colocated with one). That's .git itself, the
+/// directory a .git file points at (for worktrees and submodules), or GIT_DIR.
+/// * `enclosing`: The repository the root lies within, if any (e.g. the
+/// Mercurial repository a git repository was cloned into).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Repository {
+    pub vcs: VCS,
+    pub root: PathBuf,
+    pub git_dir: Option<PathBuf>,
+    pub enclosing: Option<Box<Repository>>,
+}
+
+impl Repository {
+    /// Finds the repository the given directory belongs to by walking up
+    /// from it. The closest repository wins, so nested repositories are
+    /// detected as such rather than as part of the enclosing one.
+    ///
+    /// As with git, GIT_DIR (and GIT_WORK_TREE, which defaults to the given
+    /// directory) take precedence over walking up.
+    ///
+    /// Arguments:
+    ///
+    /// * `path`: The directory to start from, relative to the working
+    /// directory unless it's absolute.
+    pub fn discover(path: impl AsRef<Path>) -> ScudResult<Repository> {
+        let path = canonicalize(path.as_ref())?;
+
+        match env::var_os("GIT_DIR") {
+            Some(git_dir) => Repository::from_git_dir(&path, Path::new(&git_dir)),
+            None => Repository::find(&path)?.ok_or(ScudError::VcsNotFound),
+        }
+    }
+
+    /// The git repository GIT_DIR and GIT_WORK_TREE point at, which are
+    /// relative to the given directory.
+    fn from_git_dir(path: &Path, git_dir: &Path) -> ScudResult<Repository> {
+        let git_dir = path.join(git_dir);
+        if !git_dir.is_dir() {
+            return Err(ScudError::failed(
+                "GIT_DIR doesn't point at a git repository",
+                format!("{} isn't a directory", git_dir.display()),
+            ));
+        }
+
+        let root = match env::var_os("GIT_WORK_TREE") {
+            Some(work_tree) => canonicalize(&path.join(work_tree))?,
+            None => path.to_path_buf(),
+        };
+
+        Ok(Repository {
+            vcs: VCS::Git,
+            git_dir: Some(canonicalize(&git_dir)?),
+            enclosing: Repository::find_enclosing(&root)?,
+            root,
+        })
+    }
+
+    /// Walks up from the given absolute directory to the closest repository.
+    fn find(directory: &Path) -> ScudResult<Option<Repository>> {
+        for directory in directory.ancestors() {
+            if let Some(vcs) = detect_vcs(directory) {
+                let git_dir = match vcs {
+                    VCS::Git | VCS::Jujutsu => find_git_dir(directory)?,
+                    _ => None,
+                };
+
+                return Ok(Some(Repository {
+                    vcs,
+                    root: directory.to_path_buf(),
+                    git_dir,
+                    enclosing: Repository::find_enclosing(directory)?,
+                }));
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn find_enclosing(root: &Path) -> ScudResult<Option<Box<Repository>>> {
+        match root.parent() {
+            Some(parent) => Ok(Repository::find(parent)?.map(Box::new)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// The VCS whose metadata lives in the given directory, if any. Unlike
+/// [`Repository::discover`], it doesn't walk up, so it tells whether the
+/// directory is the root of a repository.
+pub fn detect_vcs(directory: &Path) -> Option<VCS> {
+    // jj repositories are usually colocated with a git one, whose commands
+    // would fight jj's working-copy commit, so .jj goes before .git
+    if directory.join(".jj").exists() {
+        Some(VCS::Jujutsu)
+    } else if directory.join(".git").exists() {
+        Some(VCS::Git)
+    } else if directory.join(".hg").exists() {
+        Some(VCS::Mercurial)
+    } else if directory.join(".bzr").exists() {
+        Some(VCS::Breezy)
+    } else if directory.join(".svn").exists() {
+        // Since Subversion 1.7, only the root of a working copy has a .svn
+        Some(VCS::Subversion)
+    } else if directory.join(".fslckout").exists()
+        || directory.join("_FOSSIL_").exists()
+    {
+        // _FOSSIL_ is the name of the checkout database on Windows
+        Some(VCS::Fossil)
+    } else {
+        None
+    }
+}
+
+/// The git dir of the given root, following .git files (e.g.
+/// "gitdir: ../.git/worktrees/feature"), which worktrees and submodules have
+/// in place of a .git directory.
+fn find_git_dir(root: &Path) -> ScudResult<Option<PathBuf>> {
+    let dot_git = root.join(".git");
+
+    if dot_git.is_dir() {
+        return Ok(Some(dot_git));
+    } else if !dot_git.is_file() {
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&dot_git).map_err(|error| {
+        ScudError::io(format!("Failed to read {}", dot_git.display()), error)
+    })?;
+    let git_dir = content
+        .trim()
+        .strip_prefix("gitdir:")
+        .ok_or_else(|| ScudError::parse(".git file", content.trim()))?;
+
+    canonicalize(&root.join(git_dir.trim())).map(Some)
+}
+
+fn canonicalize(path: &Path) -> ScudResult<PathBuf> {
+    fs::canonicalize(path).map_err(|error| {
+        ScudError::io(format!("Failed to find {}", path.display()), error)
+    })
+}
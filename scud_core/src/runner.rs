@@ -0,0 +1,555 @@
+use std::{
+    ffi::OsStr,
+    io::{self, Read, Write},
+    path::{Component, Path, PathBuf},
+    process::{Command, Output, Stdio},
+    sync::{
+        atomic::{AtomicU8, Ordering},
+        OnceLock,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use colored::Colorize;
+
+use crate::{
+    commands::setup::helpers::is_in_path,
+    error::{ScudError, ScudResult},
+};
+
+// Every external program scud runs (above all the underlying VCS) goes
+// through ExternalCommand, so failures are reported the same way everywhere
+// and `-v` can trace exactly what was run under the hood.
+
+/// Traces each command along with its working directory, exit code and
+/// duration (`-v`).
+pub const VERBOSITY_COMMANDS: u8 = 1;
+
+/// Additionally traces the captured output of each command (`-vv`).
+pub const VERBOSITY_OUTPUT: u8 = 2;
+
+static VERBOSITY: AtomicU8 = AtomicU8::new(0);
+
+/// Sets how much of the external commands is traced to stderr (the number of
+/// times `-v` was passed).
+pub fn set_verbosity(verbosity: u8) {
+    VERBOSITY.store(verbosity, Ordering::Relaxed);
+}
+
+pub fn verbosity() -> u8 {
+    VERBOSITY.load(Ordering::Relaxed)
+}
+
+/// An external command to be run by scud.
+///
+/// Properties:
+///
+/// * `program`: The program to run (e.g. git).
+/// * `args`: The arguments passed to the program.
+/// * `envs`: Environment variables set for the program only.
+/// * `current_dir`: The directory to run the program in (optional).
+/// [default: the current directory]
+/// * `stdin`: Input written to the program's stdin (optional).
+/// * `timeout`: How long to wait before killing the program (optional).
+#[derive(Debug, Clone)]
+pub struct ExternalCommand {
+    program: String,
+    args: Vec<String>,
+    envs: Vec<(String, String)>,
+    current_dir: Option<PathBuf>,
+    stdin: Option<String>,
+    timeout: Option<Duration>,
+}
+
+/// The recorded result of an external command which ran to completion.
+///
+/// Properties:
+///
+/// * `argv`: The program and arguments which were run.
+/// * `status`: The exit code, if the command wasn't killed by a signal.
+/// * `stdout`: What the command wrote to stdout.
+/// * `stderr`: What the command wrote to stderr.
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub argv: Vec<String>,
+    pub status: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl CommandOutput {
+    pub fn success(&self) -> bool {
+        self.status == Some(0)
+    }
+
+    /// The stdout of the command without surrounding whitespace, which is
+    /// what's wanted for single values (e.g. git branch --show-current).
+    pub fn trimmed_stdout(&self) -> &str {
+        self.stdout.trim()
+    }
+
+    /// The command line which was run (e.g. git commit -m "feat: ...").
+    pub fn command_line(&self) -> String {
+        command_line(&self.argv)
+    }
+
+    /// Converts an unsuccessful exit into a ScudError, so it can be
+    /// propagated with `?`.
+    pub fn check(self) -> ScudResult<CommandOutput> {
+        if self.success() {
+            Ok(self)
+        } else {
+            Err(ScudError::CommandFailed {
+                cmd: self.command_line(),
+                status: self.status,
+                stderr: self.message().to_string(),
+            })
+        }
+    }
+
+    /// What the command had to say about its outcome. Most tools write
+    /// errors to stderr, but some messages (e.g. git's "nothing to commit")
+    /// are written to stdout.
+    pub fn message(&self) -> &str {
+        if self.stderr.trim().is_empty() {
+            self.stdout.trim()
+        } else {
+            self.stderr.trim()
+        }
+    }
+}
+
+impl ExternalCommand {
+    pub fn new(program: impl Into<String>) -> Self {
+        ExternalCommand {
+            program: program.into(),
+            args: vec![],
+            envs: vec![],
+            current_dir: None,
+            stdin: None,
+            timeout: None,
+        }
+    }
+
+    /// A git command with the given arguments.
+    pub fn git<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        ExternalCommand::new("git").args(args)
+    }
+
+    /// A Mercurial command with the given arguments. HGPLAIN leaves out the
+    /// user's aliases, defaults and localisation, so the output can be parsed.
+    pub fn hg<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        ExternalCommand::new("hg").env("HGPLAIN", "1").args(args)
+    }
+
+    /// A Breezy command with the given arguments, run by whichever of `brz`
+    /// and `bzr` is installed.
+    pub fn brz<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        ExternalCommand::new(breezy_program()).args(args)
+    }
+
+    /// A Subversion command with the given arguments.
+    pub fn svn<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        ExternalCommand::new("svn").args(args)
+    }
+
+    /// A Jujutsu command with the given arguments.
+    pub fn jj<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        ExternalCommand::new("jj").args(args)
+    }
+
+    /// A Fossil command with the given arguments.
+    pub fn fossil<I, S>(args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        ExternalCommand::new("fossil").args(args)
+    }
+
+    pub fn arg(mut self, arg: impl AsRef<OsStr>) -> Self {
+        self.args.push(arg.as_ref().to_string_lossy().to_string());
+        self
+    }
+
+    pub fn args<I, S>(mut self, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args.extend(
+            args.into_iter()
+                .map(|arg| arg.as_ref().to_string_lossy().to_string()),
+        );
+        self
+    }
+
+    pub fn env(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn current_dir(mut self, directory: impl AsRef<Path>) -> Self {
+        self.current_dir = Some(directory.as_ref().to_path_buf());
+        self
+    }
+
+    pub fn stdin(mut self, input: impl Into<String>) -> Self {
+        self.stdin = Some(input.into());
+        self
+    }
+
+    /// Kills the program when it hasn't finished after the given duration
+    /// (e.g. a remote which doesn't answer).
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// The program and arguments which will be run.
+    pub fn argv(&self) -> Vec<String> {
+        std::iter::once(self.program.clone())
+            .chain(self.args.iter().cloned())
+            .collect()
+    }
+
+    fn command(&self) -> Command {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args);
+
+        for (key, value) in &self.envs {
+            command.env(key, value);
+        }
+
+        if let Some(directory) = &self.current_dir {
+            command.current_dir(directory);
+        }
+
+        command
+    }
+
+    /// Runs the command, capturing its output. Unsuccessful exits aren't
+    /// errors here, which is what's wanted for queries whose exit code is an
+    /// answer (e.g. git rev-parse --verify).
+    ///
+    /// Returns:
+    ///
+    /// The recorded output, or an error if the command couldn't be run at all.
+    pub fn output(&self) -> ScudResult<CommandOutput> {
+        let argv = self.argv();
+        trace_start(&argv);
+        let start_time = Instant::now();
+
+        let output = match self.spawn_and_wait() {
+            Ok(Some(output)) => output,
+            Ok(None) => {
+                self.trace_finish(None, start_time, None);
+                return Err(ScudError::CommandFailed {
+                    cmd: command_line(&argv),
+                    status: None,
+                    stderr: format!(
+                        "No answer after {}s, so it was stopped",
+                        self.timeout.unwrap_or_default().as_secs()
+                    ),
+                });
+            }
+            Err(error) => return Err(ScudError::spawn(&self.program, error)),
+        };
+
+        let output = CommandOutput {
+            argv,
+            status: output.status.code(),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        };
+        self.trace_finish(output.status, start_time, Some(&output));
+
+        Ok(output)
+    }
+
+    /// Spawns the command with its output captured, feeding it the input
+    /// from a separate thread so a command answering while it reads (e.g. git
+    /// cat-file --batch-check) can't fill up its stdout and block. With a
+    /// timeout, the output is read from separate threads too while waiting,
+    /// so a command printing more than fits into a pipe doesn't block either.
+    ///
+    /// Returns:
+    ///
+    /// The output, or None when the command was killed after the timeout.
+    fn spawn_and_wait(&self) -> io::Result<Option<Output>> {
+        let mut child = self
+            .command()
+            .stdin(if self.stdin.is_some() {
+                Stdio::piped()
+            } else {
+                Stdio::null()
+            })
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+
+        if let (Some(input), Some(mut stdin)) =
+            (self.stdin.clone(), child.stdin.take())
+        {
+            // A command which exits before reading all of its input is fine
+            thread::spawn(move || {
+                let _ = stdin.write_all(input.as_bytes());
+            });
+        }
+
+        let timeout = match self.timeout {
+            Some(timeout) => timeout,
+            None => return child.wait_with_output().map(Some),
+        };
+
+        let stdout = read_in_background(child.stdout.take());
+        let stderr = read_in_background(child.stderr.take());
+        let start_time = Instant::now();
+
+        let status = loop {
+            if let Some(status) = child.try_wait()? {
+                break status;
+            }
+
+            if start_time.elapsed() >= timeout {
+                // The readers are left to finish on their own, as processes
+                // the command started (e.g. ssh) can keep the pipes open
+                let _ = child.kill();
+                let _ = child.wait();
+                return Ok(None);
+            }
+
+            thread::sleep(Duration::from_millis(50));
+        };
+
+        Ok(Some(Output {
+            status,
+            stdout: stdout.join().unwrap_or_default(),
+            stderr: stderr.join().unwrap_or_default(),
+        }))
+    }
+
+    /// Runs the command, capturing its output and turning an unsuccessful
+    /// exit into an error quoting what the command reported.
+    pub fn run(&self) -> ScudResult<CommandOutput> {
+        self.output()?.check()
+    }
+
+    /// Runs the command with the terminal attached, for commands whose
+    /// progress the user should see (e.g. git push) or which prompt.
+    pub fn run_attached(&self) -> ScudResult {
+        let argv = self.argv();
+        trace_start(&argv);
+        let start_time = Instant::now();
+
+        let status = self
+            .command()
+            .status()
+            .map_err(|error| ScudError::spawn(&self.program, error))?;
+        // The output went straight to the terminal, so there's none to dump
+        self.trace_finish(status.code(), start_time, None);
+
+        if status.success() {
+            Ok(())
+        } else {
+            Err(ScudError::from_status(command_line(&argv), status))
+        }
+    }
+
+    /// Traces how the command finished, along with the directory it ran in and
+    /// (at `-vv`) what it printed.
+    fn trace_finish(
+        &self,
+        status: Option<i32>,
+        start_time: Instant,
+        output: Option<&CommandOutput>,
+    ) {
+        if verbosity() < VERBOSITY_COMMANDS {
+            return;
+        }
+
+        // The directory is left out when the current one can't be read
+        let directory = std::env::current_dir()
+            .ok()
+            .map(|current_dir| {
+                resolve_directory(&current_dir, self.current_dir.as_deref())
+            })
+            .map(|directory| format!("in {}, ", directory.display()))
+            .unwrap_or_default();
+        let status = match status {
+            Some(code) => format!("exit code {}", code),
+            None => "terminated".to_string(),
+        };
+
+        eprintln!(
+            "{}",
+            format!(
+                "  ({}{} after {}ms)",
+                directory,
+                status,
+                start_time.elapsed().as_millis()
+            )
+            .bright_black()
+            .italic()
+        );
+
+        if let (Some(output), true) = (output, verbosity() >= VERBOSITY_OUTPUT) {
+            trace_stream("stdout", &output.stdout);
+            trace_stream("stderr", &output.stderr);
+        }
+    }
+
+    /// Runs the command for its trimmed stdout, or None if it couldn't be run
+    /// or failed. Meant for best effort lookups with a sensible fallback.
+    pub fn stdout(&self) -> Option<String> {
+        self.output()
+            .ok()
+            .filter(|output| output.success())
+            .map(|output| output.trimmed_stdout().to_string())
+    }
+}
+
+/// Reads the given pipe to its end on a separate thread.
+fn read_in_background<R>(pipe: Option<R>) -> thread::JoinHandle<Vec<u8>>
+where
+    R: Read + Send + 'static,
+{
+    thread::spawn(move || {
+        let mut content = vec![];
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut content);
+        }
+        content
+    })
+}
+
+/// The executable of Breezy. It's called `brz`, but it's also installed as
+/// (or only available under) `bzr`, the name of its predecessor Bazaar.
+pub fn breezy_program() -> &'static str {
+    static PROGRAM: OnceLock<&'static str> = OnceLock::new();
+
+    PROGRAM.get_or_init(|| {
+        ["brz", "bzr"]
+            .into_iter()
+            .find(|program| is_in_path(program))
+            .unwrap_or("brz")
+    })
+}
+
+/// Joins an argv into a command line, quoting arguments which contain
+/// whitespace so the line can be copied back into a shell.
+pub fn command_line(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| {
+            if arg.is_empty() || arg.contains(char::is_whitespace) {
+                format!("{:?}", arg)
+            } else {
+                arg.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// The absolute directory a command runs in, given the current directory and
+/// the directory it was told to run in, if any (e.g. `.` or `../other`).
+fn resolve_directory(current_dir: &Path, directory: Option<&Path>) -> PathBuf {
+    let mut resolved = current_dir.to_path_buf();
+
+    for component in directory.map(Path::components).into_iter().flatten() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            component => resolved.push(component),
+        }
+    }
+
+    resolved
+}
+
+fn trace_start(argv: &[String]) {
+    if verbosity() >= VERBOSITY_COMMANDS {
+        eprintln!("{} {}", "$".bright_black(), command_line(argv).cyan());
+    }
+}
+
+/// Prints a captured stream of a command, each line prefixed with the name of
+/// the stream (e.g. `stdout | ## main...origin/main`).
+fn trace_stream(name: &str, content: &str) {
+    for line in content.trim_end().lines() {
+        eprintln!("  {} {}", format!("{} |", name).bright_black(), line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(unix)]
+    fn output_larger_than_a_pipe_is_read_before_the_timeout() {
+        let output = ExternalCommand::new("sh")
+            .args(["-c", "head -c 1000000 /dev/zero; echo done >&2"])
+            .timeout(Duration::from_secs(30))
+            .run()
+            .unwrap();
+
+        assert_eq!(output.stdout.len(), 1_000_000);
+        assert_eq!(output.stderr, "done\n");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn commands_are_stopped_after_the_timeout() {
+        let error = ExternalCommand::new("sleep")
+            .arg("10")
+            .timeout(Duration::from_millis(200))
+            .output()
+            .unwrap_err();
+
+        assert!(error.to_string().contains("No answer"), "{}", error);
+    }
+
+    #[test]
+    fn traced_directories_are_absolute() {
+        let current_dir = Path::new("/home/me/project");
+
+        for (directory, expected) in [
+            (None, "/home/me/project"),
+            (Some("."), "/home/me/project"),
+            (Some("./src/../docs"), "/home/me/project/docs"),
+            (Some("../other"), "/home/me/other"),
+            (Some("/srv/repository"), "/srv/repository"),
+        ] {
+            assert_eq!(
+                resolve_directory(current_dir, directory.map(Path::new)),
+                PathBuf::from(expected),
+                "{:?}",
+                directory
+            );
+        }
+    }
+}
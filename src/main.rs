@@ -1,22 +1,54 @@
-use std::time::Instant;
+use std::{process::exit, time::Instant};
 
 use clap::Parser;
 
 use scud_core::{
-    cli::cli::Cli, general::log_execution_time, helpers::check_version,
+    cli::cli::{Cli, ColorChoice, Commands, OutputFormat},
+    diagnostics::set_quiet,
+    general::log_execution_time,
     process_commands::process_args,
+    runner::set_verbosity,
+    theme::apply_color_choice,
+    update::notifier::start_update_check,
 };
 
 fn main() {
     let start_time = Instant::now();
 
-    // Parse and process command line arguments
+    // Parse command line arguments
     let args = Cli::parse();
-    process_args(args);
+    set_verbosity(args.verbose);
+    set_quiet(args.quiet);
+    apply_color_choice(if args.no_color {
+        ColorChoice::Never
+    } else {
+        args.color
+    });
+
+    // Nothing may follow a JSON document on stdout, and nothing but the output
+    // of the command is wanted when quiet
+    let quiet = args.quiet;
+    let prints_json = matches!(
+        &args.command,
+        Commands::Healthcheck(healthcheck) if healthcheck.format == OutputFormat::Json
+    );
+
+    // Check for scud updates in the background while the command runs
+    let update_check = start_update_check();
+
+    // Process command line arguments, reporting any failure once
+    if let Err(error) = process_args(args) {
+        error.log();
+        exit(error.exit_code());
+    }
+
+    if prints_json || quiet {
+        return;
+    }
 
     // log the execution time
     log_execution_time(start_time);
 
-    // Check for scud updates and notify user when available
-    check_version();
+    // Notify user when an update is available
+    update_check.finish();
 }
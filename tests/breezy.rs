@@ -0,0 +1,151 @@
+//! Integration tests for the Breezy backend, run against throwaway local
+//! branches. They're ignored unless requested since they need brz or bzr
+//! (see the common module).
+
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, Repo, Vcs};
+
+#[test]
+#[ignore = "needs brz"]
+fn state_groups_the_changes_of_brz_status() {
+    let branch = Repo::new(Vcs::Breezy, "state");
+    branch
+        .commit("tracked.txt", "one\n", "initial")
+        .commit("gone.txt", "gone\n", "second")
+        .write("tracked.txt", "two\n")
+        .write("new.txt", "new\n")
+        .remove("gone.txt");
+
+    let output = branch.scud(&["state"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("Breezy"));
+    assert!(stdout.contains("Modified files staged for commit"));
+    assert!(stdout.contains("tracked.txt"));
+    assert!(stdout.contains("Deleted files not yet staged for commit"));
+    assert!(stdout.contains("gone.txt"));
+    assert!(stdout.contains("?? ") && stdout.contains("new.txt"));
+}
+
+#[test]
+#[ignore = "needs brz"]
+fn stage_adds_new_files_and_removes_deleted_ones() {
+    let branch = Repo::new(Vcs::Breezy, "stage");
+    branch
+        .commit("gone.txt", "gone\n", "initial")
+        .write("src/new.txt", "new\n")
+        .remove("gone.txt");
+
+    // Staging covers the whole branch, wherever it's run from
+    let output = branch.scud_in(&branch.dir.join("src"), &["stage"]);
+    let status = branch.status();
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        status.contains(&"+N  src/new.txt".to_string()),
+        "{:?}",
+        status
+    );
+    assert!(
+        status
+            .iter()
+            .any(|line| line.starts_with('-') && line.ends_with("gone.txt")),
+        "{:?}",
+        status
+    );
+    assert!(stdout(&output).contains("Added files staged for commit"));
+}
+
+#[test]
+#[ignore = "needs brz"]
+fn unstage_undoes_stage() {
+    let branch = Repo::new(Vcs::Breezy, "unstage");
+    branch
+        .commit("gone.txt", "gone\n", "initial")
+        .write("new.txt", "new\n")
+        .remove("gone.txt");
+    branch.run(&["add", "--quiet"]);
+    branch.run(&["remove", "gone.txt"]);
+
+    let output = branch.scud(&["unstage"]);
+    let status = branch.status();
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(status.contains(&"?   new.txt".to_string()), "{:?}", status);
+    assert!(status.contains(&" D  gone.txt".to_string()), "{:?}", status);
+    assert!(!branch.dir.join("gone.txt").exists());
+}
+
+#[test]
+#[ignore = "needs brz"]
+fn unstage_keeps_files_removed_with_keep() {
+    let branch = Repo::new(Vcs::Breezy, "unstage-kept");
+    branch
+        .commit("kept.txt", "one\n", "initial")
+        .write("kept.txt", "edited\n");
+    branch.run(&["remove", "--keep", "kept.txt"]);
+
+    let output = branch.scud(&["unstage"]);
+    let status = branch.status();
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        fs::read_to_string(branch.dir.join("kept.txt")).unwrap(),
+        "edited\n"
+    );
+    assert!(status.contains(&" M  kept.txt".to_string()), "{:?}", status);
+}
+
+#[test]
+#[ignore = "needs brz"]
+fn commit_without_changes_fails() {
+    let branch = Repo::new(Vcs::Breezy, "commit");
+    branch.commit("tracked.txt", "one\n", "initial");
+
+    let output = branch.scud(&["commit"]);
+
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("without any staged changes"));
+}
+
+#[test]
+#[ignore = "needs brz"]
+fn branch_lists_the_branches_of_the_shared_repository() {
+    let project = Repo::empty(Vcs::Breezy, "branch");
+    project.run(&["init-shared-repo", "."]);
+    project.run(&["init", "trunk"]);
+
+    let trunk = project.dir.join("trunk");
+    fs::write(trunk.join("tracked.txt"), "one\n").unwrap();
+    project.run_in(&trunk, &["add", "--quiet"]);
+    project.run_in(&trunk, &["commit", "--quiet", "-m", "initial"]);
+    project.run(&["branch", "--quiet", "trunk", "feature"]);
+
+    let output = project.scud_in(&trunk, &["branch"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("feature"));
+    assert!(stdout.contains("** trunk <- ACTIVE"));
+    assert!(stdout.contains("Latest commit: r1"));
+}
+
+#[test]
+#[ignore = "needs brz"]
+fn diff_shows_uncommitted_changes() {
+    let branch = Repo::new(Vcs::Breezy, "diff");
+    branch
+        .commit("tracked.txt", "one\n", "initial")
+        .write("tracked.txt", "two\n");
+
+    let output = branch.scud(&["diff"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("-one"));
+    assert!(stdout.contains("+two"));
+}
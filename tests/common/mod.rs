@@ -0,0 +1,330 @@
+//! Fixture shared by the integration tests, which run against throwaway local
+//! repositories.
+//!
+//! The git tests run by default and are skipped when git isn't installed.
+//! The tests of the other backends need their VCS to be installed, so they're
+//! ignored by default. Run them with `cargo test --test <backend> --
+//! --ignored`, which fails instead of skipping them when the VCS isn't
+//! installed.
+
+// Each test crate only uses some of the fixture
+#![allow(dead_code)]
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// The version control systems the fixture can create repositories of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Vcs {
+    Git,
+    Mercurial,
+    Breezy,
+    Subversion,
+    Fossil,
+}
+
+impl Vcs {
+    /// Short name of the VCS, used in the names of the fixture directories.
+    fn name(self) -> &'static str {
+        match self {
+            Vcs::Git => "git",
+            Vcs::Mercurial => "hg",
+            Vcs::Breezy => "brz",
+            Vcs::Subversion => "svn",
+            Vcs::Fossil => "fossil",
+        }
+    }
+
+    /// Finds the program of the VCS, or None when it (or one of the other
+    /// programs the fixture needs) isn't installed.
+    fn installed(self) -> Option<&'static str> {
+        let runs = |program: &str, arg: &str| {
+            Command::new(program)
+                .arg(arg)
+                .output()
+                .map_or(false, |output| output.status.success())
+        };
+
+        match self {
+            Vcs::Git => runs("git", "--version").then(|| "git"),
+            Vcs::Mercurial => runs("hg", "version").then(|| "hg"),
+            // Breezy is still installed as bzr on some systems
+            Vcs::Breezy => ["brz", "bzr"]
+                .into_iter()
+                .find(|program| runs(program, "--version")),
+            Vcs::Subversion => (runs("svn", "--version")
+                && runs("svnadmin", "--version"))
+            .then(|| "svn"),
+            Vcs::Fossil => runs("fossil", "version").then(|| "fossil"),
+        }
+    }
+
+    /// Finds the program of the VCS, panicking when it isn't installed.
+    fn program(self) -> &'static str {
+        self.installed().unwrap_or_else(|| {
+            panic!(
+                "{} isn't installed, which the {:?} tests need",
+                self.name(),
+                self
+            )
+        })
+    }
+}
+
+/// A throwaway repository along with a working copy of it, isolated from the
+/// user's configuration. For git, Mercurial and Breezy the working copy is
+/// the repository itself.
+pub struct Repo {
+    pub vcs: Vcs,
+    /// The working copy the tests run in.
+    pub dir: PathBuf,
+    /// Holds everything the fixture creates, and is removed on drop.
+    base: PathBuf,
+    /// Stands in for the home directory, where each VCS looks for the
+    /// configuration of the user. It's outside of the working copy so it
+    /// doesn't show up as unknown files.
+    home: PathBuf,
+    program: &'static str,
+}
+
+impl Repo {
+    /// Creates an empty working directory without any repository in it.
+    pub fn empty(vcs: Vcs, test_name: &str) -> Self {
+        let program = vcs.program();
+
+        // Test crates can run at the same time and share test names
+        let base = std::env::temp_dir().join(format!(
+            "scud-{}-{}-{}-{}",
+            env!("CARGO_CRATE_NAME"),
+            vcs.name(),
+            test_name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&base);
+
+        let repo = Repo {
+            vcs,
+            dir: base.join("work"),
+            home: base.join("home"),
+            base,
+            program,
+        };
+        for dir in [&repo.dir, &repo.home] {
+            fs::create_dir_all(dir).unwrap();
+        }
+
+        repo
+    }
+
+    /// Creates an empty repository along with its working copy.
+    pub fn new(vcs: Vcs, test_name: &str) -> Self {
+        let repo = Repo::empty(vcs, test_name);
+
+        match vcs {
+            Vcs::Git => {
+                repo.run(&["init", "--quiet", "--initial-branch=main"]);
+            }
+            Vcs::Mercurial | Vcs::Breezy => {
+                repo.run(&["init"]);
+            }
+            Vcs::Subversion => {
+                let output = repo
+                    .isolated(Command::new("svnadmin"), &repo.base)
+                    .arg("create")
+                    .arg(repo.base.join("repository"))
+                    .output()
+                    .unwrap();
+                assert!(
+                    output.status.success(),
+                    "svnadmin create failed: {}",
+                    String::from_utf8_lossy(&output.stderr)
+                );
+                repo.checkout(&repo.dir);
+            }
+            Vcs::Fossil => {
+                let repository = repo.base.join("repository.fossil");
+                let repository = repository.to_str().unwrap();
+                repo.run_in(&repo.base, &["init", repository]);
+                repo.run(&["open", repository]);
+            }
+        }
+
+        repo
+    }
+
+    /// Creates an empty working directory for git repositories to be
+    /// created in, or None when git isn't installed so the test can be
+    /// skipped.
+    pub fn empty_git(test_name: &str) -> Option<Self> {
+        if Vcs::Git.installed().is_none() {
+            eprintln!("skipping {}: git isn't installed", test_name);
+            return None;
+        }
+
+        Some(Repo::empty(Vcs::Git, test_name))
+    }
+
+    /// Creates a git repository with an empty initial commit, or None when
+    /// git isn't installed so the test can be skipped.
+    pub fn git(test_name: &str) -> Option<Self> {
+        let repo = Repo::empty_git(test_name)?;
+        repo.run(&["init", "--quiet", "--initial-branch=main"]);
+        repo.run(&["commit", "--quiet", "--allow-empty", "-m", "initial"]);
+
+        Some(repo)
+    }
+
+    /// The file:// URL of the Subversion repository.
+    pub fn url(&self) -> String {
+        format!("file://{}", self.base.join("repository").display())
+    }
+
+    /// Checks out another working copy of the Subversion repository.
+    pub fn checkout(&self, dir: &Path) {
+        let parent = dir.parent().unwrap();
+        let name = dir.file_name().unwrap().to_str().unwrap();
+        self.run_in(parent, &["checkout", "--quiet", &self.url(), name]);
+    }
+
+    pub fn write(&self, path: &str, content: &str) -> &Self {
+        let path = self.dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+        self
+    }
+
+    pub fn remove(&self, path: &str) -> &Self {
+        fs::remove_file(self.dir.join(path)).unwrap();
+        self
+    }
+
+    /// Runs the VCS in the working copy, panicking when it fails.
+    pub fn run(&self, args: &[&str]) -> String {
+        self.run_in(&self.dir, args)
+    }
+
+    /// Runs the VCS in the given directory, panicking when it fails.
+    pub fn run_in(&self, dir: &Path, args: &[&str]) -> String {
+        let output = self
+            .isolated(Command::new(self.program), dir)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{} {} failed: {}",
+            self.program,
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8_lossy(&output.stdout).to_string()
+    }
+
+    /// Commits the given file so the repository has some history.
+    pub fn commit(&self, path: &str, content: &str, message: &str) -> &Self {
+        self.write(path, content);
+        match self.vcs {
+            Vcs::Git => {
+                self.run(&["add", "-A"]);
+                self.run(&["commit", "--quiet", "-m", message])
+            }
+            Vcs::Mercurial => self.run(&["commit", "-A", "-m", message]),
+            Vcs::Breezy => {
+                self.run(&["add", "--quiet"]);
+                self.run(&["commit", "--quiet", "-m", message])
+            }
+            Vcs::Subversion => {
+                self.run(&["add", "--quiet", "--force", "."]);
+                self.run(&["commit", "--quiet", "-m", message])
+            }
+            Vcs::Fossil => {
+                self.run(&["addremove"]);
+                self.run(&["commit", "--no-warnings", "-m", message])
+            }
+        };
+        self
+    }
+
+    /// The lines of the short status of the working copy (`fossil changes`
+    /// for Fossil, with the whitespace between the columns collapsed).
+    pub fn status(&self) -> Vec<String> {
+        match self.vcs {
+            Vcs::Git => self.run(&["status", "--short"]),
+            Vcs::Mercurial => self.run(&["status"]),
+            Vcs::Breezy => self.run(&["status", "--short"]),
+            Vcs::Subversion => self.run(&["status"]),
+            Vcs::Fossil => self.run(&["changes"]),
+        }
+        .lines()
+        .map(|line| match self.vcs {
+            Vcs::Fossil => line.split_whitespace().collect::<Vec<_>>().join(" "),
+            _ => line.trim_end().to_string(),
+        })
+        .collect()
+    }
+
+    /// Runs scud in the working copy.
+    pub fn scud(&self, args: &[&str]) -> Output {
+        self.scud_in(&self.dir, args)
+    }
+
+    /// Runs scud in the given directory.
+    pub fn scud_in(&self, dir: &Path, args: &[&str]) -> Output {
+        self.isolated(Command::new(env!("CARGO_BIN_EXE_scud")), dir)
+            .args(args)
+            .env("NO_COLOR", "1")
+            .output()
+            .unwrap()
+    }
+
+    /// Keeps the configuration of the user (e.g. aliases, extensions or
+    /// global ignores) out of the tests.
+    pub fn isolated(&self, mut command: Command, dir: &Path) -> Command {
+        command
+            .current_dir(dir)
+            .env("HOME", &self.home)
+            .env("SCUD_NO_UPDATE_CHECK", "1")
+            .env_remove("SCUD_HOOKS_DIR");
+
+        match self.vcs {
+            Vcs::Git => command
+                .env("GIT_CONFIG_GLOBAL", "/dev/null")
+                .env("GIT_CONFIG_NOSYSTEM", "1")
+                .env("GIT_AUTHOR_NAME", "scud")
+                .env("GIT_AUTHOR_EMAIL", "scud@example.com")
+                .env("GIT_COMMITTER_NAME", "scud")
+                .env("GIT_COMMITTER_EMAIL", "scud@example.com"),
+            Vcs::Mercurial => command
+                .env("HGRCPATH", "")
+                .env("HGUSER", "scud <scud@example.com>"),
+            Vcs::Breezy => command
+                .env("BRZ_HOME", &self.home)
+                .env("BZR_HOME", &self.home)
+                .env("BRZ_EMAIL", "scud <scud@example.com>")
+                .env("BZR_EMAIL", "scud <scud@example.com>"),
+            Vcs::Subversion => &mut command,
+            Vcs::Fossil => command
+                .env("FOSSIL_HOME", &self.home)
+                .env("FOSSIL_USER", "scud"),
+        };
+
+        command
+    }
+}
+
+impl Drop for Repo {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.base);
+    }
+}
+
+pub fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+pub fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).to_string()
+}
@@ -0,0 +1,74 @@
+//! Integration tests for the global `-C`/`--directory` option, run against
+//! throwaway git repositories from a directory outside of them. They're
+//! skipped when git isn't installed.
+
+mod common;
+
+use std::{fs, process::Output};
+
+use common::{stderr, stdout, Repo};
+
+/// Runs scud from a directory next to the repository, which isn't inside any
+/// repository.
+fn scud_elsewhere(repo: &Repo, args: &[&str]) -> Output {
+    let elsewhere = repo.dir.with_file_name("elsewhere");
+    fs::create_dir_all(&elsewhere).unwrap();
+    repo.scud_in(&elsewhere, args)
+}
+
+#[test]
+fn state_reads_the_given_repository() {
+    let Some(repo) = Repo::git("state") else {
+        return;
+    };
+    repo.write("new.txt", "new\n");
+
+    let output = scud_elsewhere(&repo, &["-C", repo.dir.to_str().unwrap(), "state"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("Git"));
+    assert!(stdout.contains("?? ") && stdout.contains("new.txt"));
+}
+
+#[test]
+fn stage_runs_git_in_the_given_repository() {
+    let Some(repo) = Repo::git("stage") else {
+        return;
+    };
+    repo.write("src/new.txt", "new\n");
+
+    // The option is global, so it's accepted after the subcommand too
+    let output = scud_elsewhere(&repo, &["stage", "--directory", repo.dir.to_str().unwrap()]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        repo.run(&["diff", "--cached", "--name-only"]),
+        "src/new.txt\n"
+    );
+}
+
+#[test]
+fn repository_healthcheck_diagnoses_the_given_repository() {
+    let Some(repo) = Repo::git("healthcheck") else {
+        return;
+    };
+
+    let output =
+        scud_elsewhere(&repo, &["-C", repo.dir.to_str().unwrap(), "healthcheck", "--repo"]);
+
+    assert!(stdout(&output).contains("REPOSITORY HEALTHCHECK"));
+}
+
+#[test]
+fn a_missing_directory_fails() {
+    let Some(repo) = Repo::git("missing") else {
+        return;
+    };
+    let missing = repo.dir.with_file_name("missing");
+
+    let output = scud_elsewhere(&repo, &["-C", missing.to_str().unwrap(), "state"]);
+
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("isn't a directory"));
+}
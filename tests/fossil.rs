@@ -0,0 +1,117 @@
+//! Integration tests for the Fossil backend, run against checkouts of
+//! throwaway local repositories (Fossil repositories are single files, so
+//! everything runs offline). They're ignored unless requested since they
+//! need fossil (see the common module).
+
+mod common;
+
+use common::{stderr, stdout, Repo, Vcs};
+
+#[test]
+#[ignore = "needs fossil"]
+fn state_groups_the_changes_of_fossil_changes() {
+    let repo = Repo::new(Vcs::Fossil, "state");
+    repo.commit("tracked.txt", "one\n", "initial")
+        .commit("gone.txt", "gone\n", "second")
+        .write("tracked.txt", "two\n")
+        .write("new.txt", "new\n")
+        .remove("gone.txt");
+
+    let output = repo.scud(&["state"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("Fossil"));
+    assert!(stdout.contains("On branch:  trunk"));
+    assert!(stdout.contains("Modified files staged for commit"));
+    assert!(stdout.contains("tracked.txt"));
+    assert!(stdout.contains("Deleted files not yet staged for commit"));
+    assert!(stdout.contains("gone.txt"));
+    assert!(stdout.contains("?? ") && stdout.contains("new.txt"));
+}
+
+#[test]
+#[ignore = "needs fossil"]
+fn stage_adds_new_files_and_removes_missing_ones() {
+    let repo = Repo::new(Vcs::Fossil, "stage");
+    repo.commit("gone.txt", "gone\n", "initial")
+        .write("src/new.txt", "new\n")
+        .remove("gone.txt");
+
+    // Staging covers the whole checkout, wherever it's run from
+    let output = repo.scud_in(&repo.dir.join("src"), &["stage"]);
+    let changes = repo.status();
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(
+        changes.contains(&"ADDED src/new.txt".to_string()),
+        "{:?}",
+        changes
+    );
+    assert!(
+        changes.contains(&"DELETED gone.txt".to_string()),
+        "{:?}",
+        changes
+    );
+    assert!(stdout(&output).contains("Added files staged for commit"));
+}
+
+#[test]
+#[ignore = "needs fossil"]
+fn unstage_undoes_stage() {
+    let repo = Repo::new(Vcs::Fossil, "unstage");
+    repo.commit("gone.txt", "gone\n", "initial")
+        .write("new.txt", "new\n")
+        .remove("gone.txt");
+    repo.run(&["addremove"]);
+
+    let output = repo.scud(&["unstage"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(repo.status(), vec!["MISSING gone.txt"]);
+    assert!(repo.dir.join("new.txt").exists());
+    assert!(!repo.dir.join("gone.txt").exists());
+}
+
+#[test]
+#[ignore = "needs fossil"]
+fn commit_without_changes_fails() {
+    let repo = Repo::new(Vcs::Fossil, "commit");
+    repo.commit("tracked.txt", "one\n", "initial");
+
+    let output = repo.scud(&["commit"]);
+
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("without any staged changes"));
+}
+
+#[test]
+#[ignore = "needs fossil"]
+fn branch_lists_the_open_branches() {
+    let repo = Repo::new(Vcs::Fossil, "branch");
+    repo.commit("tracked.txt", "one\n", "initial");
+    repo.run(&["branch", "new", "feature", "trunk"]);
+
+    let output = repo.scud(&["branch"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("feature"));
+    assert!(stdout.contains("** trunk <- ACTIVE"));
+    assert!(stdout.contains("Latest commit:"));
+}
+
+#[test]
+#[ignore = "needs fossil"]
+fn diff_shows_uncommitted_changes() {
+    let repo = Repo::new(Vcs::Fossil, "diff");
+    repo.commit("tracked.txt", "one\n", "initial")
+        .write("tracked.txt", "two\n");
+
+    let output = repo.scud(&["diff"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("-one"));
+    assert!(stdout.contains("+two"));
+}
@@ -0,0 +1,99 @@
+//! Integration tests for `scud hooks`, run against throwaway git
+//! repositories. They're skipped when git isn't installed.
+
+mod common;
+
+use std::{
+    process::{Command, Stdio},
+    thread,
+    time::Duration,
+};
+
+use common::{stderr, stdout, Repo};
+
+#[test]
+fn hooks_installed_from_a_directory_block_commits() {
+    let Some(repo) = Repo::git("install-dir") else {
+        return;
+    };
+    repo.write(
+        "ci/hooks/pre-commit",
+        "#!/bin/sh\necho blocked by ci\nexit 1\n",
+    );
+
+    let output = repo.scud(&["hooks", "install", "--dir", "ci/hooks"]);
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(
+        repo.run(&["config", "--get", "scud.hooksDir"]),
+        "ci/hooks\n"
+    );
+
+    repo.write("new.txt", "new\n");
+    repo.run(&["add", "new.txt"]);
+    let head = repo.run(&["rev-parse", "HEAD"]);
+
+    let output = repo.scud(&["commit"]);
+    let stdout = stdout(&output);
+
+    assert!(!output.status.success(), "{}", stderr(&output));
+    assert!(
+        stdout.contains("The pre-commit hook pre-commit failed"),
+        "{}",
+        stdout
+    );
+    assert!(stdout.contains("blocked by ci"));
+    assert_eq!(repo.run(&["rev-parse", "HEAD"]), head);
+}
+
+#[test]
+fn uninstall_forgets_the_directory() {
+    let Some(repo) = Repo::git("uninstall") else {
+        return;
+    };
+    repo.write("ci/hooks/pre-commit", "#!/bin/sh\nexit 0\n");
+
+    let output = repo.scud(&["hooks", "install", "--dir", "ci/hooks"]);
+    assert!(output.status.success(), "{}", stdout(&output));
+
+    let output = repo.scud(&["hooks", "uninstall"]);
+    assert!(output.status.success(), "{}", stdout(&output));
+
+    assert!(!repo.dir.join(".git/hooks/pre-commit").exists());
+    assert!(repo.isolated(Command::new("git"), &repo.dir)
+        .args(["config", "--get", "scud.hooksDir"])
+        .output()
+        .map_or(false, |output| output.stdout.is_empty()));
+}
+
+#[test]
+fn scripts_a_repository_ships_only_run_once_installed() {
+    let Some(repo) = Repo::git("not-installed") else {
+        return;
+    };
+    repo.write(
+        "tools/hooks/pre-commit/planted.hook",
+        "#!/bin/sh\ntouch ran\nexit 1\n",
+    );
+    repo.write("new.txt", "new\n");
+    repo.run(&["add", "new.txt"]);
+
+    // Without a terminal the commit wizard (which comes after the hooks)
+    // waits forever, so scud is stopped once the hooks would have run
+    let mut scud = repo.isolated(Command::new(env!("CARGO_BIN_EXE_scud")), &repo.dir)
+        .arg("commit")
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    for _ in 0..30 {
+        if scud.try_wait().unwrap().is_some() {
+            break;
+        }
+        thread::sleep(Duration::from_millis(100));
+    }
+    let _ = scud.kill();
+    let _ = scud.wait();
+
+    assert!(!repo.dir.join("ran").exists());
+}
@@ -0,0 +1,147 @@
+//! Integration tests for the Mercurial backend, run against throwaway local
+//! repositories. They're ignored unless requested since they need hg (see
+//! the common module).
+
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, Repo, Vcs};
+
+#[test]
+#[ignore = "needs hg"]
+fn state_groups_the_changes_of_hg_status() {
+    let repo = Repo::new(Vcs::Mercurial, "state");
+    repo.commit("tracked.txt", "one\n", "initial")
+        .commit("gone.txt", "gone\n", "second")
+        .write("tracked.txt", "two\n")
+        .write("new.txt", "new\n")
+        .remove("gone.txt");
+
+    let output = repo.scud(&["state"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("Mercurial"));
+    assert!(stdout.contains("On branch:  default"));
+    assert!(stdout.contains("Modified files staged for commit"));
+    assert!(stdout.contains("tracked.txt"));
+    assert!(stdout.contains("Deleted files not yet staged for commit"));
+    assert!(stdout.contains("gone.txt"));
+    assert!(stdout.contains("?? ") && stdout.contains("new.txt"));
+}
+
+#[test]
+#[ignore = "needs hg"]
+fn stage_adds_new_files_and_removes_deleted_ones() {
+    let repo = Repo::new(Vcs::Mercurial, "stage");
+    repo.commit("gone.txt", "gone\n", "initial")
+        .write("src/new.txt", "new\n")
+        .remove("gone.txt");
+
+    // Staging covers the whole repository, wherever it's run from
+    let output = repo.scud_in(&repo.dir.join("src"), &["stage"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(repo.status(), vec!["A src/new.txt", "R gone.txt"]);
+    assert!(stdout(&output).contains("Added files staged for commit"));
+}
+
+#[test]
+#[ignore = "needs hg"]
+fn unstage_undoes_stage() {
+    let repo = Repo::new(Vcs::Mercurial, "unstage");
+    repo.commit("gone.txt", "gone\n", "initial")
+        .write("new.txt", "new\n")
+        .remove("gone.txt");
+    repo.run(&["addremove"]);
+
+    let output = repo.scud(&["unstage"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(repo.status(), vec!["! gone.txt", "? new.txt"]);
+    assert!(!repo.dir.join("gone.txt").exists());
+}
+
+#[test]
+#[ignore = "needs hg"]
+fn unstage_keeps_forgotten_files() {
+    let repo = Repo::new(Vcs::Mercurial, "unstage-forgotten");
+    repo.commit("kept.txt", "one\n", "initial")
+        .write("kept.txt", "edited\n");
+    repo.run(&["forget", "kept.txt"]);
+
+    let output = repo.scud(&["unstage"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        fs::read_to_string(repo.dir.join("kept.txt")).unwrap(),
+        "edited\n"
+    );
+    assert_eq!(repo.status(), vec!["M kept.txt"]);
+}
+
+#[test]
+#[ignore = "needs hg"]
+fn commit_without_changes_fails() {
+    let repo = Repo::new(Vcs::Mercurial, "commit");
+    repo.commit("tracked.txt", "one\n", "initial");
+
+    let output = repo.scud(&["commit"]);
+
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("without any staged changes"));
+}
+
+#[test]
+#[ignore = "needs hg"]
+fn branch_lists_branches_and_bookmarks() {
+    let repo = Repo::new(Vcs::Mercurial, "branch");
+    repo.commit("tracked.txt", "one\n", "initial");
+    repo.run(&["bookmark", "feature"]);
+    repo.run(&["branch", "stable"]);
+    repo.commit("tracked.txt", "two\n", "on stable");
+
+    let output = repo.scud(&["branch"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("BRANCHES"));
+    assert!(stdout.contains("default"));
+    assert!(stdout.contains("** stable <- ACTIVE"));
+    assert!(stdout.contains("BOOKMARKS"));
+    assert!(stdout.contains("** feature <- ACTIVE"));
+}
+
+#[test]
+#[ignore = "needs hg"]
+fn diff_shows_uncommitted_changes() {
+    let repo = Repo::new(Vcs::Mercurial, "diff");
+    repo.commit("tracked.txt", "one\n", "initial")
+        .write("tracked.txt", "two\n");
+
+    let output = repo.scud(&["diff"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("-one"));
+    assert!(stdout.contains("+two"));
+}
+
+#[test]
+#[ignore = "needs hg"]
+fn pull_updates_to_the_pulled_changesets() {
+    let repo = Repo::new(Vcs::Mercurial, "pull");
+    repo.commit("tracked.txt", "one\n", "initial");
+    let other = repo.dir.with_file_name("other");
+    repo.run_in(other.parent().unwrap(), &["clone", "--quiet", "work", "other"]);
+    repo.commit("tracked.txt", "two\n", "change");
+
+    let output = repo.scud_in(&other, &["pull"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        fs::read_to_string(other.join("tracked.txt")).unwrap(),
+        "two\n"
+    );
+}
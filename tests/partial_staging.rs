@@ -0,0 +1,107 @@
+//! Integration tests for staging and unstaging only some of the changes of
+//! a throwaway git repository. The pickers of --interactive and --patch need
+//! a terminal, so only how they fail without one is covered. They're skipped
+//! when git isn't installed.
+
+mod common;
+
+use common::{stderr, stdout, Repo};
+
+/// Creates a repository with a commit of the given files, or None when git
+/// isn't installed.
+fn repository(test_name: &str, files: &[&str]) -> Option<Repo> {
+    let repo = Repo::git(test_name)?;
+    for file in files {
+        repo.write(file, "initial\n");
+    }
+    repo.run(&["add", "-A"]);
+    repo.run(&["commit", "--quiet", "--allow-empty", "-m", "add files"]);
+
+    Some(repo)
+}
+
+/// The paths of the staged changes.
+fn staged(repo: &Repo) -> Vec<String> {
+    repo.run(&["diff", "--cached", "--name-only"])
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn stage_only_stages_the_given_paths() {
+    let Some(repo) = repository("stage", &["src/main.rs", "notes.txt"]) else {
+        return;
+    };
+    repo.write("src/main.rs", "changed\n")
+        .write("src/new.rs", "new\n")
+        .write("notes.txt", "changed\n");
+
+    // Paths are relative to where scud runs, like with git
+    let output = repo.scud_in(&repo.dir.join("src"), &["stage", "main.rs", "new.rs"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(staged(&repo), ["src/main.rs", "src/new.rs"]);
+}
+
+#[test]
+fn unstage_only_unstages_the_given_paths() {
+    let Some(repo) = repository("unstage", &["a.txt", "b.txt"]) else {
+        return;
+    };
+    repo.write("a.txt", "changed\n").write("b.txt", "changed\n");
+    repo.run(&["add", "-A"]);
+
+    let output = repo.scud(&["unstage", "a.txt"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(staged(&repo), ["b.txt"]);
+}
+
+#[test]
+fn unstage_without_paths_unstages_everything() {
+    let Some(repo) = repository("unstage-all", &["a.txt"]) else {
+        return;
+    };
+    repo.write("a.txt", "changed\n").write("b.txt", "new\n");
+    repo.run(&["add", "-A"]);
+
+    let output = repo.scud(&["unstage"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(staged(&repo).is_empty());
+}
+
+#[test]
+fn pickers_fail_without_a_terminal() {
+    let Some(repo) = repository("no-terminal", &["a.txt"]) else {
+        return;
+    };
+    repo.write("a.txt", "changed\n");
+
+    for args in [["stage", "--interactive"], ["unstage", "--patch"]] {
+        let output = repo.scud(&args);
+
+        assert!(!output.status.success());
+        assert!(stdout(&output).contains("without a terminal"));
+    }
+    assert!(staged(&repo).is_empty());
+}
+
+#[test]
+fn undo_restores_what_was_staged_before() {
+    let Some(repo) = repository("undo", &["a.txt", "b.txt"]) else {
+        return;
+    };
+    repo.write("a.txt", "changed\n").write("b.txt", "changed\n");
+    repo.run(&["add", "a.txt"]);
+
+    let output = repo.scud(&["stage", "b.txt"]);
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(staged(&repo), ["a.txt", "b.txt"]);
+
+    let output = repo.scud(&["undo"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(staged(&repo), ["a.txt"]);
+}
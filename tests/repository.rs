@@ -0,0 +1,124 @@
+//! Tests for finding the repository a directory belongs to. Detection only
+//! looks at the metadata directories of each VCS, so the repositories here
+//! are faked by creating those rather than running the VCSs themselves.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use scud_core::{cli::cli::VCS, Repository, ScudError};
+
+/// A throwaway directory to lay out fake repositories in.
+struct TempDir {
+    dir: PathBuf,
+}
+
+impl TempDir {
+    fn new(test_name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "scud-repository-{}-{}",
+            test_name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        // Canonicalized since discovery returns canonical paths (e.g. /tmp
+        // is a symlink on macOS)
+        TempDir {
+            dir: fs::canonicalize(dir).unwrap(),
+        }
+    }
+
+    fn mkdir(&self, path: &str) -> PathBuf {
+        let path = self.dir.join(path);
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    fn write(&self, path: &str, content: &str) -> PathBuf {
+        let path = self.dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, content).unwrap();
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn discover(path: &Path) -> Repository {
+    Repository::discover(path).unwrap()
+}
+
+#[test]
+fn discover_walks_up_to_the_root() {
+    let temp = TempDir::new("walk");
+    temp.mkdir("project/.hg");
+    let nested = temp.mkdir("project/src/module");
+
+    let repository = discover(&nested);
+
+    assert_eq!(repository.vcs, VCS::Mercurial);
+    assert_eq!(repository.root, temp.dir.join("project"));
+    assert_eq!(repository.git_dir, None);
+    assert_eq!(repository.enclosing, None);
+}
+
+#[test]
+fn discover_reports_the_repository_a_nested_one_lies_within() {
+    let temp = TempDir::new("nested");
+    temp.mkdir("project/.hg");
+    let git_dir = temp.mkdir("project/vendor/library/.git");
+
+    let repository = discover(&temp.dir.join("project/vendor/library"));
+
+    assert_eq!(repository.vcs, VCS::Git);
+    assert_eq!(repository.root, temp.dir.join("project/vendor/library"));
+    assert_eq!(repository.git_dir, Some(git_dir));
+
+    let enclosing = repository.enclosing.expect("no enclosing repository");
+    assert_eq!(enclosing.vcs, VCS::Mercurial);
+    assert_eq!(enclosing.root, temp.dir.join("project"));
+}
+
+#[test]
+fn discover_follows_git_files_of_worktrees() {
+    let temp = TempDir::new("worktree");
+    let worktree_git_dir = temp.mkdir("main/.git/worktrees/feature");
+    temp.write("feature/.git", "gitdir: ../main/.git/worktrees/feature\n");
+
+    let repository = discover(&temp.dir.join("feature"));
+
+    assert_eq!(repository.vcs, VCS::Git);
+    assert_eq!(repository.root, temp.dir.join("feature"));
+    assert_eq!(repository.git_dir, Some(worktree_git_dir));
+}
+
+#[test]
+fn discover_prefers_jujutsu_over_a_colocated_git_repository() {
+    let temp = TempDir::new("colocated");
+    temp.mkdir("project/.jj");
+    let git_dir = temp.mkdir("project/.git");
+
+    let repository = discover(&temp.dir.join("project"));
+
+    assert_eq!(repository.vcs, VCS::Jujutsu);
+    assert_eq!(repository.git_dir, Some(git_dir));
+}
+
+#[test]
+fn discover_fails_outside_of_a_repository() {
+    let temp = TempDir::new("none");
+
+    // The temporary directory itself could be inside a repository, so this
+    // only checks that discovery returns rather than exiting
+    match Repository::discover(&temp.dir) {
+        Ok(repository) => assert!(!repository.root.starts_with(&temp.dir)),
+        Err(error) => assert!(matches!(error, ScudError::VcsNotFound)),
+    }
+}
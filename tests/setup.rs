@@ -0,0 +1,314 @@
+//! Integration tests for `scud setup`, run against fake package managers:
+//! shell scripts on an otherwise empty `PATH` which record the arguments they
+//! were called with instead of installing anything. `scud setup identity` is
+//! run against a throwaway home directory instead.
+#![cfg(unix)]
+
+use std::{
+    fs,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+/// A directory holding the fake package managers, used as the only entry of
+/// `PATH`.
+struct Shims {
+    dir: PathBuf,
+}
+
+impl Shims {
+    fn new(test_name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "scud-setup-{}-{}",
+            test_name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Shims { dir }
+    }
+
+    /// Adds a fake package manager exiting with the given status.
+    fn add(&self, program: &str, exit_status: u8) -> &Self {
+        let shim = self.dir.join(program);
+        fs::write(
+            &shim,
+            format!(
+                "#!/bin/sh\nprintf '%s\\n' \"$*\" >> \"{}\"\nexit {}\n",
+                self.log().display(),
+                exit_status
+            ),
+        )
+        .unwrap();
+        fs::set_permissions(&shim, fs::Permissions::from_mode(0o755)).unwrap();
+        self
+    }
+
+    fn log(&self) -> PathBuf {
+        self.dir.join("calls.log")
+    }
+
+    /// The arguments each fake package manager was called with.
+    fn calls(&self) -> Vec<String> {
+        fs::read_to_string(self.log())
+            .map(|log| log.lines().map(str::to_string).collect())
+            .unwrap_or_default()
+    }
+
+    fn scud(&self, args: &[&str]) -> Output {
+        scud_with_path(&self.dir, args)
+    }
+}
+
+impl Drop for Shims {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
+
+fn scud_with_path(path: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_scud"))
+        .args(args)
+        .env("PATH", path)
+        .env("NO_COLOR", "1")
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).to_string()
+}
+
+#[test]
+fn dry_run_shows_install_command_without_running_it() {
+    let shims = Shims::new("dry-run");
+    shims.add("apt-get", 0);
+
+    let output = shims.scud(&["setup", "vcs", "mercurial", "--dry-run"]);
+
+    assert!(output.status.success());
+    assert!(stdout(&output).contains("apt-get install -y mercurial"));
+    assert!(shims.calls().is_empty());
+}
+
+#[test]
+fn installs_with_detected_package_manager() {
+    let shims = Shims::new("install");
+    shims.add("pacman", 0);
+
+    let output = shims.scud(&["setup", "sc", "github"]);
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(shims.calls(), vec!["-S --noconfirm github-cli"]);
+    // The healthcheck is re-run after installing
+    assert!(stdout(&output).contains("HEALTHCHECK:"));
+}
+
+#[test]
+fn prefers_system_package_manager() {
+    let shims = Shims::new("preference");
+    shims.add("nix-env", 0).add("dnf", 0).add("brew", 0);
+
+    let output = shims.scud(&["setup", "vcs", "breezy"]);
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    if cfg!(target_os = "macos") {
+        assert_eq!(shims.calls(), vec!["install breezy"]);
+    } else {
+        assert_eq!(shims.calls(), vec!["install -y breezy"]);
+    }
+}
+
+#[test]
+fn reports_failed_install() {
+    let shims = Shims::new("failure");
+    shims.add("brew", 1);
+
+    let output = shims.scud(&["setup", "tool", "onefetch"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("Failed to install onefetch"));
+    assert_eq!(shims.calls(), vec!["install onefetch"]);
+}
+
+#[test]
+fn reports_tool_not_packaged_for_package_manager() {
+    let shims = Shims::new("unpackaged");
+    shims.add("apt-get", 0);
+
+    let output = shims.scud(&["setup", "tool", "onefetch"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("onefetch isn't packaged for apt"));
+    assert!(shims.calls().is_empty());
+}
+
+#[test]
+fn reports_missing_package_manager() {
+    let shims = Shims::new("no-package-manager");
+
+    let output = shims.scud(&["setup", "vcs", "git"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("Could not detect a supported package manager"));
+}
+
+#[test]
+fn info_lists_install_commands() {
+    let shims = Shims::new("info");
+    shims.add("nix-env", 0);
+
+    let output = shims.scud(&["setup", "--info"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success());
+    assert!(stdout.contains("nix-env -iA nixpkgs.git"));
+    assert!(stdout.contains("nix-env -iA nixpkgs.onefetch"));
+    assert!(shims.calls().is_empty());
+}
+
+/// Runs `scud setup identity` from the given directory, with a home directory
+/// (and so a ~/.gitconfig) of its own under base, or returns None when git
+/// isn't installed.
+fn setup_identity(base: &Path, dir: &Path, args: &[&str]) -> Option<Output> {
+    let installed = Command::new("git")
+        .arg("--version")
+        .output()
+        .map_or(false, |output| output.status.success());
+    if !installed {
+        eprintln!("skipping setup identity: git isn't installed");
+        return None;
+    }
+
+    fs::create_dir_all(base.join("home")).unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_scud"))
+        .current_dir(dir)
+        .args(["setup", "identity"])
+        .args(args)
+        .env("HOME", base.join("home"))
+        .env_remove("GIT_CONFIG_GLOBAL")
+        .env("GIT_CONFIG_NOSYSTEM", "1")
+        .env("NO_COLOR", "1")
+        .env("SCUD_NO_UPDATE_CHECK", "1")
+        .output()
+        .unwrap();
+
+    Some(output)
+}
+
+fn identity_base(test_name: &str) -> PathBuf {
+    let base = std::env::temp_dir().join(format!(
+        "scud-setup-identity-{}-{}",
+        test_name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&base);
+    fs::create_dir_all(&base).unwrap();
+    base
+}
+
+#[test]
+fn identity_directory_is_relative_to_the_directory_option() {
+    let base = identity_base("relative");
+    fs::create_dir_all(base.join("other").join("work")).unwrap();
+
+    let Some(output) = setup_identity(
+        &base,
+        &base,
+        &[
+            "-C",
+            "other",
+            "--dir",
+            "./work",
+            "--name",
+            "scud",
+            "--email",
+            "scud@example.com",
+        ],
+    ) else {
+        return;
+    };
+    let gitconfig =
+        fs::read_to_string(base.join("home").join(".gitconfig")).unwrap_or_default();
+    let directory = base.join("other").join("work").canonicalize().unwrap();
+    let _ = fs::remove_dir_all(&base);
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert!(
+        gitconfig.contains(&format!("gitdir:{}/", directory.display())),
+        "{}",
+        gitconfig
+    );
+}
+
+#[test]
+fn identity_directories_sharing_a_name_get_their_own_file() {
+    let base = identity_base("same-name");
+    let home = base.join("home");
+    for dir in ["work/src", "oss/src"] {
+        fs::create_dir_all(home.join(dir)).unwrap();
+    }
+
+    let Some(output) = setup_identity(
+        &base,
+        &home,
+        &["--dir", "~/work/src", "--email", "me@work.example.com"],
+    ) else {
+        return;
+    };
+    assert!(output.status.success(), "{}", stdout(&output));
+    let output = setup_identity(
+        &base,
+        &home,
+        &["--dir", "~/oss/src", "--email", "me@example.com"],
+    )
+    .unwrap();
+    assert!(output.status.success(), "{}", stdout(&output));
+
+    let work =
+        fs::read_to_string(home.join(".gitconfig-work-src")).unwrap_or_default();
+    let oss =
+        fs::read_to_string(home.join(".gitconfig-oss-src")).unwrap_or_default();
+    let _ = fs::remove_dir_all(&base);
+
+    assert!(work.contains("me@work.example.com"), "{}", work);
+    assert!(
+        oss.contains("me@example.com") && !oss.contains("work"),
+        "{}",
+        oss
+    );
+}
+
+#[test]
+fn identity_directories_with_spaces_are_listed() {
+    let base = identity_base("spaces");
+    let home = base.join("home");
+    fs::create_dir_all(home.join("my projects")).unwrap();
+
+    let Some(output) = setup_identity(
+        &base,
+        &home,
+        &["--dir", "~/my projects", "--email", "me@example.com"],
+    ) else {
+        return;
+    };
+    assert!(output.status.success(), "{}", stdout(&output));
+    // Every run starts by listing the current identities
+    let output =
+        setup_identity(&base, &home, &["--dir", "~", "--name", "scud"]).unwrap();
+    let _ = fs::remove_dir_all(&base);
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    let listed = stdout(&output)
+        .lines()
+        .find(|line| line.contains("my projects/:"))
+        .map(str::to_string);
+    assert!(
+        listed
+            .as_deref()
+            .map_or(false, |line| line.contains("me@example.com")),
+        "{}",
+        stdout(&output)
+    );
+}
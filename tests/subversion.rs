@@ -0,0 +1,140 @@
+//! Integration tests for the Subversion backend, run against working copies
+//! of throwaway local repositories (`svnadmin create`, checked out over
+//! file://). They're ignored unless requested since they need svn and
+//! svnadmin (see the common module).
+
+mod common;
+
+use std::fs;
+
+use common::{stderr, stdout, Repo, Vcs};
+
+#[test]
+#[ignore = "needs svn"]
+fn state_groups_the_changes_of_svn_status() {
+    let repo = Repo::new(Vcs::Subversion, "state");
+    repo.commit("tracked.txt", "one\n", "initial")
+        .commit("gone.txt", "gone\n", "second")
+        .write("tracked.txt", "two\n")
+        .write("new.txt", "new\n")
+        .remove("gone.txt");
+
+    let output = repo.scud(&["state"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("Subversion"));
+    assert!(stdout.contains("Modified files staged for commit"));
+    assert!(stdout.contains("tracked.txt"));
+    assert!(stdout.contains("Deleted files not yet staged for commit"));
+    assert!(stdout.contains("gone.txt"));
+    assert!(stdout.contains("?? ") && stdout.contains("new.txt"));
+}
+
+#[test]
+#[ignore = "needs svn"]
+fn stage_adds_new_files_and_deletes_missing_ones() {
+    let repo = Repo::new(Vcs::Subversion, "stage");
+    repo.commit("gone.txt", "gone\n", "initial")
+        .write("src/new.txt", "new\n")
+        .remove("gone.txt");
+
+    // Staging covers the whole working copy, wherever it's run from
+    let output = repo.scud_in(&repo.dir.join("src"), &["stage"]);
+    let status = repo.status();
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(status.contains(&"A       src".to_string()), "{:?}", status);
+    assert!(
+        status.contains(&"A       src/new.txt".to_string()),
+        "{:?}",
+        status
+    );
+    assert!(
+        status.contains(&"D       gone.txt".to_string()),
+        "{:?}",
+        status
+    );
+    assert!(stdout(&output).contains("Added files staged for commit"));
+}
+
+#[test]
+#[ignore = "needs svn"]
+fn unstage_undoes_stage() {
+    let repo = Repo::new(Vcs::Subversion, "unstage");
+    repo.commit("gone.txt", "gone\n", "initial")
+        .write("src/new.txt", "new\n")
+        .remove("gone.txt");
+    repo.run(&["add", "--quiet", "src"]);
+    repo.run(&["delete", "--quiet", "gone.txt"]);
+
+    let output = repo.scud(&["unstage"]);
+    let status = repo.status();
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(status, vec!["!       gone.txt", "?       src"]);
+    assert!(repo.dir.join("src/new.txt").exists());
+    assert!(!repo.dir.join("gone.txt").exists());
+}
+
+#[test]
+#[ignore = "needs svn"]
+fn unstage_keeps_files_deleted_with_keep_local() {
+    let repo = Repo::new(Vcs::Subversion, "unstage-kept");
+    repo.commit("kept.txt", "one\n", "initial")
+        .write("kept.txt", "edited\n");
+    repo.run(&["delete", "--quiet", "--keep-local", "kept.txt"]);
+
+    let output = repo.scud(&["unstage"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        fs::read_to_string(repo.dir.join("kept.txt")).unwrap(),
+        "edited\n"
+    );
+    assert_eq!(repo.status(), vec!["M       kept.txt"]);
+}
+
+#[test]
+#[ignore = "needs svn"]
+fn commit_without_changes_fails() {
+    let repo = Repo::new(Vcs::Subversion, "commit");
+    repo.commit("tracked.txt", "one\n", "initial");
+
+    let output = repo.scud(&["commit"]);
+
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("without any staged changes"));
+}
+
+#[test]
+#[ignore = "needs svn"]
+fn diff_shows_uncommitted_changes() {
+    let repo = Repo::new(Vcs::Subversion, "diff");
+    repo.commit("tracked.txt", "one\n", "initial")
+        .write("tracked.txt", "two\n");
+
+    let output = repo.scud(&["diff"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert!(stdout.contains("-one"));
+    assert!(stdout.contains("+two"));
+}
+
+#[test]
+#[ignore = "needs svn"]
+fn pull_updates_the_working_copy() {
+    let repo = Repo::new(Vcs::Subversion, "pull");
+    let other = repo.dir.with_file_name("other");
+    repo.checkout(&other);
+    repo.commit("tracked.txt", "one\n", "initial");
+
+    let output = repo.scud_in(&other, &["pull"]);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    assert_eq!(
+        fs::read_to_string(other.join("tracked.txt")).unwrap(),
+        "one\n"
+    );
+}
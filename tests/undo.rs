@@ -0,0 +1,122 @@
+//! Integration tests for `scud undo`, run against throwaway git repositories.
+//! They're skipped when git isn't installed.
+
+mod common;
+
+use std::fs;
+
+use common::{stdout, Repo};
+
+/// Creates a repository with a commit of a.txt, or None when git isn't
+/// installed.
+fn repository(test_name: &str) -> Option<Repo> {
+    let repo = Repo::git(test_name)?;
+    repo.commit("a.txt", "initial\n", "add a");
+    Some(repo)
+}
+
+/// Adds the given line to the journal scud keeps in the repository.
+fn record(repo: &Repo, line: &str) {
+    let journal = repo.dir.join(".git").join("scud").join("journal");
+    fs::create_dir_all(journal.parent().unwrap()).unwrap();
+    let mut content = fs::read_to_string(&journal).unwrap_or_default();
+    content.push_str(line);
+    content.push('\n');
+    fs::write(journal, content).unwrap();
+}
+
+/// The paths of the staged changes.
+fn staged(repo: &Repo) -> Vec<String> {
+    repo.run(&["diff", "--cached", "--name-only"])
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn undo_unstages_what_scud_staged() {
+    let Some(repo) = repository("stage") else {
+        return;
+    };
+    repo.write("a.txt", "changed\n").write("b.txt", "new\n");
+
+    let output = repo.scud(&["stage"]);
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(staged(&repo), ["a.txt", "b.txt"]);
+
+    let output = repo.scud(&["undo"]);
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert!(staged(&repo).is_empty());
+    // The changes themselves are kept
+    assert_eq!(
+        fs::read_to_string(repo.dir.join("a.txt")).unwrap(),
+        "changed\n"
+    );
+}
+
+#[test]
+fn undo_uncommits_keeping_the_changes_staged() {
+    let Some(repo) = repository("commit") else {
+        return;
+    };
+    // The commit wizard needs a terminal, so record what `scud commit` would
+    // have before committing with git
+    let head = repo.run(&["rev-parse", "HEAD"]);
+    let tree = repo.run(&["write-tree"]);
+    record(&repo, &format!("commit\t{}\t{}\tmain\t-\t-\t0", head.trim(), tree.trim()));
+    repo.write("a.txt", "changed\n");
+    repo.run(&["commit", "--quiet", "-am", "change a"]);
+
+    let output = repo.scud(&["undo"]);
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(repo.run(&["rev-parse", "HEAD"]), head);
+    assert_eq!(staged(&repo), ["a.txt"]);
+
+    // The journal entry is used up
+    let output = repo.scud(&["undo"]);
+    assert!(!output.status.success());
+    assert!(stdout(&output).contains("Nothing to undo"));
+}
+
+#[test]
+fn undo_skips_corrupt_journal_lines() {
+    let Some(repo) = repository("corrupt") else {
+        return;
+    };
+    repo.write("a.txt", "changed\n");
+    let output = repo.scud(&["stage"]);
+    assert!(output.status.success(), "{}", stdout(&output));
+    record(&repo, "commit\tdeadbeef");
+    record(&repo, "rebase\t-\t-\t-\t-\t-\t0");
+
+    let output = repo.scud(&["undo"]);
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert!(staged(&repo).is_empty());
+}
+
+#[test]
+fn undo_resets_pushes_to_remotes_with_slashes_in_their_name() {
+    let Some(repo) = repository("push") else {
+        return;
+    };
+    let remote = repo.dir.with_file_name("remote.git");
+    repo.run(&["init", "--quiet", "--bare", remote.to_str().unwrap()]);
+    repo.run(&["remote", "add", "team/fork", remote.to_str().unwrap()]);
+    repo.run(&["push", "--quiet", "-u", "team/fork", "main"]);
+    let pushed = repo.run(&["rev-parse", "HEAD"]);
+    repo.write("a.txt", "changed\n");
+    repo.run(&["commit", "--quiet", "-am", "change a"]);
+
+    let output = repo.scud(&["push"]);
+    assert!(output.status.success(), "{}", stdout(&output));
+    let remote_head = || repo.run_in(&remote, &["rev-parse", "main"]);
+    assert_eq!(remote_head(), repo.run(&["rev-parse", "HEAD"]));
+
+    let output = repo.scud(&["undo", "--force"]);
+
+    assert!(output.status.success(), "{}", stdout(&output));
+    assert_eq!(remote_head(), pushed);
+}
@@ -0,0 +1,121 @@
+//! Integration tests for `scud workspace`, run against throwaway git
+//! repositories. They're skipped when git isn't installed.
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use common::{stderr, stdout, Repo};
+
+/// Creates a repository with a single commit at the given path within the
+/// workspace.
+fn repository(workspace: &Repo, path: &str) -> PathBuf {
+    let repository = workspace.dir.join(path);
+    fs::create_dir_all(&repository).unwrap();
+    workspace.run_in(&repository, &["init", "--quiet"]);
+    workspace.run_in(
+        &repository,
+        &["commit", "--quiet", "--allow-empty", "-m", "initial"],
+    );
+    repository
+}
+
+/// The row of the table for the given repository.
+fn row<'a>(stdout: &'a str, repository: &str) -> &'a str {
+    stdout
+        .lines()
+        .find(|line| line.trim_start().starts_with(&format!("{} ", repository)))
+        .unwrap_or_else(|| panic!("no row for {} in:\n{}", repository, stdout))
+}
+
+#[test]
+fn state_lists_the_repositories_of_the_manifest() {
+    let Some(workspace) = Repo::empty_git("manifest") else {
+        return;
+    };
+    repository(&workspace, "api");
+    repository(&workspace, "web");
+    workspace.write("web/new.txt", "new\n");
+    workspace.write("scud-workspace", "# Services\napi\n\nweb\nmissing\n");
+
+    let output = workspace.scud(&["workspace", "state"]);
+    let stdout = stdout(&output);
+
+    // The missing repository fails without stopping the others
+    assert!(!output.status.success());
+    assert!(row(&stdout, "api").contains("clean"));
+    assert!(row(&stdout, "web").contains("1 untracked"));
+    assert!(row(&stdout, "missing").contains("failed"));
+    assert!(stdout.contains("Failed to read the state of missing"));
+}
+
+#[test]
+fn repositories_are_discovered_under_the_directory() {
+    let Some(workspace) = Repo::empty_git("discover") else {
+        return;
+    };
+    let api = repository(&workspace, "team/api");
+    repository(&workspace, ".cache/ignored");
+    workspace.write("team/notes.txt", "not a repository\n");
+
+    let output = workspace.scud(&["ws", "st"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stderr(&output));
+    let branch = workspace.run_in(&api, &["branch", "--show-current"]);
+    assert!(row(&stdout, "team/api").contains(branch.trim()));
+    assert!(stdout.contains("1 repository"));
+    assert!(!stdout.contains("ignored"));
+}
+
+#[test]
+fn pull_updates_every_repository() {
+    let Some(workspace) = Repo::empty_git("pull") else {
+        return;
+    };
+    // Repositories which aren't part of the workspace go next to it
+    let outside = workspace.dir.with_file_name("outside");
+    let remote = outside.join("remote.git");
+    fs::create_dir_all(&remote).unwrap();
+    workspace.run_in(&remote, &["init", "--quiet", "--bare"]);
+
+    let api = repository(&workspace, "api");
+    workspace.run_in(&api, &["remote", "add", "origin", remote.to_str().unwrap()]);
+    workspace.run_in(&api, &["push", "--quiet", "-u", "origin", "HEAD"]);
+
+    // A clone outside of the workspace pushes a commit the workspace lacks
+    let other = outside.join("other");
+    workspace.run_in(
+        &outside,
+        &["clone", "--quiet", remote.to_str().unwrap(), "other"],
+    );
+    fs::write(other.join("new.txt"), "new\n").unwrap();
+    workspace.run_in(&other, &["add", "new.txt"]);
+    workspace.run_in(&other, &["commit", "--quiet", "-m", "add new.txt"]);
+    workspace.run_in(&other, &["push", "--quiet"]);
+
+    let output = workspace.scud(&["workspace", "pull", "--jobs", "2"]);
+    let stdout = stdout(&output);
+
+    assert!(output.status.success(), "{}", stdout);
+    assert!(row(&stdout, "api").contains("pulled"));
+    assert!(workspace.dir.join("api/new.txt").exists());
+}
+
+#[test]
+fn branch_switches_the_repositories_which_have_it() {
+    let Some(workspace) = Repo::empty_git("branch") else {
+        return;
+    };
+    let api = repository(&workspace, "api");
+    workspace.run_in(&api, &["branch", "feature"]);
+    repository(&workspace, "web");
+
+    let output = workspace.scud(&["workspace", "branch", "feature"]);
+    let stdout = stdout(&output);
+
+    assert!(!output.status.success());
+    assert!(row(&stdout, "api").contains("switched"));
+    assert!(row(&stdout, "web").contains("failed"));
+    assert_eq!(workspace.run_in(&api, &["branch", "--show-current"]), "feature\n");
+}